@@ -600,6 +600,9 @@ macro_rules! declare_arena {
         pub struct Arena<$tcx> {
             pub dropless: $crate::DroplessArena,
             drop: $crate::DropArena,
+            /// The number of bytes taken up by the objects allocated in this arena, kept as a
+            /// running count so that reading it doesn't have to walk all the arenas.
+            allocated_bytes: ::std::cell::Cell<usize>,
             $($name: $crate::arena_for_type!($a[$ty]),)*
         }
 
@@ -661,6 +664,7 @@ macro_rules! declare_arena {
         impl<'tcx> Arena<'tcx> {
             #[inline]
             pub fn alloc<T: ArenaAllocatable<'tcx, U>, U>(&self, value: T) -> &mut T {
+                self.record_allocation(::std::mem::size_of::<T>());
                 value.allocate_on(self)
             }
 
@@ -669,6 +673,7 @@ macro_rules! declare_arena {
                 if value.is_empty() {
                     return &mut [];
                 }
+                self.record_allocation(::std::mem::size_of_val(value));
                 self.dropless.alloc_slice(value)
             }
 
@@ -676,7 +681,27 @@ macro_rules! declare_arena {
                 &'a self,
                 iter: impl ::std::iter::IntoIterator<Item = T>,
             ) -> &'a mut [T] {
-                T::allocate_from_iter(self, iter)
+                let slice = T::allocate_from_iter(self, iter);
+                self.record_allocation(::std::mem::size_of_val(&*slice));
+                slice
+            }
+
+            /// Allocates uninitialized memory with the given layout in the dropless arena.
+            #[inline]
+            pub fn alloc_raw(&self, layout: ::std::alloc::Layout) -> *mut u8 {
+                self.record_allocation(layout.size());
+                self.dropless.alloc_raw(layout)
+            }
+
+            /// Returns the number of bytes taken up by the objects allocated in this arena.
+            #[inline]
+            pub fn allocated_bytes(&self) -> usize {
+                self.allocated_bytes.get()
+            }
+
+            #[inline]
+            fn record_allocation(&self, bytes: usize) {
+                self.allocated_bytes.set(self.allocated_bytes.get() + bytes);
             }
         }
     }
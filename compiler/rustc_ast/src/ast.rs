@@ -996,6 +996,9 @@ pub struct Local {
     pub ty: Option<P<Ty>>,
     /// Initializer expression to set the value, if any.
     pub init: Option<P<Expr>>,
+    /// Diverging block run if the pattern does not match the initializer,
+    /// as in `let <pat> = <expr> else { <block> };`.
+    pub els: Option<P<Block>>,
     pub span: Span,
     pub attrs: AttrVec,
 }
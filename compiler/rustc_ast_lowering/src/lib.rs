@@ -2301,29 +2301,96 @@ impl<'a, 'hir> LoweringContext<'a, 'hir> {
     }
 
     fn lower_block_noalloc(&mut self, b: &Block, targeted_by_break: bool) -> hir::Block<'hir> {
+        let (stmts, expr) = self.lower_stmts(&b.stmts);
+
+        hir::Block {
+            hir_id: self.lower_node_id(b.id),
+            stmts,
+            expr,
+            rules: self.lower_block_check_mode(&b.rules),
+            span: b.span,
+            targeted_by_break,
+        }
+    }
+
+    /// Lowers the statements of a block, returning the lowered statements
+    /// together with the trailing expression, if any.
+    fn lower_stmts(
+        &mut self,
+        mut ast_stmts: &[Stmt],
+    ) -> (&'hir [hir::Stmt<'hir>], Option<&'hir hir::Expr<'hir>>) {
         let mut stmts = vec![];
         let mut expr: Option<&'hir _> = None;
 
-        for (index, stmt) in b.stmts.iter().enumerate() {
-            if index == b.stmts.len() - 1 {
-                if let StmtKind::Expr(ref e) = stmt.kind {
+        while let [stmt, tail @ ..] = ast_stmts {
+            match stmt.kind {
+                StmtKind::Local(ref local) if local.els.is_some() => {
+                    // The rest of the block becomes part of the `let...else`.
+                    expr = Some(self.lower_let_else(stmt, local, tail));
+                    break;
+                }
+                StmtKind::Expr(ref e) if tail.is_empty() => {
                     expr = Some(self.lower_expr(e));
-                } else {
-                    stmts.extend(self.lower_stmt(stmt));
                 }
-            } else {
-                stmts.extend(self.lower_stmt(stmt));
+                _ => stmts.extend(self.lower_stmt(stmt)),
             }
+            ast_stmts = tail;
         }
 
-        hir::Block {
-            hir_id: self.lower_node_id(b.id),
-            stmts: self.arena.alloc_from_iter(stmts),
-            expr,
-            rules: self.lower_block_check_mode(&b.rules),
-            span: b.span,
-            targeted_by_break,
+        (self.arena.alloc_from_iter(stmts), expr)
+    }
+
+    /// Desugar `let PAT: TY = INIT else { ELSE }; REST` into:
+    ///
+    /// ```rust
+    /// match INIT: TY {
+    ///     PAT => { REST }
+    ///     _ => { ELSE }
+    /// }
+    /// ```
+    ///
+    /// `PAT` is matched against `INIT` itself, so that it can move out of or
+    /// borrow parts of `INIT` if it is a place expression, such as `*call()`.
+    /// Type checking ensures that the `else` block diverges. Note that, unlike
+    /// for a plain `let`, the temporaries of `INIT` live until the end of the
+    /// enclosing block, as `INIT` is the scrutinee of its tail expression.
+    fn lower_let_else(
+        &mut self,
+        stmt: &Stmt,
+        local: &Local,
+        rest: &[Stmt],
+    ) -> &'hir hir::Expr<'hir> {
+        let init = local.init.as_ref().expect("`let...else` without an initializer");
+        let els = local.els.as_ref().unwrap();
+
+        let mut scrutinee = self.lower_expr(init);
+        if let Some(ref ty) = local.ty {
+            let ty = self.lower_ty(ty, ImplTraitContext::Disallowed(ImplTraitPosition::Binding));
+            let kind = hir::ExprKind::Type(scrutinee, ty);
+            scrutinee = self.arena.alloc(self.expr(init.span, kind, AttrVec::new()));
         }
+        let pat = self.lower_pat(&local.pat);
+
+        let (stmts, expr) = self.lower_stmts(rest);
+        let rest_span = match (rest.first(), rest.last()) {
+            (Some(first), Some(last)) => first.span.to(last.span),
+            _ => stmt.span.shrink_to_hi(),
+        };
+        let rest_block = self.block_all(rest_span, stmts, expr);
+        let rest_expr = self.arena.alloc(self.expr_block(rest_block, AttrVec::new()));
+        let rest_arm = self.arm(pat, rest_expr);
+
+        let else_expr = self.arena.alloc(self.lower_block_expr(els));
+        let else_pat = self.pat_wild(els.span);
+        let else_arm = self.arm(else_pat, else_expr);
+
+        let arms = self.arena.alloc_from_iter(vec![rest_arm, else_arm]);
+        self.arena.alloc(hir::Expr {
+            hir_id: self.lower_node_id(stmt.id),
+            kind: hir::ExprKind::Match(scrutinee, arms, hir::MatchSource::LetElseDesugar),
+            span: stmt.span,
+            attrs: local.attrs.clone(),
+        })
     }
 
     /// Lowers a block directly to an expression, presuming that it
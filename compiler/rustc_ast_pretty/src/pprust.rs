@@ -1489,6 +1489,12 @@ impl<'a> State<'a> {
                     self.word_space("=");
                     self.print_expr(init);
                 }
+                if let Some(ref els) = loc.els {
+                    self.cbox(INDENT_UNIT - 1);
+                    self.ibox(0);
+                    self.s.word(" else ");
+                    self.print_block(els);
+                }
                 self.s.word(";");
                 self.end();
             }
@@ -2,11 +2,14 @@ use crate::deriving::generic::ty::*;
 use crate::deriving::generic::*;
 
 use rustc_ast::ptr::P;
-use rustc_ast::{Expr, MetaItem};
-use rustc_errors::struct_span_err;
+use rustc_ast::visit::{self, Visitor};
+use rustc_ast::{self as ast, EnumDef, Expr, MetaItem, VariantData};
+use rustc_errors::{struct_span_err, Applicability};
 use rustc_expand::base::{Annotatable, DummyResult, ExtCtxt};
-use rustc_span::symbol::{kw, sym};
+use rustc_session::parse::feature_err;
+use rustc_span::symbol::{kw, sym, Ident};
 use rustc_span::Span;
+use smallvec::SmallVec;
 
 pub fn expand_deriving_default(
     cx: &mut ExtCtxt<'_>,
@@ -15,6 +18,8 @@ pub fn expand_deriving_default(
     item: &Annotatable,
     push: &mut dyn FnMut(Annotatable),
 ) {
+    item.visit_with(&mut DetectNonVariantDefaultAttr { cx });
+
     let inline = cx.meta_word(span, sym::inline);
     let attrs = vec![cx.attribute(inline)];
     let trait_def = TraitDef {
@@ -70,6 +75,9 @@ fn default_substructure(
                 cx.expr_struct_ident(trait_span, substr.type_ident, default_fields)
             }
         },
+        StaticEnum(enum_def, _) if derives_default_enum(cx, enum_def) => {
+            default_enum_substructure(cx, trait_span, enum_def)
+        }
         StaticEnum(..) => {
             struct_span_err!(
                 &cx.sess.parse_sess.span_diagnostic,
@@ -84,3 +92,183 @@ fn default_substructure(
         _ => cx.span_bug(trait_span, "method in `derive(Default)`"),
     }
 }
+
+/// Whether to derive `Default` for an enum, rather than rejecting enums outright
+/// like we do without the `derive_default_enum` feature.
+fn derives_default_enum(cx: &ExtCtxt<'_>, enum_def: &EnumDef) -> bool {
+    let has_default_variant =
+        enum_def.variants.iter().any(|variant| cx.sess.contains_name(&variant.attrs, kw::Default));
+    has_default_variant || cx.ecfg.features.map_or(false, |features| features.derive_default_enum)
+}
+
+fn default_enum_substructure(
+    cx: &mut ExtCtxt<'_>,
+    trait_span: Span,
+    enum_def: &EnumDef,
+) -> P<Expr> {
+    if !cx.ecfg.features.map_or(false, |features| features.derive_default_enum) {
+        feature_err(
+            &cx.sess.parse_sess,
+            sym::derive_default_enum,
+            trait_span,
+            "deriving `Default` on enums is experimental",
+        )
+        .emit();
+        return DummyResult::raw_expr(trait_span, true);
+    }
+
+    let default_variant = match extract_default_variant(cx, enum_def, trait_span) {
+        Ok(variant) => variant,
+        Err(()) => return DummyResult::raw_expr(trait_span, true),
+    };
+
+    let span = default_variant.span;
+    cx.expr_path(cx.path(span, vec![Ident::new(kw::SelfUpper, span), default_variant.ident]))
+}
+
+/// Finds the variant marked with `#[default]`, making sure that there is
+/// exactly one and that it is a unit variant.
+fn extract_default_variant<'a>(
+    cx: &mut ExtCtxt<'_>,
+    enum_def: &'a EnumDef,
+    trait_span: Span,
+) -> Result<&'a ast::Variant, ()> {
+    let default_variants: SmallVec<[_; 1]> = enum_def
+        .variants
+        .iter()
+        .filter(|variant| cx.sess.contains_name(&variant.attrs, kw::Default))
+        .collect();
+
+    let variant = match default_variants.as_slice() {
+        [variant] => variant,
+        [] => {
+            let possible_defaults = enum_def
+                .variants
+                .iter()
+                .filter(|variant| matches!(variant.data, VariantData::Unit(..)))
+                .filter(|variant| !cx.sess.contains_name(&variant.attrs, sym::non_exhaustive));
+
+            let mut diag = cx.struct_span_err(trait_span, "no default declared");
+            diag.help("make a unit variant default by placing `#[default]` above it");
+            for variant in possible_defaults {
+                // Suggest making each unit variant default.
+                diag.tool_only_span_suggestion(
+                    variant.span,
+                    &format!("make `{}` default", variant.ident),
+                    format!("#[default] {}", variant.ident),
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            diag.emit();
+
+            return Err(());
+        }
+        [first, rest @ ..] => {
+            let mut diag = cx.struct_span_err(trait_span, "multiple declared defaults");
+            diag.span_label(first.span, "first default");
+            diag.span_labels(rest.iter().map(|variant| variant.span), "additional default");
+            diag.note("only one variant can be default");
+            for variant in &default_variants {
+                let suggestion = default_variants
+                    .iter()
+                    .filter(|other| other.ident != variant.ident)
+                    .filter_map(|other| {
+                        Some((cx.sess.find_by_name(&other.attrs, kw::Default)?.span, String::new()))
+                    })
+                    .collect();
+                diag.tool_only_multipart_suggestion(
+                    &format!("make `{}` default", variant.ident),
+                    suggestion,
+                    Applicability::MaybeIncorrect,
+                );
+            }
+            diag.emit();
+            return Err(());
+        }
+    };
+
+    if !matches!(variant.data, VariantData::Unit(..)) {
+        cx.struct_span_err(
+            variant.ident.span,
+            "the `#[default]` attribute may only be used on unit enum variants",
+        )
+        .help("consider a manual implementation of `Default`")
+        .emit();
+        return Err(());
+    }
+
+    if let Some(non_exhaustive_attr) = cx.sess.find_by_name(&variant.attrs, sym::non_exhaustive) {
+        cx.struct_span_err(variant.ident.span, "default variant must be exhaustive")
+            .span_label(non_exhaustive_attr.span, "declared `#[non_exhaustive]` here")
+            .help("consider a manual implementation of `Default`")
+            .emit();
+        return Err(());
+    }
+
+    let attrs: SmallVec<[_; 1]> = cx.sess.filter_by_name(&variant.attrs, kw::Default).collect();
+    if attrs.len() > 1 {
+        let rest = &attrs[1..];
+        cx.struct_span_err(variant.ident.span, "multiple `#[default]` attributes")
+            .note("only one `#[default]` attribute is needed")
+            .span_label(attrs[0].span, "`#[default]` used here")
+            .span_label(rest[0].span, "`#[default]` used again here")
+            .tool_only_multipart_suggestion(
+                "try removing these",
+                rest.iter().map(|attr| (attr.span, String::new())).collect(),
+                Applicability::MachineApplicable,
+            )
+            .emit();
+        return Err(());
+    }
+
+    for attr in attrs {
+        if !attr.is_word() {
+            cx.struct_span_err(attr.span, "`#[default]` attribute does not accept a value")
+                .span_suggestion_hidden(
+                    attr.span,
+                    "try using `#[default]`",
+                    "#[default]".into(),
+                    Applicability::MaybeIncorrect,
+                )
+                .emit();
+            return Err(());
+        }
+    }
+
+    Ok(variant)
+}
+
+/// Reports `#[default]` attributes anywhere but on enum variants, where they
+/// would otherwise be silently accepted as a helper attribute of the derive.
+struct DetectNonVariantDefaultAttr<'a, 'b> {
+    cx: &'a ExtCtxt<'b>,
+}
+
+impl<'a, 'b> Visitor<'a> for DetectNonVariantDefaultAttr<'a, 'b> {
+    fn visit_attribute(&mut self, attr: &'a ast::Attribute) {
+        if attr.has_name(kw::Default) {
+            self.cx
+                .struct_span_err(
+                    attr.span,
+                    "the `#[default]` attribute may only be used on unit enum variants",
+                )
+                .emit();
+        }
+
+        visit::walk_attribute(self, attr);
+    }
+
+    fn visit_variant(&mut self, v: &'a ast::Variant) {
+        // Like `visit::walk_variant`, but without visiting the variant's own
+        // attributes, which may contain `#[default]`.
+        self.visit_ident(v.ident);
+        self.visit_vis(&v.vis);
+        self.visit_variant_data(&v.data);
+        if let Some(ref disr_expr) = v.disr_expr {
+            self.visit_anon_const(disr_expr);
+        }
+        for attr in &v.attrs {
+            visit::walk_attribute(self, attr);
+        }
+    }
+}
@@ -15,9 +15,9 @@ extern crate proc_macro;
 use crate::deriving::*;
 
 use rustc_expand::base::{MacroExpanderFn, ResolverExpand, SyntaxExtension, SyntaxExtensionKind};
-use rustc_expand::proc_macro::BangProcMacro;
+use rustc_expand::proc_macro::{BangProcMacro, ProcMacroClient};
 use rustc_span::edition::Edition;
-use rustc_span::symbol::{sym, Ident};
+use rustc_span::symbol::{kw, sym, Ident};
 
 mod asm;
 mod assert;
@@ -98,7 +98,6 @@ pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand, edition: Editi
         Clone: clone::expand_deriving_clone,
         Copy: bounds::expand_deriving_copy,
         Debug: debug::expand_deriving_debug,
-        Default: default::expand_deriving_default,
         Eq: eq::expand_deriving_eq,
         Hash: hash::expand_deriving_hash,
         Ord: ord::expand_deriving_ord,
@@ -109,5 +108,19 @@ pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand, edition: Editi
     }
 
     let client = proc_macro::bridge::client::Client::expand1(proc_macro::quote);
-    register(sym::quote, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })));
+    let expander = BangProcMacro { client: ProcMacroClient::InProcess(client), cache_id: None };
+    register(sym::quote, SyntaxExtensionKind::Bang(Box::new(expander)));
+
+    // `#[derive(Default)]` on enums uses `#[default]` to mark the default variant.
+    let kind = SyntaxExtensionKind::LegacyDerive(Box::new(BuiltinDerive(
+        default::expand_deriving_default,
+    )));
+    resolver.register_builtin_macro(
+        Ident::with_dummy_span(sym::Default),
+        SyntaxExtension {
+            is_builtin: true,
+            helper_attrs: vec![kw::Default],
+            ..SyntaxExtension::default(kind, edition)
+        },
+    );
 }
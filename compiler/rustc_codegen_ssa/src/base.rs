@@ -632,6 +632,9 @@ pub fn codegen_crate<B: ExtraBackendMethods>(
             cgu_reuse = tcx.sess.time("find_cgu_reuse", || {
                 codegen_units.iter().map(|cgu| determine_cgu_reuse(tcx, &cgu)).collect()
             });
+            if tcx.sess.opts.debugging_opts.incremental_info {
+                report_cgu_reuse(&codegen_units, &cgu_reuse);
+            }
             // Pre compile some CGUs
             pre_compiled_cgus = Some(pre_compile_cgus(&cgu_reuse));
         }
@@ -889,6 +892,45 @@ pub fn provide_both(providers: &mut Providers) {
         |tcx, def_id| tcx.dllimport_foreign_items(def_id.krate).contains(&def_id);
 }
 
+/// Prints how much of the codegen of the previous session can be reused, both in terms of codegen
+/// units and of the mono items they contain.
+fn report_cgu_reuse(codegen_units: &[&CodegenUnit<'_>], cgu_reuse: &[CguReuse]) {
+    let mut reused_cgus = 0;
+    let mut post_lto_cgus = 0;
+    let mut reused_items = 0;
+    let mut total_items = 0;
+
+    for (cgu, &reuse) in codegen_units.iter().zip(cgu_reuse) {
+        let items = cgu.items().len();
+        total_items += items;
+        match reuse {
+            CguReuse::No => {}
+            CguReuse::PreLto => {
+                reused_cgus += 1;
+                reused_items += items;
+            }
+            CguReuse::PostLto => {
+                reused_cgus += 1;
+                post_lto_cgus += 1;
+                reused_items += items;
+            }
+        }
+    }
+
+    println!(
+        "[incremental] Re-using {} out of {} codegen units ({} of them post-LTO)",
+        reused_cgus,
+        codegen_units.len(),
+        post_lto_cgus
+    );
+    println!(
+        "[incremental] Re-using {} out of {} mono items ({:.1}%)",
+        reused_items,
+        total_items,
+        if total_items == 0 { 100.0 } else { reused_items as f64 * 100.0 / total_items as f64 }
+    );
+}
+
 fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguReuse {
     if !tcx.dep_graph.is_fully_enabled() {
         return CguReuse::No;
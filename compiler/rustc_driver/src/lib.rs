@@ -350,8 +350,11 @@ pub fn run_compiler(
 
             queries.prepare_outputs()?;
 
-            if sess.opts.output_types.contains_key(&OutputType::DepInfo)
-                && sess.opts.output_types.len() == 1
+            if sess
+                .opts
+                .output_types
+                .keys()
+                .all(|ty| matches!(ty, OutputType::DepInfo | OutputType::Expanded))
             {
                 return early_exit();
             }
@@ -1269,6 +1272,7 @@ pub fn init_env_logger(env: &str) {
 }
 
 pub fn main() -> ! {
+    rustc_metadata::proc_macro_process::maybe_serve();
     let start = Instant::now();
     init_rustc_env_logger();
     let mut callbacks = TimePassesCallbacks::default();
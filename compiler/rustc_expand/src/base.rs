@@ -1,5 +1,6 @@
 use crate::expand::{self, AstFragment, Invocation};
 use crate::module::DirectoryOwnership;
+use crate::proc_macro_cache::ProcMacroCache;
 
 use rustc_ast::ptr::P;
 use rustc_ast::token;
@@ -933,6 +934,8 @@ pub struct ExtCtxt<'a> {
     pub expansions: FxHashMap<Span, Vec<String>>,
     /// Called directly after having parsed an external `mod foo;` in expansion.
     pub(super) extern_mod_loaded: Option<&'a dyn Fn(&ast::Crate)>,
+    /// Outputs of pure proc macros, reused across incremental compilations.
+    pub proc_macro_cache: Option<Lrc<ProcMacroCache>>,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -957,6 +960,7 @@ impl<'a> ExtCtxt<'a> {
                 prior_type_ascription: None,
             },
             expansions: FxHashMap::default(),
+            proc_macro_cache: None,
         }
     }
 
@@ -177,6 +177,7 @@ impl<'a> ExtCtxt<'a> {
             pat,
             ty: None,
             init: Some(ex),
+            els: None,
             id: ast::DUMMY_NODE_ID,
             span: sp,
             attrs: AttrVec::new(),
@@ -195,6 +196,7 @@ impl<'a> ExtCtxt<'a> {
             pat: self.pat_wild(span),
             ty: Some(ty),
             init: None,
+            els: None,
             id: ast::DUMMY_NODE_ID,
             span,
             attrs: AttrVec::new(),
@@ -2,7 +2,7 @@
 #![feature(crate_visibility_modifier)]
 #![feature(decl_macro)]
 #![feature(or_patterns)]
-#![feature(proc_macro_diagnostic)]
+#![cfg_attr(bootstrap, feature(proc_macro_diagnostic))]
 #![feature(proc_macro_internals)]
 #![feature(proc_macro_span)]
 #![feature(try_blocks)]
@@ -24,6 +24,7 @@ pub mod config;
 pub mod expand;
 pub mod module;
 pub mod proc_macro;
+pub mod proc_macro_cache;
 
 crate mod mbe;
 
@@ -7,6 +7,7 @@ crate mod macro_check;
 crate mod macro_parser;
 crate mod macro_rules;
 crate mod quoted;
+crate mod trace;
 crate mod transcribe;
 
 use rustc_ast::token::{self, NonterminalKind, Token, TokenKind};
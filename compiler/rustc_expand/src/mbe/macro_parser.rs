@@ -76,10 +76,12 @@ use TokenTreeOrTokenTreeSlice::*;
 
 use crate::mbe::{self, TokenTree};
 
-use rustc_ast::token::{self, DocComment, Nonterminal, Token};
+use rustc_ast::token::{self, DocComment, Nonterminal, NonterminalKind, Token};
+use rustc_ast_pretty::pprust;
 use rustc_parse::parser::Parser;
 use rustc_session::parse::ParseSess;
-use rustc_span::symbol::MacroRulesNormalizedIdent;
+use rustc_span::symbol::{Ident, MacroRulesNormalizedIdent};
+use rustc_span::Span;
 
 use smallvec::{smallvec, SmallVec};
 
@@ -261,14 +263,54 @@ impl<'root, 'tt> DerefMut for MatcherPosHandle<'root, 'tt> {
 crate enum ParseResult<T> {
     /// Parsed successfully.
     Success(T),
-    /// Arm failed to match. If the second parameter is `token::Eof`, it indicates an unexpected
+    /// Arm failed to match. If the first parameter is `token::Eof`, it indicates an unexpected
     /// end of macro invocation. Otherwise, it indicates that no rules expected the given token.
-    Failure(Token, &'static str),
+    Failure(Token, &'static str, MatchFailure),
     /// Fatal error (malformed macro?). Abort compilation.
     Error(rustc_span::Span, String),
     ErrorReported,
 }
 
+/// Details about where and why an arm failed to match, to explain the failure.
+crate struct MatchFailure {
+    /// How far into the input the arm got, in tokens (as counted by `Parser::num_bumps`, so
+    /// this is only meaningful compared to other arms matched against the same input).
+    crate progress: usize,
+    /// What the arm could have matched instead of the token it failed at.
+    crate expected: Vec<Expected>,
+}
+
+/// Something a matcher could have matched next, but the input didn't.
+#[derive(Clone)]
+crate enum Expected {
+    /// A token of the matcher (so its span points into the macro definition), or a separator.
+    Token(Token),
+    /// A metavariable of the matcher, e.g. `$e:expr`.
+    MetaVarDecl(Span, Ident, NonterminalKind),
+    /// The end of the macro input.
+    End,
+}
+
+impl Expected {
+    /// The span of the expected part of the matcher, if any.
+    crate fn span(&self) -> Option<Span> {
+        let span = match *self {
+            Expected::Token(ref token) => token.span,
+            Expected::MetaVarDecl(span, ..) => span,
+            Expected::End => return None,
+        };
+        if span.is_dummy() { None } else { Some(span) }
+    }
+
+    crate fn describe(&self) -> String {
+        match *self {
+            Expected::Token(ref token) => format!("`{}`", pprust::token_to_string(token)),
+            Expected::MetaVarDecl(_, name, kind) => format!("`${}:{}`", name, kind),
+            Expected::End => "the end of the macro input".to_string(),
+        }
+    }
+}
+
 /// A `ParseResult` where the `Success` variant contains a mapping of
 /// `MacroRulesNormalizedIdent`s to `NamedMatch`es. This represents the mapping
 /// of metavars to the token trees they bind to.
@@ -429,6 +471,8 @@ fn token_name_eq(t1: &Token, t2: &Token) -> bool {
 ///   the function `parse`.
 /// - `eof_items`: the set of items that would be valid if this was the EOF.
 /// - `bb_items`: the set of items that are waiting for the black-box parser.
+/// - `expected`: the things that items which can't match `token` expected instead, to explain
+///   the failure if no item can match it.
 /// - `token`: the current token of the parser.
 /// - `span`: the `Span` in the source code corresponding to the token trees we are trying to match
 ///   against the matcher positions in `cur_items`.
@@ -441,6 +485,7 @@ fn inner_parse_loop<'root, 'tt>(
     next_items: &mut Vec<MatcherPosHandle<'root, 'tt>>,
     eof_items: &mut SmallVec<[MatcherPosHandle<'root, 'tt>; 1]>,
     bb_items: &mut SmallVec<[MatcherPosHandle<'root, 'tt>; 1]>,
+    expected: &mut Vec<Expected>,
     token: &Token,
 ) -> ParseResult<()> {
     // Pop items from `cur_items` until it is empty.
@@ -497,9 +542,13 @@ fn inner_parse_loop<'root, 'tt>(
                 if idx == len && item.sep.is_some() {
                     // We have a separator, and it is the current token. We can advance past the
                     // separator token.
-                    if item.sep.as_ref().map(|sep| token_name_eq(token, sep)).unwrap_or(false) {
-                        item.idx += 1;
-                        next_items.push(item);
+                    match item.sep {
+                        Some(ref sep) if token_name_eq(token, sep) => {
+                            item.idx += 1;
+                            next_items.push(item);
+                        }
+                        Some(ref sep) => expected.push(Expected::Token(sep.clone())),
+                        None => {}
                     }
                 }
                 // We don't need a separator. Move the "dot" back to the beginning of the matcher
@@ -513,6 +562,9 @@ fn inner_parse_loop<'root, 'tt>(
             // If we are not in a repetition, then being at the end of a matcher means that we have
             // reached the potential end of the input.
             else {
+                if *token != token::Eof {
+                    expected.push(Expected::End);
+                }
                 eof_items.push(item);
             }
         }
@@ -556,11 +608,13 @@ fn inner_parse_loop<'root, 'tt>(
 
                 // We need to match a metavar with a valid ident... call out to the black-box
                 // parser by adding an item to `bb_items`.
-                TokenTree::MetaVarDecl(_, _, kind) => {
+                TokenTree::MetaVarDecl(span, name, kind) => {
                     // Built-in nonterminals never start with these tokens,
                     // so we can eliminate them from consideration.
                     if Parser::nonterminal_may_begin_with(kind, token) {
                         bb_items.push(item);
+                    } else {
+                        expected.push(Expected::MetaVarDecl(span, name, kind));
                     }
                 }
 
@@ -591,7 +645,8 @@ fn inner_parse_loop<'root, 'tt>(
                 // rules. NOTE that this is not necessarily an error unless _all_ items in
                 // `cur_items` end up doing this. There may still be some other matchers that do
                 // end up working out.
-                TokenTree::Token(..) | TokenTree::MetaVar(..) => {}
+                TokenTree::Token(t) => expected.push(Expected::Token(t)),
+                TokenTree::MetaVar(..) => {}
             }
         }
     }
@@ -614,6 +669,7 @@ pub(super) fn parse_tt(parser: &mut Cow<'_, Parser<'_>>, ms: &[TokenTree]) -> Na
     let mut initial = initial_matcher_pos(ms);
     let mut cur_items = smallvec![MatcherPosHandle::Ref(&mut initial)];
     let mut next_items = Vec::new();
+    let mut expected = Vec::new();
 
     loop {
         // Matcher positions black-box parsed by parser.rs (`parser`)
@@ -622,6 +678,7 @@ pub(super) fn parse_tt(parser: &mut Cow<'_, Parser<'_>>, ms: &[TokenTree]) -> Na
         // Matcher positions that would be valid if the macro invocation was over now
         let mut eof_items = SmallVec::new();
         assert!(next_items.is_empty());
+        expected.clear();
 
         // Process `cur_items` until either we have finished the input or we need to get some
         // parsing from the black-box parser done. The result is that `next_items` will contain a
@@ -631,10 +688,11 @@ pub(super) fn parse_tt(parser: &mut Cow<'_, Parser<'_>>, ms: &[TokenTree]) -> Na
             &mut next_items,
             &mut eof_items,
             &mut bb_items,
+            &mut expected,
             &parser.token,
         ) {
             Success(_) => {}
-            Failure(token, msg) => return Failure(token, msg),
+            Failure(token, msg, failure) => return Failure(token, msg, failure),
             Error(sp, msg) => return Error(sp, msg),
             ErrorReported => return ErrorReported,
         }
@@ -659,6 +717,15 @@ pub(super) fn parse_tt(parser: &mut Cow<'_, Parser<'_>>, ms: &[TokenTree]) -> Na
                     "ambiguity: multiple successful parses".to_string(),
                 );
             } else {
+                // Nonterminals which may begin with anything (like `tt`) were expected too.
+                expected.extend(bb_items.iter().filter_map(|item| {
+                    match item.top_elts.get_tt(item.idx) {
+                        TokenTree::MetaVarDecl(span, name, kind) => {
+                            Some(Expected::MetaVarDecl(span, name, kind))
+                        }
+                        _ => None,
+                    }
+                }));
                 return Failure(
                     Token::new(
                         token::Eof,
@@ -669,6 +736,7 @@ pub(super) fn parse_tt(parser: &mut Cow<'_, Parser<'_>>, ms: &[TokenTree]) -> Na
                         },
                     ),
                     "missing tokens in macro arguments",
+                    MatchFailure { progress: parser.num_bumps(), expected },
                 );
             }
         }
@@ -680,7 +748,11 @@ pub(super) fn parse_tt(parser: &mut Cow<'_, Parser<'_>>, ms: &[TokenTree]) -> Na
         // If there are no possible next positions AND we aren't waiting for the black-box parser,
         // then there is a syntax error.
         if bb_items.is_empty() && next_items.is_empty() {
-            return Failure(parser.token.clone(), "no rules expected this token in macro call");
+            return Failure(
+                parser.token.clone(),
+                "no rules expected this token in macro call",
+                MatchFailure { progress: parser.num_bumps(), expected },
+            );
         }
         // Another possibility is that we need to call out to parse some rust nonterminal
         // (black-box) parser. However, if there is not EXACTLY ONE of these, something is wrong.
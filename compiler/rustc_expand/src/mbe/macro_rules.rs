@@ -4,8 +4,9 @@ use crate::expand::{ensure_complete_parse, parse_ast_fragment, AstFragment, AstF
 use crate::mbe;
 use crate::mbe::macro_check;
 use crate::mbe::macro_parser::parse_tt;
-use crate::mbe::macro_parser::{Error, ErrorReported, Failure, Success};
+use crate::mbe::macro_parser::{Error, ErrorReported, Expected, Failure, MatchFailure, Success};
 use crate::mbe::macro_parser::{MatchedNonterminal, MatchedSeq};
+use crate::mbe::trace;
 use crate::mbe::transcribe::transcribe;
 
 use rustc_ast as ast;
@@ -13,7 +14,7 @@ use rustc_ast::token::{self, NonterminalKind, NtTT, Token, TokenKind::*};
 use rustc_ast::tokenstream::{DelimSpan, TokenStream};
 use rustc_ast_pretty::pprust;
 use rustc_attr::{self as attr, TransparencyError};
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::{Applicability, DiagnosticBuilder};
 use rustc_feature::Features;
@@ -224,9 +225,11 @@ fn generic_extension<'cx>(
         let msg = format!("expanding `{}! {{ {} }}`", name, pprust::tts_to_string(&arg));
         trace_macros_note(&mut cx.expansions, sp, msg);
     }
+    let mut trace = trace::Invocation::new(cx, sp, name, &arg);
 
-    // Which arm's failure should we report? (the one furthest along)
-    let mut best_failure: Option<(Token, &str)> = None;
+    // Which arm's failure should we report? (the one furthest along, along with what
+    // all the arms that got as far expected)
+    let mut best_failure: Option<(Token, &str, MatchFailure)> = None;
 
     // We create a base parser that can be used for the "black box" parts.
     // Every iteration needs a fresh copy of that parser. However, the parser
@@ -262,7 +265,12 @@ fn generic_extension<'cx>(
         // are not recorded. On the first `Success(..)`ful matcher, the spans are merged.
         let mut gated_spans_snapshot = mem::take(&mut *sess.gated_spans.spans.borrow_mut());
 
-        match parse_tt(&mut Cow::Borrowed(&parser), lhs_tt) {
+        let result = parse_tt(&mut Cow::Borrowed(&parser), lhs_tt);
+        if let Some(trace) = &mut trace {
+            trace.record_arm(cx, sp, i, lhs, &result);
+        }
+
+        match result {
             Success(named_matches) => {
                 // The matcher was `Success(..)`ful.
                 // Merge the gated spans from parsing the matcher with the pre-existing ones.
@@ -281,6 +289,9 @@ fn generic_extension<'cx>(
                     Ok(tts) => tts,
                     Err(mut err) => {
                         err.emit();
+                        if let Some(trace) = trace {
+                            trace.emit();
+                        }
                         return DummyResult::any(arm_span);
                     }
                 };
@@ -300,6 +311,10 @@ fn generic_extension<'cx>(
                     let msg = format!("to `{}`", pprust::tts_to_string(&tts));
                     trace_macros_note(&mut cx.expansions, sp, msg);
                 }
+                if let Some(mut trace) = trace {
+                    trace.record_expansion(&tts);
+                    trace.emit();
+                }
 
                 let mut p = Parser::new(sess, tts, false, None);
                 p.last_type_ascription = cx.current_expansion.prior_type_ascription;
@@ -317,16 +332,27 @@ fn generic_extension<'cx>(
                     arm_span,
                 });
             }
-            Failure(token, msg) => match best_failure {
-                Some((ref best_token, _)) if best_token.span.lo() >= token.span.lo() => {}
-                _ => best_failure = Some((token, msg)),
+            Failure(token, msg, mut failure) => match best_failure {
+                Some((_, _, ref best)) if best.progress > failure.progress => {}
+                Some((_, _, ref mut best)) if best.progress == failure.progress => {
+                    best.expected.append(&mut failure.expected);
+                }
+                _ => best_failure = Some((token, msg, failure)),
             },
             Error(err_sp, ref msg) => {
+                if let Some(trace) = trace {
+                    trace.emit();
+                }
                 let span = err_sp.substitute_dummy(sp);
                 cx.struct_span_err(span, &msg).emit();
                 return DummyResult::any(span);
             }
-            ErrorReported => return DummyResult::any(sp),
+            ErrorReported => {
+                if let Some(trace) = trace {
+                    trace.emit();
+                }
+                return DummyResult::any(sp);
+            }
         }
 
         // The matcher was not `Success(..)`ful.
@@ -334,14 +360,18 @@ fn generic_extension<'cx>(
         mem::swap(&mut gated_spans_snapshot, &mut sess.gated_spans.spans.borrow_mut());
     }
     drop(parser);
+    if let Some(trace) = trace {
+        trace.emit();
+    }
 
-    let (token, label) = best_failure.expect("ran no matchers");
+    let (token, label, failure) = best_failure.expect("ran no matchers");
     let span = token.span.substitute_dummy(sp);
     let mut err = cx.struct_span_err(span, &parse_failure_msg(&token));
     err.span_label(span, label);
     if !def_span.is_dummy() && !cx.source_map().is_imported(def_span) {
         err.span_label(cx.source_map().guess_head_span(def_span), "when calling this macro");
     }
+    note_expected(&mut err, &failure.expected);
 
     // Check whether there's a missing comma in this macro call, like `println!("{}" a);`
     if let Some((arg, comma_span)) = arg.add_comma() {
@@ -448,7 +478,7 @@ pub fn compile_declarative_macro(
     let parser = Parser::new(&sess.parse_sess, body, true, rustc_parse::MACRO_ARGUMENTS);
     let argument_map = match parse_tt(&mut Cow::Borrowed(&parser), &argument_gram) {
         Success(m) => m,
-        Failure(token, msg) => {
+        Failure(token, msg, _) => {
             let s = parse_failure_msg(&token);
             let sp = token.span.substitute_dummy(def.span);
             sess.parse_sess.span_diagnostic.struct_span_err(sp, &s).span_label(sp, msg).emit();
@@ -1191,3 +1221,29 @@ fn parse_failure_msg(tok: &Token) -> String {
         _ => format!("no rules expected the token `{}`", pprust::token_to_string(tok),),
     }
 }
+
+/// Explains what the arms that got furthest into the input expected instead of the
+/// token they failed at, pointing into the macro definition if possible.
+fn note_expected(err: &mut DiagnosticBuilder<'_>, expected: &[Expected]) {
+    let mut expected: Vec<_> = expected.iter().map(|e| (e.span(), e.describe())).collect();
+    // In the order they appear in the macro definition, with the end of the input last,
+    // only pointing at the first place where each thing is expected.
+    expected.sort_by_key(|&(span, _)| span.map_or(u32::MAX, |span| span.lo().0));
+    let mut seen = FxHashSet::default();
+    expected.retain(|(_, description)| seen.insert(description.clone()));
+
+    let msg = match &expected[..] {
+        [] => return,
+        [(_, description)] => format!("while trying to match {}", description),
+        [rest @ .., (_, last)] => {
+            let rest: Vec<&str> = rest.iter().map(|(_, description)| &description[..]).collect();
+            format!("while trying to match {} or {}", rest.join(", "), last)
+        }
+    };
+    let spans: Vec<Span> = expected.iter().filter_map(|&(span, _)| span).collect();
+    if spans.is_empty() {
+        err.note(&msg);
+    } else {
+        err.span_note(spans, &msg);
+    }
+}
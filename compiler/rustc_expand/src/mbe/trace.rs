@@ -0,0 +1,220 @@
+//! A structured trace of `macro_rules!` matching, printed as JSON by `-Z trace-macros-json`.
+//!
+//! Each invocation of a `macro_rules!` macro prints a single line, with the arms that were
+//! tried (in order) and, for each of them, either the token at which it failed to match,
+//! or what each of its metavariables bound to. For example:
+//!
+//! ```json
+//! {"macro_name":"foo","call_site":{...},"depth":1,"input":"1 + 2",
+//!  "arms":[{"index":0,"span":{...},"outcome":"failed",
+//!           "failure":{"token":"1",...,"expected":["the end of the macro input"]},
+//!           "bindings":null},
+//!          {"index":1,"span":{...},"outcome":"matched","failure":null,
+//!           "bindings":[{"name":"e","kind":"expr","bound_to":"1 + 2"}]}],
+//!  "expansion":"1 + 2 + 1"}
+//! ```
+//!
+//! Metavariables in repetitions are bound to (possibly nested) arrays of fragments.
+
+use crate::base::ExtCtxt;
+use crate::mbe;
+use crate::mbe::macro_parser::{MatchedNonterminal, MatchedSeq};
+use crate::mbe::macro_parser::{Expected, NamedMatch, ParseResult};
+
+use rustc_ast::token::Token;
+use rustc_ast::tokenstream::TokenStream;
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_serialize::json;
+use rustc_serialize::{Encodable, Encoder};
+use rustc_span::symbol::{Ident, MacroRulesNormalizedIdent};
+use rustc_span::Span;
+
+use std::slice;
+
+#[derive(Encodable)]
+struct TraceSpan {
+    file_name: String,
+    /// 1-based.
+    line_start: usize,
+    line_end: usize,
+    /// 1-based, in characters.
+    column_start: usize,
+    column_end: usize,
+}
+
+impl TraceSpan {
+    fn new(cx: &ExtCtxt<'_>, span: Span) -> TraceSpan {
+        let source_map = cx.source_map();
+        let start = source_map.lookup_char_pos(span.lo());
+        let end = source_map.lookup_char_pos(span.hi());
+        TraceSpan {
+            file_name: start.file.name.to_string(),
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+        }
+    }
+}
+
+/// Where an arm failed to match.
+#[derive(Encodable)]
+struct Failure {
+    /// The offending token, `<eof>` if the input ended too early,
+    /// or `None` if the matcher itself was invalid.
+    token: Option<String>,
+    span: TraceSpan,
+    message: String,
+    /// What the arm could have matched instead, e.g. `` `$e:expr` `` or `` `,` ``.
+    expected: Vec<String>,
+}
+
+/// What a metavariable bound to: a fragment, or one entry
+/// per repetition of the sequence it's in.
+enum BoundTo {
+    Fragment(String),
+    Repetition(Vec<BoundTo>),
+}
+
+impl<S: Encoder> Encodable<S> for BoundTo {
+    fn encode(&self, s: &mut S) -> Result<(), S::Error> {
+        match self {
+            BoundTo::Fragment(tokens) => tokens.encode(s),
+            BoundTo::Repetition(matches) => matches.encode(s),
+        }
+    }
+}
+
+impl BoundTo {
+    fn new(m: &NamedMatch) -> BoundTo {
+        match m {
+            MatchedNonterminal(nt) => BoundTo::Fragment(pprust::nonterminal_to_string(nt)),
+            MatchedSeq(matches) => BoundTo::Repetition(matches.iter().map(BoundTo::new).collect()),
+        }
+    }
+}
+
+#[derive(Encodable)]
+struct Binding {
+    name: String,
+    /// The fragment specifier, e.g. `expr`.
+    kind: String,
+    bound_to: BoundTo,
+}
+
+#[derive(Encodable)]
+struct Arm {
+    /// The index of the arm in the macro definition, starting at 0.
+    index: usize,
+    span: TraceSpan,
+    /// `matched`, `failed`, or `error` if the matcher itself couldn't be used.
+    outcome: &'static str,
+    failure: Option<Failure>,
+    /// In the order the metavariables appear in the matcher.
+    bindings: Option<Vec<Binding>>,
+}
+
+/// The trace of a single invocation.
+#[derive(Encodable)]
+crate struct Invocation {
+    macro_name: String,
+    call_site: TraceSpan,
+    /// How many expansions deep the invocation is, e.g. to follow recursive macros.
+    depth: usize,
+    input: String,
+    arms: Vec<Arm>,
+    /// The transcribed output, if an arm matched and transcription succeeded.
+    expansion: Option<String>,
+}
+
+impl Invocation {
+    /// Returns `None` if the trace wasn't requested.
+    crate fn new(cx: &ExtCtxt<'_>, sp: Span, name: Ident, arg: &TokenStream) -> Option<Invocation> {
+        if !cx.sess.opts.debugging_opts.trace_macros_json {
+            return None;
+        }
+        Some(Invocation {
+            macro_name: name.to_string(),
+            call_site: TraceSpan::new(cx, sp),
+            depth: cx.current_expansion.depth,
+            input: pprust::tts_to_string(arg),
+            arms: Vec::new(),
+            expansion: None,
+        })
+    }
+
+    /// Records the result of trying the `index`-th arm, whose matcher is `lhs`.
+    crate fn record_arm(
+        &mut self,
+        cx: &ExtCtxt<'_>,
+        sp: Span,
+        index: usize,
+        lhs: &mbe::TokenTree,
+        result: &ParseResult<FxHashMap<MacroRulesNormalizedIdent, NamedMatch>>,
+    ) {
+        let failure =
+            |token: Option<&Token>, span: Span, message: &str, expected: &[Expected]| Failure {
+                token: token.map(pprust::token_to_string),
+                span: TraceSpan::new(cx, span.substitute_dummy(sp)),
+                message: message.to_owned(),
+                expected: expected.iter().map(Expected::describe).collect(),
+            };
+        let (outcome, failure, bindings) = match result {
+            ParseResult::Success(named_matches) => {
+                let mut bindings = Vec::new();
+                collect_bindings(slice::from_ref(lhs), named_matches, &mut bindings);
+                ("matched", None, Some(bindings))
+            }
+            ParseResult::Failure(token, msg, match_failure) => {
+                let failure = failure(Some(token), token.span, msg, &match_failure.expected);
+                ("failed", Some(failure), None)
+            }
+            ParseResult::Error(err_sp, msg) => {
+                ("error", Some(failure(None, *err_sp, msg, &[])), None)
+            }
+            ParseResult::ErrorReported => ("error", None, None),
+        };
+        self.arms.push(Arm {
+            index,
+            span: TraceSpan::new(cx, lhs.span()),
+            outcome,
+            failure,
+            bindings,
+        });
+    }
+
+    crate fn record_expansion(&mut self, tts: &TokenStream) {
+        self.expansion = Some(pprust::tts_to_string(tts));
+    }
+
+    crate fn emit(self) {
+        println!("{}", json::as_json(&self));
+    }
+}
+
+/// Collects the bindings of the metavariables declared in `tts`, in order.
+fn collect_bindings(
+    tts: &[mbe::TokenTree],
+    named_matches: &FxHashMap<MacroRulesNormalizedIdent, NamedMatch>,
+    bindings: &mut Vec<Binding>,
+) {
+    for tt in tts {
+        match tt {
+            mbe::TokenTree::Delimited(_, delimited) => {
+                collect_bindings(&delimited.tts, named_matches, bindings)
+            }
+            mbe::TokenTree::Sequence(_, seq) => collect_bindings(&seq.tts, named_matches, bindings),
+            mbe::TokenTree::MetaVarDecl(_, ident, kind) => {
+                if let Some(m) = named_matches.get(&MacroRulesNormalizedIdent::new(*ident)) {
+                    bindings.push(Binding {
+                        name: ident.to_string(),
+                        kind: kind.to_string(),
+                        bound_to: BoundTo::new(m),
+                    });
+                }
+            }
+            mbe::TokenTree::Token(..) | mbe::TokenTree::MetaVar(..) => {}
+        }
+    }
+}
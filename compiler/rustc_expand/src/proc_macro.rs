@@ -1,20 +1,303 @@
 use crate::base::{self, *};
+use crate::proc_macro_cache::{ProcMacroId, Query, RunRecord};
 use crate::proc_macro_server;
 
 use rustc_ast::token;
 use rustc_ast::tokenstream::{TokenStream, TokenTree};
 use rustc_ast::{self as ast, *};
-use rustc_data_structures::sync::Lrc;
+use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_errors::{struct_span_err, Applicability, ErrorReported};
 use rustc_lexer::is_ident;
 use rustc_parse::nt_to_tokenstream;
 use rustc_span::symbol::sym;
 use rustc_span::{Span, DUMMY_SP};
 
+use pm::bridge::process::Connection;
+use pm::bridge::PanicMessage;
+use std::fmt;
+use std::io::{self, BufReader, BufWriter};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+
 const EXEC_STRATEGY: pm::bridge::server::SameThread = pm::bridge::server::SameThread;
 
+/// A child process running proc macros on behalf of the compiler
+/// (see `-Z proc-macro-out-of-process`), talking to it over its stdin and stdout.
+///
+/// The process is only started once a proc macro needs it, and is started again
+/// for the next proc macro whenever it crashes (or stops following the protocol).
+pub struct ProcMacroServer {
+    command: Box<dyn Fn() -> Command + sync::Send + sync::Sync>,
+    process: Lock<Option<ServerProcess>>,
+}
+
+struct ServerProcess {
+    child: Child,
+    connection: Lrc<Connection>,
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        // The server doesn't hold onto any state worth shutting down cleanly.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Why a proc macro couldn't be run by the `ProcMacroServer`.
+#[derive(Debug)]
+pub enum ServerError {
+    /// The proc macro server process couldn't be started.
+    Spawn(io::Error),
+    /// Talking to the proc macro server process failed, with `status` being
+    /// its exit status if it had exited on its own (e.g. by aborting).
+    Crashed { error: io::Error, status: Option<ExitStatus> },
+}
+
+impl ServerError {
+    fn note(&self) -> String {
+        match self {
+            ServerError::Spawn(e) => e.to_string(),
+            ServerError::Crashed { status: Some(status), .. } => {
+                format!("the proc macro server exited with {}", status)
+            }
+            ServerError::Crashed { error, status: None } => {
+                format!("lost connection to the proc macro server: {}", error)
+            }
+        }
+    }
+
+    fn report(&self, ecx: &ExtCtxt<'_>, span: Span) {
+        let msg = match self {
+            ServerError::Spawn(_) => "failed to start the proc macro server",
+            ServerError::Crashed { .. } => "proc macro server crashed",
+        };
+        ecx.struct_span_err(span, msg).note(&self.note()).emit();
+    }
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerError::Spawn(_) => write!(f, "failed to start the proc macro server: ")?,
+            ServerError::Crashed { .. } => write!(f, "proc macro server crashed: ")?,
+        }
+        f.write_str(&self.note())
+    }
+}
+
+impl ProcMacroServer {
+    /// `command` is expected to end up in `proc_macro::bridge::process::serve`.
+    pub fn new(command: impl Fn() -> Command + sync::Send + sync::Sync + 'static) -> Self {
+        ProcMacroServer { command: Box::new(command), process: Lock::new(None) }
+    }
+
+    fn connection(&self) -> Result<Lrc<Connection>, ServerError> {
+        let mut process = self.process.lock();
+        if let Some(process) = &*process {
+            return Ok(process.connection.clone());
+        }
+
+        let mut command = (self.command)();
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(ServerError::Spawn)?;
+        let connection = Lrc::new(Connection::new(
+            BufReader::new(child.stdout.take().unwrap()),
+            BufWriter::new(child.stdin.take().unwrap()),
+        ));
+        *process = Some(ServerProcess { child, connection: connection.clone() });
+        Ok(connection)
+    }
+
+    /// Stops the process behind `connection`, unless it was already replaced, so
+    /// that the next proc macro starts a new one. Returns its exit status if it
+    /// exited on its own.
+    fn stop(&self, connection: &Lrc<Connection>, error: &io::Error) -> Option<ExitStatus> {
+        let mut process = self.process.lock();
+        match &*process {
+            Some(p) if Lrc::ptr_eq(&p.connection, connection) => {}
+            _ => return None,
+        }
+        let mut process = process.take().unwrap();
+        match error.kind() {
+            // The process closed its end of the pipes, which it only does by exiting.
+            io::ErrorKind::UnexpectedEof | io::ErrorKind::BrokenPipe => process.child.wait().ok(),
+            _ => None,
+        }
+    }
+
+    /// Runs `f` with a connection to the proc macro server process, starting it
+    /// if needed. If `f` fails (or panics), the connection is unusable and the
+    /// process is stopped.
+    fn with_connection<R>(
+        &self,
+        f: impl FnOnce(&Connection) -> io::Result<R>,
+    ) -> Result<R, ServerError> {
+        struct StopOnUnwind<'a>(&'a ProcMacroServer, &'a Lrc<Connection>);
+
+        impl Drop for StopOnUnwind<'_> {
+            fn drop(&mut self) {
+                if thread::panicking() {
+                    let error = io::Error::new(io::ErrorKind::Other, "panicked");
+                    self.0.stop(self.1, &error);
+                }
+            }
+        }
+
+        let connection = self.connection()?;
+        let guard = StopOnUnwind(self, &connection);
+        let result = f(&connection);
+        drop(guard);
+        result.map_err(|error| {
+            let status = self.stop(&connection, &error);
+            ServerError::Crashed { error, status }
+        })
+    }
+
+    /// Lists the proc macros of the crate at `locator` (see `RemoteProcMacro`).
+    pub fn list(
+        &self,
+        locator: &[u8],
+    ) -> Result<Result<Vec<pm::bridge::process::ProcMacroDecl>, String>, ServerError> {
+        self.with_connection(|connection| connection.list(locator))
+    }
+}
+
+/// A proc macro to run in a `ProcMacroServer`: the `index`-th proc macro of the
+/// crate identified by `locator`.
+#[derive(Clone)]
+pub struct RemoteProcMacro {
+    pub server: Lrc<ProcMacroServer>,
+    pub locator: Lrc<[u8]>,
+    pub index: u32,
+}
+
+/// Where a proc macro runs: in the compiler itself, or in a `ProcMacroServer`.
+pub enum ProcMacroClient<F> {
+    InProcess(pm::bridge::client::Client<F>),
+    OutOfProcess(RemoteProcMacro),
+}
+
+/// Why running a proc macro didn't produce any output.
+enum ExpansionError {
+    Panicked(PanicMessage),
+    Server(ServerError),
+}
+
+impl ExpansionError {
+    /// Reports the error, with `panicked` describing a panic of the proc macro.
+    fn report(self, ecx: &ExtCtxt<'_>, span: Span, panicked: &str) {
+        match self {
+            ExpansionError::Panicked(e) => {
+                let mut err = ecx.struct_span_err(span, panicked);
+                if let Some(s) = e.as_str() {
+                    err.help(&format!("message: {}", s));
+                }
+                err.emit();
+            }
+            ExpansionError::Server(e) => e.report(ecx, span),
+        }
+    }
+}
+
+impl From<PanicMessage> for ExpansionError {
+    fn from(e: PanicMessage) -> Self {
+        ExpansionError::Panicked(e)
+    }
+}
+
+impl From<ServerError> for ExpansionError {
+    fn from(e: ServerError) -> Self {
+        ExpansionError::Server(e)
+    }
+}
+
+impl ProcMacroClient<fn(pm::TokenStream) -> pm::TokenStream> {
+    fn run(
+        &self,
+        server: proc_macro_server::Rustc<'_, '_>,
+        input: TokenStream,
+        force_show_panics: bool,
+    ) -> Result<TokenStream, ExpansionError> {
+        match self {
+            ProcMacroClient::InProcess(client) => {
+                Ok(client.run(&EXEC_STRATEGY, server, input, force_show_panics)?)
+            }
+            ProcMacroClient::OutOfProcess(remote) => {
+                Ok(remote.server.with_connection(|connection| {
+                    connection.expand1(
+                        &remote.locator,
+                        remote.index,
+                        server,
+                        input,
+                        force_show_panics,
+                    )
+                })??)
+            }
+        }
+    }
+}
+
+impl ProcMacroClient<fn(pm::TokenStream, pm::TokenStream) -> pm::TokenStream> {
+    fn run(
+        &self,
+        server: proc_macro_server::Rustc<'_, '_>,
+        input: TokenStream,
+        input2: TokenStream,
+        force_show_panics: bool,
+    ) -> Result<TokenStream, ExpansionError> {
+        match self {
+            ProcMacroClient::InProcess(client) => {
+                Ok(client.run(&EXEC_STRATEGY, server, input, input2, force_show_panics)?)
+            }
+            ProcMacroClient::OutOfProcess(remote) => {
+                Ok(remote.server.with_connection(|connection| {
+                    connection.expand2(
+                        &remote.locator,
+                        remote.index,
+                        server,
+                        input,
+                        input2,
+                        force_show_panics,
+                    )
+                })??)
+            }
+        }
+    }
+}
+
+/// Runs a proc macro with `run`, unless its output from a previous compilation
+/// can be reused (see `proc_macro_cache`), in which case `id` identifies it.
+fn run_cached(
+    ecx: &mut ExtCtxt<'_>,
+    id: Option<&ProcMacroId>,
+    inputs: &[&TokenStream],
+    run: impl FnOnce(proc_macro_server::Rustc<'_, '_>) -> Result<TokenStream, ExpansionError>,
+) -> Result<TokenStream, ExpansionError> {
+    let cache = ecx.proc_macro_cache.clone();
+    let (cache, query) = match (cache, id) {
+        (Some(cache), Some(id)) => match Query::new(ecx, id, inputs) {
+            Some(query) => (cache, query),
+            None => return run(proc_macro_server::Rustc::new(ecx, None)),
+        },
+        _ => return run(proc_macro_server::Rustc::new(ecx, None)),
+    };
+
+    if let Some(output) = cache.lookup(ecx.sess, &query) {
+        return Ok(output);
+    }
+    let mut record = RunRecord::default();
+    let output = run(proc_macro_server::Rustc::new(ecx, Some(&mut record)))?;
+    cache.insert(query, record, &output);
+    Ok(output)
+}
+
 pub struct BangProcMacro {
-    pub client: pm::bridge::client::Client<fn(pm::TokenStream) -> pm::TokenStream>,
+    pub client: ProcMacroClient<fn(pm::TokenStream) -> pm::TokenStream>,
+    pub cache_id: Option<ProcMacroId>,
 }
 
 impl base::ProcMacro for BangProcMacro {
@@ -24,20 +307,20 @@ impl base::ProcMacro for BangProcMacro {
         span: Span,
         input: TokenStream,
     ) -> Result<TokenStream, ErrorReported> {
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&EXEC_STRATEGY, server, input, ecx.ecfg.proc_macro_backtrace).map_err(|e| {
-            let mut err = ecx.struct_span_err(span, "proc macro panicked");
-            if let Some(s) = e.as_str() {
-                err.help(&format!("message: {}", s));
-            }
-            err.emit();
+        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+        let result = run_cached(ecx, self.cache_id.as_ref(), &[&input], |server| {
+            self.client.run(server, input.clone(), proc_macro_backtrace)
+        });
+        result.map_err(|e| {
+            e.report(ecx, span, "proc macro panicked");
             ErrorReported
         })
     }
 }
 
 pub struct AttrProcMacro {
-    pub client: pm::bridge::client::Client<fn(pm::TokenStream, pm::TokenStream) -> pm::TokenStream>,
+    pub client: ProcMacroClient<fn(pm::TokenStream, pm::TokenStream) -> pm::TokenStream>,
+    pub cache_id: Option<ProcMacroId>,
 }
 
 impl base::AttrProcMacro for AttrProcMacro {
@@ -48,22 +331,21 @@ impl base::AttrProcMacro for AttrProcMacro {
         annotation: TokenStream,
         annotated: TokenStream,
     ) -> Result<TokenStream, ErrorReported> {
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client
-            .run(&EXEC_STRATEGY, server, annotation, annotated, ecx.ecfg.proc_macro_backtrace)
-            .map_err(|e| {
-                let mut err = ecx.struct_span_err(span, "custom attribute panicked");
-                if let Some(s) = e.as_str() {
-                    err.help(&format!("message: {}", s));
-                }
-                err.emit();
-                ErrorReported
-            })
+        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+        let inputs = [&annotation, &annotated];
+        let result = run_cached(ecx, self.cache_id.as_ref(), &inputs, |server| {
+            self.client.run(server, annotation.clone(), annotated.clone(), proc_macro_backtrace)
+        });
+        result.map_err(|e| {
+            e.report(ecx, span, "custom attribute panicked");
+            ErrorReported
+        })
     }
 }
 
 pub struct ProcMacroDerive {
-    pub client: pm::bridge::client::Client<fn(pm::TokenStream) -> pm::TokenStream>,
+    pub client: ProcMacroClient<fn(pm::TokenStream) -> pm::TokenStream>,
+    pub cache_id: Option<ProcMacroId>,
 }
 
 impl MultiItemModifier for ProcMacroDerive {
@@ -113,19 +395,17 @@ impl MultiItemModifier for ProcMacroDerive {
             nt_to_tokenstream(&item, &ecx.sess.parse_sess, DUMMY_SP)
         };
 
-        let server = proc_macro_server::Rustc::new(ecx);
-        let stream =
-            match self.client.run(&EXEC_STRATEGY, server, input, ecx.ecfg.proc_macro_backtrace) {
-                Ok(stream) => stream,
-                Err(e) => {
-                    let mut err = ecx.struct_span_err(span, "proc-macro derive panicked");
-                    if let Some(s) = e.as_str() {
-                        err.help(&format!("message: {}", s));
-                    }
-                    err.emit();
-                    return ExpandResult::Ready(vec![]);
-                }
-            };
+        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+        let result = run_cached(ecx, self.cache_id.as_ref(), &[&input], |server| {
+            self.client.run(server, input.clone(), proc_macro_backtrace)
+        });
+        let stream = match result {
+            Ok(stream) => stream,
+            Err(e) => {
+                e.report(ecx, span, "proc-macro derive panicked");
+                return ExpandResult::Ready(vec![]);
+            }
+        };
 
         let error_count_before = ecx.sess.parse_sess.span_diagnostic.err_count();
         let mut parser =
@@ -0,0 +1,307 @@
+//! Reusing the output of pure proc macros from previous incremental compilations
+//! (see `-Z cache-proc-macros` and `proc_macro::declare_pure`).
+//!
+//! Outputs are keyed on the proc macro (the `Svh` of its crate and its name) and
+//! on a hash of its input tokens, ignoring their spans, and are only reused if
+//! the environment variables and files tracked by the proc macro are unchanged.
+//!
+//! The spans in an output can't be stored as they are, since the input usually
+//! moves around between compilations, so they're stored relative to the spans of
+//! the input tokens, or as the def/call/mixed site of the expansion. Outputs with
+//! any other spans (e.g. from `Span::join`) aren't cached, and neither are those
+//! of proc macros which looked at source locations or emitted diagnostics.
+
+use crate::base::ExtCtxt;
+use crate::proc_macro_server::ExpansionSites;
+
+use rustc_ast::token::{self, DelimToken, TokenKind};
+use rustc_ast::tokenstream::{DelimSpan, Spacing, TokenStream, TokenTree};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::svh::Svh;
+use rustc_data_structures::sync::Lock;
+use rustc_serialize::opaque::{Decoder, Encoder};
+use rustc_serialize::{Decodable, Encodable};
+use rustc_session::Session;
+use rustc_span::symbol::Symbol;
+use rustc_span::Span;
+
+use std::env;
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::PathBuf;
+use tracing::debug;
+
+const CACHE_FILENAME: &str = "proc-macro-cache.bin";
+const RUSTC_VERSION: Option<&str> = option_env!("CFG_VERSION");
+
+/// Identifies a proc macro across compilations.
+#[derive(Clone, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct ProcMacroId {
+    pub crate_hash: Svh,
+    pub name: Symbol,
+}
+
+/// What a proc macro did while running, besides producing its output.
+#[derive(Default)]
+crate struct RunRecord {
+    /// The proc macro called `proc_macro::declare_pure`.
+    crate pure: bool,
+    /// The proc macro observed something its output can't be keyed on (e.g. the
+    /// line a span is on), or had a side effect that can't be replayed (e.g.
+    /// emitting a diagnostic), so its output can't be reused.
+    crate uncacheable: bool,
+    crate env_vars: Vec<(String, Option<String>)>,
+    crate paths: Vec<String>,
+}
+
+/// A span in a cached output.
+#[derive(Copy, Clone, Encodable, Decodable)]
+enum CachedSpan {
+    /// The span of the input token (or delimiter) with this index,
+    /// in the order they're visited by `CachedTree::from_stream`.
+    Input(u32),
+    DefSite,
+    CallSite,
+    MixedSite,
+}
+
+#[derive(Encodable, Decodable)]
+enum CachedTree {
+    Token(TokenKind, CachedSpan, bool /* joint */),
+    Delimited(CachedSpan, CachedSpan, DelimToken, Vec<CachedTree>),
+}
+
+impl CachedTree {
+    /// Converts `stream`, using `span` to convert its spans. Returns `None`
+    /// if `stream` contains a span which can't be converted, or nonterminals.
+    fn from_stream(
+        stream: &TokenStream,
+        span: &mut impl FnMut(Span) -> Option<CachedSpan>,
+    ) -> Option<Vec<CachedTree>> {
+        stream
+            .0
+            .iter()
+            .map(|(tree, spacing)| match tree {
+                TokenTree::Token(token::Token { kind: token::Interpolated(..), .. }) => None,
+                TokenTree::Token(token) => Some(CachedTree::Token(
+                    token.kind.clone(),
+                    span(token.span)?,
+                    *spacing == Spacing::Joint,
+                )),
+                TokenTree::Delimited(delim_span, delim, stream) => Some(CachedTree::Delimited(
+                    span(delim_span.open)?,
+                    span(delim_span.close)?,
+                    *delim,
+                    CachedTree::from_stream(stream, span)?,
+                )),
+            })
+            .collect()
+    }
+
+    fn to_stream(trees: &[CachedTree], span: &impl Fn(CachedSpan) -> Span) -> TokenStream {
+        TokenStream::new(
+            trees
+                .iter()
+                .map(|tree| match tree {
+                    CachedTree::Token(kind, token_span, joint) => (
+                        TokenTree::token(kind.clone(), span(*token_span)),
+                        if *joint { Spacing::Joint } else { Spacing::Alone },
+                    ),
+                    CachedTree::Delimited(open, close, delim, trees) => (
+                        TokenTree::Delimited(
+                            DelimSpan { open: span(*open), close: span(*close) },
+                            *delim,
+                            CachedTree::to_stream(trees, span),
+                        ),
+                        Spacing::Alone,
+                    ),
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Encodable, Decodable)]
+struct Entry {
+    env_vars: Vec<(String, Option<String>)>,
+    /// Tracked files, along with the hash of their contents.
+    files: Vec<(String, Option<Fingerprint>)>,
+    output: Vec<CachedTree>,
+}
+
+fn hash_file(path: &str) -> Option<Fingerprint> {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = StableHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+impl Entry {
+    fn is_up_to_date(&self) -> bool {
+        self.env_vars.iter().all(|(var, value)| env::var(var).ok() == *value)
+            && self.files.iter().all(|(path, hash)| hash_file(path) == *hash)
+    }
+}
+
+/// An invocation of a proc macro, as the cache sees it.
+crate struct Query {
+    key: (ProcMacroId, Fingerprint),
+    /// The spans of the input tokens, indexed by `CachedSpan::Input`.
+    input_spans: Vec<Span>,
+    sites: ExpansionSites,
+}
+
+impl Query {
+    /// Returns `None` if the expansion can't be cached (due to its input).
+    crate fn new(ecx: &ExtCtxt<'_>, id: &ProcMacroId, inputs: &[&TokenStream]) -> Option<Query> {
+        let mut input_spans = Vec::new();
+        let mut encoder = Encoder::new(Vec::new());
+        for input in inputs {
+            let trees = CachedTree::from_stream(input, &mut |span| {
+                input_spans.push(span);
+                Some(CachedSpan::Input(input_spans.len() as u32 - 1))
+            })?;
+            trees.encode(&mut encoder).unwrap();
+        }
+        let mut hasher = StableHasher::new();
+        encoder.into_inner().hash(&mut hasher);
+        Some(Query {
+            key: (id.clone(), hasher.finish()),
+            input_spans,
+            sites: ExpansionSites::new(ecx),
+        })
+    }
+
+    fn to_span(&self, span: CachedSpan) -> Span {
+        match span {
+            CachedSpan::Input(i) => self.input_spans[i as usize],
+            CachedSpan::DefSite => self.sites.def_site,
+            CachedSpan::CallSite => self.sites.call_site,
+            CachedSpan::MixedSite => self.sites.mixed_site,
+        }
+    }
+}
+
+pub struct ProcMacroCache {
+    /// Entries loaded from the previous compilation session, which
+    /// are moved to `current` when they're (still) used.
+    previous: Lock<FxHashMap<(ProcMacroId, Fingerprint), Entry>>,
+    /// Entries to save for the next compilation session.
+    current: Lock<FxHashMap<(ProcMacroId, Fingerprint), Entry>>,
+}
+
+impl ProcMacroCache {
+    fn path(sess: &Session) -> Option<PathBuf> {
+        Some(sess.incr_comp_session_dir_opt()?.join(CACHE_FILENAME))
+    }
+
+    /// Loads the entries saved by the previous compilation session, returning
+    /// `None` if proc macro outputs shouldn't be cached in this one.
+    pub fn load(sess: &Session) -> Option<ProcMacroCache> {
+        if !sess.opts.debugging_opts.cache_proc_macros {
+            return None;
+        }
+        let path = ProcMacroCache::path(sess)?;
+        let previous = match fs::read(&path) {
+            Ok(data) => {
+                let mut decoder = Decoder::new(&data, 0);
+                let entries: Result<_, String> = try {
+                    if Option::<String>::decode(&mut decoder)?.as_deref() == RUSTC_VERSION {
+                        Vec::<((ProcMacroId, Fingerprint), Entry)>::decode(&mut decoder)?
+                    } else {
+                        Vec::new()
+                    }
+                };
+                entries.unwrap_or_else(|e| {
+                    debug!("failed to decode {}: {}", path.display(), e);
+                    Vec::new()
+                })
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                sess.warn(&format!("failed to read proc macro cache `{}`: {}", path.display(), e));
+                Vec::new()
+            }
+        };
+        Some(ProcMacroCache {
+            previous: Lock::new(previous.into_iter().collect()),
+            current: Default::default(),
+        })
+    }
+
+    /// Saves the entries used in this compilation session for the next one.
+    pub fn save(&self, sess: &Session) {
+        let path = match ProcMacroCache::path(sess) {
+            Some(path) => path,
+            None => return,
+        };
+        let mut encoder = Encoder::new(Vec::new());
+        RUSTC_VERSION.map(str::to_owned).encode(&mut encoder).unwrap();
+        self.current.lock().iter().collect::<Vec<_>>().encode(&mut encoder).unwrap();
+        // The file may be a hard link to the one in the previous session directory,
+        // which mustn't be modified, so it has to be replaced rather than overwritten.
+        let result = match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => fs::write(&path, encoder.into_inner()),
+        };
+        if let Err(e) = result {
+            sess.warn(&format!("failed to write proc macro cache `{}`: {}", path.display(), e));
+        }
+    }
+
+    /// Returns the output of a previous run of the proc macro for this query, if any,
+    /// replaying the tracking of the environment variables and files it accessed.
+    crate fn lookup(&self, sess: &Session, query: &Query) -> Option<TokenStream> {
+        let entry = self.previous.lock().remove(&query.key);
+        let entry = match entry {
+            Some(entry) => entry,
+            None => self.current.lock().remove(&query.key)?,
+        };
+        if !entry.is_up_to_date() {
+            return None;
+        }
+
+        let parse_sess = &sess.parse_sess;
+        for (var, value) in &entry.env_vars {
+            let value = value.as_deref().map(Symbol::intern);
+            parse_sess.env_depinfo.borrow_mut().insert((Symbol::intern(var), value));
+        }
+        for (path, _) in &entry.files {
+            parse_sess.file_depinfo.borrow_mut().insert(Symbol::intern(path));
+        }
+
+        let output = CachedTree::to_stream(&entry.output, &|span| query.to_span(span));
+        self.current.lock().insert(query.key.clone(), entry);
+        Some(output)
+    }
+
+    /// Stores the output of a run of the proc macro for this query,
+    /// if the proc macro is pure and its output can be cached.
+    crate fn insert(&self, query: Query, record: RunRecord, output: &TokenStream) {
+        if !record.pure || record.uncacheable {
+            return;
+        }
+
+        let mut spans = FxHashMap::default();
+        for (i, &span) in query.input_spans.iter().enumerate().rev() {
+            spans.insert(span, CachedSpan::Input(i as u32));
+        }
+        spans.insert(query.sites.mixed_site, CachedSpan::MixedSite);
+        spans.insert(query.sites.def_site, CachedSpan::DefSite);
+        spans.insert(query.sites.call_site, CachedSpan::CallSite);
+        let output = match CachedTree::from_stream(output, &mut |span| spans.get(&span).copied()) {
+            Some(output) => output,
+            None => return,
+        };
+
+        let files = record.paths.into_iter().map(|path| {
+            let hash = hash_file(&path);
+            (path, hash)
+        });
+        let entry = Entry { env_vars: record.env_vars, files: files.collect(), output };
+        self.current.lock().insert(query.key, entry);
+    }
+}
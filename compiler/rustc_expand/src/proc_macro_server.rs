@@ -1,4 +1,6 @@
 use crate::base::ExtCtxt;
+use crate::expand::AstFragment;
+use crate::proc_macro_cache::RunRecord;
 
 use rustc_ast as ast;
 use rustc_ast::token;
@@ -14,7 +16,7 @@ use rustc_span::{BytePos, FileName, MultiSpan, Pos, SourceFile, Span};
 
 use pm::bridge::{server, TokenTree};
 use pm::{Delimiter, Level, LineColumn, Spacing};
-use std::ops::Bound;
+use std::ops::{Bound, Range};
 use std::{ascii, panic};
 
 trait FromInternal<T> {
@@ -350,23 +352,50 @@ pub struct Literal {
     span: Span,
 }
 
-pub(crate) struct Rustc<'a> {
-    sess: &'a ParseSess,
+/// The spans returned by `Span::{def_site, call_site, mixed_site}`
+/// for the current expansion.
+crate struct ExpansionSites {
+    crate def_site: Span,
+    crate call_site: Span,
+    crate mixed_site: Span,
+}
+
+impl ExpansionSites {
+    crate fn new(ecx: &ExtCtxt<'_>) -> Self {
+        let expn_data = ecx.current_expansion.id.expn_data();
+        ExpansionSites {
+            def_site: ecx.with_def_site_ctxt(expn_data.def_site),
+            call_site: ecx.with_call_site_ctxt(expn_data.call_site),
+            mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
+        }
+    }
+}
+
+pub(crate) struct Rustc<'a, 'b> {
+    ecx: &'a mut ExtCtxt<'b>,
     def_site: Span,
     call_site: Span,
     mixed_site: Span,
     span_debug: bool,
+    /// Where to record what the proc macro does, if its output may be cached.
+    record: Option<&'a mut RunRecord>,
 }
 
-impl<'a> Rustc<'a> {
-    pub fn new(cx: &'a ExtCtxt<'_>) -> Self {
-        let expn_data = cx.current_expansion.id.expn_data();
-        Rustc {
-            sess: &cx.sess.parse_sess,
-            def_site: cx.with_def_site_ctxt(expn_data.def_site),
-            call_site: cx.with_call_site_ctxt(expn_data.call_site),
-            mixed_site: cx.with_mixed_site_ctxt(expn_data.call_site),
-            span_debug: cx.ecfg.span_debug,
+impl<'a, 'b> Rustc<'a, 'b> {
+    pub fn new(ecx: &'a mut ExtCtxt<'b>, record: Option<&'a mut RunRecord>) -> Self {
+        let ExpansionSites { def_site, call_site, mixed_site } = ExpansionSites::new(ecx);
+        Rustc { def_site, call_site, mixed_site, span_debug: ecx.ecfg.span_debug, ecx, record }
+    }
+
+    fn sess(&self) -> &'b ParseSess {
+        self.ecx.parse_sess()
+    }
+
+    /// Notes that the output of the proc macro can't be cached, because it may depend
+    /// on something other than its inputs, or because it had a side effect.
+    fn uncacheable(&mut self) {
+        if let Some(record) = &mut self.record {
+            record.uncacheable = true;
         }
     }
 
@@ -375,7 +404,7 @@ impl<'a> Rustc<'a> {
     }
 }
 
-impl server::Types for Rustc<'_> {
+impl server::Types for Rustc<'_, '_> {
     type FreeFunctions = FreeFunctions;
     type TokenStream = TokenStream;
     type TokenStreamBuilder = tokenstream::TokenStreamBuilder;
@@ -390,13 +419,32 @@ impl server::Types for Rustc<'_> {
     type Span = Span;
 }
 
-impl server::FreeFunctions for Rustc<'_> {
+impl server::FreeFunctions for Rustc<'_, '_> {
     fn track_env_var(&mut self, var: &str, value: Option<&str>) {
-        self.sess.env_depinfo.borrow_mut().insert((Symbol::intern(var), value.map(Symbol::intern)));
+        if let Some(record) = &mut self.record {
+            record.env_vars.push((var.to_owned(), value.map(str::to_owned)));
+        }
+        self.sess()
+            .env_depinfo
+            .borrow_mut()
+            .insert((Symbol::intern(var), value.map(Symbol::intern)));
+    }
+
+    fn track_path(&mut self, path: &str) {
+        if let Some(record) = &mut self.record {
+            record.paths.push(path.to_owned());
+        }
+        self.sess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
+    }
+
+    fn declare_pure(&mut self) {
+        if let Some(record) = &mut self.record {
+            record.pure = true;
+        }
     }
 }
 
-impl server::TokenStream for Rustc<'_> {
+impl server::TokenStream for Rustc<'_, '_> {
     fn new(&mut self) -> Self::TokenStream {
         TokenStream::default()
     }
@@ -407,13 +455,63 @@ impl server::TokenStream for Rustc<'_> {
         parse_stream_from_source_str(
             FileName::proc_macro_source_code(src),
             src.to_string(),
-            self.sess,
+            self.sess(),
             Some(self.call_site),
         )
     }
     fn to_string(&mut self, stream: &Self::TokenStream) -> String {
         pprust::tts_to_string(stream)
     }
+    fn expand_expr(&mut self, stream: &Self::TokenStream) -> Result<Self::TokenStream, ()> {
+        // The result depends on other macros, which may not be pure.
+        self.uncacheable();
+
+        // Parse the expression from our tokenstream.
+        let subparser_name = Some("proc_macro expand expr");
+        let mut parser = rustc_parse::stream_to_parser(self.sess(), stream.clone(), subparser_name);
+        let expr = match parser.parse_expr() {
+            Ok(expr) if parser.token == token::Eof => expr,
+            Ok(_) => {
+                let msg = format!(
+                    "unexpected token `{}` after expression",
+                    pprust::token_to_string(&parser.token)
+                );
+                parser.struct_span_err(parser.token.span, &msg).emit();
+                return Err(());
+            }
+            Err(mut err) => {
+                err.emit();
+                return Err(());
+            }
+        };
+
+        // Perform eager expansion on the expression.
+        let expr = self.ecx.expander().fully_expand_fragment(AstFragment::Expr(expr)).make_expr();
+
+        // NOTE: For now, limit `expand_expr` to exclusively expand to literals.
+        // This may be relaxed in the future.
+        // We don't use `TokenStream::from_ast` as the tokenstream currently cannot
+        // be recovered in the general case.
+        match &expr.kind {
+            ast::ExprKind::Lit(l) => {
+                Ok(tokenstream::TokenTree::token(token::Literal(l.token), l.span).into())
+            }
+            ast::ExprKind::Unary(ast::UnOp::Neg, e) => match &e.kind {
+                ast::ExprKind::Lit(l) => match l.token {
+                    token::Lit { kind: token::Integer | token::Float, .. } => {
+                        let minus = token::BinOp(token::Minus);
+                        Ok(TokenStream::new(vec![
+                            (tokenstream::TokenTree::token(minus, expr.span), Alone),
+                            (tokenstream::TokenTree::token(token::Literal(l.token), l.span), Alone),
+                        ]))
+                    }
+                    _ => Err(()),
+                },
+                _ => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
     fn from_token_tree(
         &mut self,
         tree: TokenTree<Self::Group, Self::Punct, Self::Ident, Self::Literal>,
@@ -425,7 +523,7 @@ impl server::TokenStream for Rustc<'_> {
     }
 }
 
-impl server::TokenStreamBuilder for Rustc<'_> {
+impl server::TokenStreamBuilder for Rustc<'_, '_> {
     fn new(&mut self) -> Self::TokenStreamBuilder {
         tokenstream::TokenStreamBuilder::new()
     }
@@ -437,7 +535,7 @@ impl server::TokenStreamBuilder for Rustc<'_> {
     }
 }
 
-impl server::TokenStreamIter for Rustc<'_> {
+impl server::TokenStreamIter for Rustc<'_, '_> {
     fn next(
         &mut self,
         iter: &mut Self::TokenStreamIter,
@@ -445,7 +543,7 @@ impl server::TokenStreamIter for Rustc<'_> {
         loop {
             let tree = iter.stack.pop().or_else(|| {
                 let next = iter.cursor.next_with_spacing()?;
-                Some(TokenTree::from_internal((next, self.sess, &mut iter.stack)))
+                Some(TokenTree::from_internal((next, self.sess(), &mut iter.stack)))
             })?;
             // A hack used to pass AST fragments to attribute and derive macros
             // as a single nonterminal token instead of a token stream.
@@ -462,7 +560,7 @@ impl server::TokenStreamIter for Rustc<'_> {
     }
 }
 
-impl server::Group for Rustc<'_> {
+impl server::Group for Rustc<'_, '_> {
     fn new(&mut self, delimiter: Delimiter, stream: Self::TokenStream) -> Self::Group {
         Group {
             delimiter,
@@ -491,7 +589,7 @@ impl server::Group for Rustc<'_> {
     }
 }
 
-impl server::Punct for Rustc<'_> {
+impl server::Punct for Rustc<'_, '_> {
     fn new(&mut self, ch: char, spacing: Spacing) -> Self::Punct {
         Punct::new(ch, spacing == Spacing::Joint, server::Span::call_site(self))
     }
@@ -509,9 +607,9 @@ impl server::Punct for Rustc<'_> {
     }
 }
 
-impl server::Ident for Rustc<'_> {
+impl server::Ident for Rustc<'_, '_> {
     fn new(&mut self, string: &str, span: Self::Span, is_raw: bool) -> Self::Ident {
-        Ident::new(self.sess, Symbol::intern(string), is_raw, span)
+        Ident::new(self.sess(), Symbol::intern(string), is_raw, span)
     }
     fn span(&mut self, ident: Self::Ident) -> Self::Span {
         ident.span
@@ -521,7 +619,7 @@ impl server::Ident for Rustc<'_> {
     }
 }
 
-impl server::Literal for Rustc<'_> {
+impl server::Literal for Rustc<'_, '_> {
     fn debug_kind(&mut self, literal: &Self::Literal) -> String {
         format!("{:?}", literal.lit.kind)
     }
@@ -611,7 +709,7 @@ impl server::Literal for Rustc<'_> {
     }
 }
 
-impl server::SourceFile for Rustc<'_> {
+impl server::SourceFile for Rustc<'_, '_> {
     fn eq(&mut self, file1: &Self::SourceFile, file2: &Self::SourceFile) -> bool {
         Lrc::ptr_eq(file1, file2)
     }
@@ -630,7 +728,7 @@ impl server::SourceFile for Rustc<'_> {
     }
 }
 
-impl server::MultiSpan for Rustc<'_> {
+impl server::MultiSpan for Rustc<'_, '_> {
     fn new(&mut self) -> Self::MultiSpan {
         vec![]
     }
@@ -639,7 +737,7 @@ impl server::MultiSpan for Rustc<'_> {
     }
 }
 
-impl server::Diagnostic for Rustc<'_> {
+impl server::Diagnostic for Rustc<'_, '_> {
     fn new(&mut self, level: Level, msg: &str, spans: Self::MultiSpan) -> Self::Diagnostic {
         let mut diag = Diagnostic::new(level.to_internal(), msg);
         diag.set_span(MultiSpan::from_spans(spans));
@@ -655,12 +753,14 @@ impl server::Diagnostic for Rustc<'_> {
         diag.sub(level.to_internal(), msg, MultiSpan::from_spans(spans), None);
     }
     fn emit(&mut self, diag: Self::Diagnostic) {
-        self.sess.span_diagnostic.emit_diagnostic(&diag);
+        self.uncacheable();
+        self.sess().span_diagnostic.emit_diagnostic(&diag);
     }
 }
 
-impl server::Span for Rustc<'_> {
+impl server::Span for Rustc<'_, '_> {
     fn debug(&mut self, span: Self::Span) -> String {
+        self.uncacheable();
         if self.span_debug {
             format!("{:?}", span)
         } else {
@@ -677,7 +777,8 @@ impl server::Span for Rustc<'_> {
         self.mixed_site
     }
     fn source_file(&mut self, span: Self::Span) -> Self::SourceFile {
-        self.sess.source_map().lookup_char_pos(span.lo()).file
+        self.uncacheable();
+        self.sess().source_map().lookup_char_pos(span.lo()).file
     }
     fn parent(&mut self, span: Self::Span) -> Option<Self::Span> {
         span.parent()
@@ -686,16 +787,25 @@ impl server::Span for Rustc<'_> {
         span.source_callsite()
     }
     fn start(&mut self, span: Self::Span) -> LineColumn {
-        let loc = self.sess.source_map().lookup_char_pos(span.lo());
+        self.uncacheable();
+        let loc = self.sess().source_map().lookup_char_pos(span.lo());
         LineColumn { line: loc.line, column: loc.col.to_usize() }
     }
     fn end(&mut self, span: Self::Span) -> LineColumn {
-        let loc = self.sess.source_map().lookup_char_pos(span.hi());
+        self.uncacheable();
+        let loc = self.sess().source_map().lookup_char_pos(span.hi());
         LineColumn { line: loc.line, column: loc.col.to_usize() }
     }
+    fn byte_range(&mut self, span: Self::Span) -> Range<usize> {
+        self.uncacheable();
+        let source_map = self.sess().source_map();
+        let start = source_map.lookup_byte_offset(span.lo()).pos;
+        let end = source_map.lookup_byte_offset(span.hi()).pos;
+        Range { start: start.to_usize(), end: end.to_usize() }
+    }
     fn join(&mut self, first: Self::Span, second: Self::Span) -> Option<Self::Span> {
-        let self_loc = self.sess.source_map().lookup_char_pos(first.lo());
-        let other_loc = self.sess.source_map().lookup_char_pos(second.lo());
+        let self_loc = self.sess().source_map().lookup_char_pos(first.lo());
+        let other_loc = self.sess().source_map().lookup_char_pos(second.lo());
 
         if self_loc.file.name != other_loc.file.name {
             return None;
@@ -707,6 +817,7 @@ impl server::Span for Rustc<'_> {
         span.with_ctxt(at.ctxt())
     }
     fn source_text(&mut self, span: Self::Span) -> Option<String> {
-        self.sess.source_map().span_to_snippet(span).ok()
+        self.uncacheable();
+        self.sess().source_map().span_to_snippet(span).ok()
     }
 }
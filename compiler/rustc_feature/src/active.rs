@@ -596,6 +596,12 @@ declare_features! (
     /// Allows rustc to inject a default alloc_error_handler
     (active, default_alloc_error_handler, "1.48.0", Some(66741), None),
 
+    /// Allows `#[derive(Default)]` on enums, picking the variant marked with `#[default]`.
+    (active, derive_default_enum, "1.49.0", None, None),
+
+    /// Allows `let...else` statements.
+    (active, let_else, "1.49.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
@@ -1732,6 +1732,9 @@ pub enum MatchSource {
     TryDesugar,
     /// A desugared `<expr>.await`.
     AwaitDesugar,
+    /// A `let _ = _ else { .. };` statement (which was desugared to a
+    /// `match _ { _ => { rest of the block }, _ => { .. } }`).
+    LetElseDesugar,
 }
 
 impl MatchSource {
@@ -1744,6 +1747,7 @@ impl MatchSource {
             ForLoopDesugar => "for",
             TryDesugar => "?",
             AwaitDesugar => ".await",
+            LetElseDesugar => "let...else",
         }
     }
 }
@@ -0,0 +1,147 @@
+//! Implementation of `-Zincremental-explain=<path>`, which tells the user why
+//! the queries and codegen units of a given item could not be reused from the
+//! previous compilation session.
+//!
+//! While trying to mark a node green, the dep-graph records the first
+//! dependency that turned out to be red (see `DepGraph::red_cause`). Starting
+//! from the nodes of the requested item, we follow these causes until we
+//! arrive at a node that was red on its own, usually because its input changed.
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::dep_graph::{DepNode, DepNodeColor, DepNodeExt};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+
+pub fn explain_reexecuted_nodes(tcx: TyCtxt<'_>) {
+    let item_path = match tcx.sess.opts.debugging_opts.incremental_explain {
+        Some(ref item_path) => item_path,
+        None => return,
+    };
+
+    if !tcx.dep_graph.is_fully_enabled() {
+        return;
+    }
+
+    let is_item = |def_id| with_no_trimmed_paths(|| tcx.def_path_str(def_id)) == *item_path;
+
+    let query = tcx.dep_graph.query();
+    let mut dep_nodes: Vec<DepNode> = query
+        .nodes()
+        .into_iter()
+        .filter(|dep_node| dep_node.extract_def_id(tcx).map_or(false, is_item))
+        .cloned()
+        .collect();
+    if dep_nodes.is_empty() {
+        println!("[incremental] explain: no queries were executed for `{}`", item_path);
+        return;
+    }
+
+    // Also explain the codegen units that contain the item, as these are
+    // usually the most expensive part to redo.
+    if tcx.sess.opts.output_types.should_codegen() && !tcx.sess.opts.debugging_opts.no_codegen {
+        let mut seen = FxHashSet::default();
+        for cgu in tcx.collect_and_partition_mono_items(LOCAL_CRATE).1 {
+            let contains_item = cgu.items().keys().any(|mono_item| match *mono_item {
+                MonoItem::Fn(instance) => is_item(instance.def_id()),
+                MonoItem::Static(def_id) => is_item(def_id),
+                MonoItem::GlobalAsm(..) => false,
+            });
+            let dep_node = cgu.codegen_dep_node(tcx);
+            if contains_item && query.contains_node(&dep_node) && seen.insert(dep_node) {
+                tcx.dep_graph.register_dep_node_debug_str(dep_node, || cgu.name().to_string());
+                dep_nodes.push(dep_node);
+            }
+        }
+    }
+
+    let mut reused = 0;
+    for dep_node in &dep_nodes {
+        if !explain_node(tcx, dep_node) {
+            reused += 1;
+        }
+    }
+
+    println!(
+        "[incremental] explain: {} of {} nodes of `{}` were reused",
+        reused,
+        dep_nodes.len(),
+        item_path
+    );
+}
+
+/// Prints why `dep_node` was re-executed. Returns `false` if it was reused instead.
+fn explain_node(tcx: TyCtxt<'_>, dep_node: &DepNode) -> bool {
+    let dep_graph = &tcx.dep_graph;
+
+    if dep_graph.prev_fingerprint_of(dep_node).is_none() {
+        println!(
+            "[incremental] explain: `{}` did not exist in the previous session",
+            describe(tcx, dep_node)
+        );
+        return true;
+    }
+
+    let outcome = match dep_graph.node_color(dep_node) {
+        Some(DepNodeColor::Green(_)) => "its result did not change",
+        Some(DepNodeColor::Red) => "its result changed",
+        None => "it did not finish",
+    };
+
+    let mut cause = match dep_graph.red_cause(dep_node) {
+        Some(cause) => cause,
+        None if dep_node.kind.is_eval_always() => {
+            println!(
+                "[incremental] explain: `{}` is always re-executed, {}",
+                describe(tcx, dep_node),
+                outcome
+            );
+            return true;
+        }
+        // Nodes without dependencies are only re-executed if their input
+        // changed, in which case they are red.
+        None if dep_graph.node_color(dep_node) == Some(DepNodeColor::Red) => {
+            println!("[incremental] explain: the input `{}` changed", describe(tcx, dep_node));
+            return true;
+        }
+        None => return false,
+    };
+
+    println!("[incremental] explain: `{}` was re-executed, {}", describe(tcx, dep_node), outcome);
+
+    // The causes always point to nodes from the previous session, so there
+    // cannot be any cycles, but be defensive anyway.
+    let mut seen = FxHashSet::default();
+    while seen.insert(cause) {
+        let next = dep_graph.red_cause(&cause);
+        let state = match (next, dep_graph.node_color(&cause)) {
+            (Some(_), Some(DepNodeColor::Red)) => "which was re-executed and changed",
+            (Some(_), _) => "which could not be marked green",
+            (None, Some(DepNodeColor::Red)) if cause.kind.is_eval_always() => {
+                "which is always re-executed and changed"
+            }
+            (None, Some(DepNodeColor::Red)) => "which changed",
+            (None, _) => "which could not be recomputed",
+        };
+        println!("[incremental] explain:     depends on `{}`, {}", describe(tcx, &cause), state);
+
+        match next {
+            Some(next) => cause = next,
+            None => break,
+        }
+    }
+
+    true
+}
+
+/// Formats `dep_node` with a def path that is stable across sessions, unlike
+/// its `Debug` implementation which includes the crate disambiguator.
+fn describe(tcx: TyCtxt<'_>, dep_node: &DepNode) -> String {
+    match dep_node.extract_def_id(tcx) {
+        Some(def_id) => {
+            format!("{:?}({})", dep_node.kind, with_no_trimmed_paths(|| tcx.def_path_str(def_id)))
+        }
+        None => format!("{:?}", dep_node),
+    }
+}
@@ -89,6 +89,41 @@
 //! so, it will also place a read lock on that the respective session directory
 //! so that it won't be deleted while the metadata hashes are loaded.
 //!
+//! ## Session Snapshots
+//!
+//! With `-Z incremental-snapshots=<dir>`, a snapshot of every finalized
+//! session directory is additionally published to a snapshot directory that
+//! can be shared between several incremental compilation directories, e.g.
+//! those of different checkouts of the same project or different CI runners.
+//! A snapshot is a complete copy of a session directory, stored as
+//! `{snapshot-dir}/{crate-name}-{disambiguator}/{svh}`. Only sessions of the
+//! same crate, compiled with the same crate disambiguator, can thus ever be
+//! reused (in particular, checkouts at different paths need to pass the same
+//! `-C metadata` and should use `--remap-path-prefix`, or spans will differ).
+//!
+//! Snapshots are only used to seed sessions: when a new session has no
+//! finalized session directory of its own to start from, it copies the most
+//! recent snapshot of the crate instead. From then on, the incremental
+//! directory is used like any other and is not synchronized with the
+//! snapshots anymore, apart from publishing its own. The seeded session has
+//! been produced from some other version of the source code, but this is no
+//! different from starting from an older local session: the dependency graph
+//! will tell which parts of it can be reused.
+//!
+//! Snapshots are never modified, so the snapshot directory needs no locking.
+//! Publishing copies the session directory to a temporary directory first,
+//! which is then atomically renamed to its final name. If there already is a
+//! snapshot for the same SVH, nothing is published at all. Readers that lose a
+//! race against the garbage collection of the snapshots (which keeps only the
+//! most recent snapshots of every crate) simply fail to copy and start out
+//! with an empty session directory.
+//!
+//! Note that the SVH only names and deduplicates snapshots: individual query
+//! results and work products are not shared between snapshots. Any edit to a
+//! crate therefore publishes a complete new snapshot, and a new session is
+//! seeded from a single snapshot, even if several of them together would cover
+//! more of its dependency graph.
+//!
 //! ## Preconditions
 //!
 //! This system relies on two features being available in the file system in
@@ -121,6 +156,9 @@ use rand::{thread_rng, RngCore};
 mod tests;
 
 const LOCK_FILE_EXT: &str = ".lock";
+const SNAPSHOT_TEMP_DIR_PREFIX: &str = "tmp-";
+/// The number of snapshots kept per crate in the snapshot directory.
+const MAX_SNAPSHOTS: usize = 8;
 const DEP_GRAPH_FILENAME: &str = "dep-graph.bin";
 const WORK_PRODUCTS_FILENAME: &str = "work-products.bin";
 const QUERY_CACHE_FILENAME: &str = "query-cache.bin";
@@ -246,13 +284,19 @@ pub fn prepare_session_directory(
         let source_directory = if let Some(dir) = source_directory {
             dir
         } else {
-            // There's nowhere to copy from, we're done
-            debug!(
-                "no source directory found. Continuing with empty session \
-                    directory."
-            );
+            // There's nowhere to copy from locally, but some other incremental
+            // directory might have published a snapshot of a session for this
+            // crate.
+            let copied = seed_from_snapshot(sess, &session_dir, &crate_dir);
+            if !copied {
+                // There's nowhere to copy from, we're done
+                debug!(
+                    "no source directory found. Continuing with empty session \
+                        directory."
+                );
+            }
 
-            sess.init_incr_comp_session(session_dir, directory_lock, false);
+            sess.init_incr_comp_session(session_dir, directory_lock, copied);
             return;
         };
 
@@ -365,6 +409,8 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
         Ok(_) => {
             debug!("finalize_session_directory() - directory renamed successfully");
 
+            publish_snapshot(sess, &new_path, svh);
+
             // This unlocks the directory
             sess.finalize_incr_comp_session(new_path);
         }
@@ -387,12 +433,7 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
 }
 
 pub fn delete_all_session_dir_contents(sess: &Session) -> io::Result<()> {
-    let sess_dir_iterator = sess.incr_comp_session_dir().read_dir()?;
-    for entry in sess_dir_iterator {
-        let entry = entry?;
-        safe_remove_file(&entry.path())?
-    }
-    Ok(())
+    delete_dir_contents(&sess.incr_comp_session_dir())
 }
 
 fn copy_files(sess: &Session, target_dir: &Path, source_dir: &Path) -> Result<bool, ()> {
@@ -412,6 +453,27 @@ fn copy_files(sess: &Session, target_dir: &Path, source_dir: &Path) -> Result<bo
         return Err(());
     };
 
+    let (files_linked, files_copied) = copy_dir_contents(target_dir, source_dir)?;
+
+    if sess.opts.debugging_opts.incremental_info {
+        println!(
+            "[incremental] session directory: \
+                  {} files hard-linked",
+            files_linked
+        );
+        println!(
+            "[incremental] session directory: \
+                 {} files copied",
+            files_copied
+        );
+    }
+
+    Ok(files_linked > 0 || files_copied == 0)
+}
+
+/// Hard-links or copies all files in `source_dir` to `target_dir`, returning
+/// the number of files linked and copied respectively.
+fn copy_dir_contents(target_dir: &Path, source_dir: &Path) -> Result<(usize, usize), ()> {
     let source_dir_iterator = match source_dir.read_dir() {
         Ok(it) => it,
         Err(_) => return Err(()),
@@ -439,20 +501,179 @@ fn copy_files(sess: &Session, target_dir: &Path, source_dir: &Path) -> Result<bo
         }
     }
 
-    if sess.opts.debugging_opts.incremental_info {
-        println!(
-            "[incremental] session directory: \
-                  {} files hard-linked",
-            files_linked
-        );
-        println!(
-            "[incremental] session directory: \
-                 {} files copied",
-            files_copied
-        );
+    Ok((files_linked, files_copied))
+}
+
+/// Initializes the (empty) session directory with the contents of the most
+/// recent snapshot of the crate, if any. Returns whether this succeeded.
+fn seed_from_snapshot(sess: &Session, session_dir: &Path, crate_dir: &Path) -> bool {
+    let snapshot_crate_dir = match snapshot_crate_dir(sess, crate_dir) {
+        Some(dir) => dir,
+        None => return false,
+    };
+
+    let mut candidates = published_snapshots(&snapshot_crate_dir);
+    candidates.sort_by_key(|&(timestamp, _)| std::cmp::Reverse(timestamp));
+
+    for (_, source_directory) in candidates {
+        debug!("attempting to copy data from snapshot: {}", source_directory.display());
+
+        match copy_dir_contents(session_dir, &source_directory) {
+            Ok((files_linked, files_copied)) => {
+                if sess.opts.debugging_opts.incremental_info {
+                    println!(
+                        "[incremental] session directory seeded from snapshot `{}` \
+                              ({} files hard-linked, {} files copied)",
+                        source_directory.display(),
+                        files_linked,
+                        files_copied
+                    );
+                }
+                return true;
+            }
+            Err(()) => {
+                // The snapshot has probably been garbage collected while we
+                // were copying from it. Clear out whatever we got so far and
+                // try the next one.
+                debug!("copying from snapshot failed - trying next snapshot");
+                if delete_dir_contents(session_dir).is_err() {
+                    return false;
+                }
+            }
+        }
     }
 
-    Ok(files_linked > 0 || files_copied == 0)
+    false
+}
+
+/// Publishes a snapshot of the finalized session directory, unless there
+/// already is a snapshot for the same SVH.
+fn publish_snapshot(sess: &Session, session_dir: &Path, svh: Svh) {
+    let snapshot_crate_dir = match snapshot_crate_dir(sess, session_dir.parent().unwrap()) {
+        Some(dir) => dir,
+        None => return,
+    };
+
+    let published_dir =
+        snapshot_crate_dir.join(base_n::encode(svh.as_u64() as u128, INT_ENCODE_BASE));
+    if published_dir.exists() {
+        debug!("publish_snapshot() - already published: {}", published_dir.display());
+        return;
+    }
+
+    // Copy everything to a temporary directory first, so that no other
+    // process ever sees a partially published snapshot.
+    let temp_dir = snapshot_crate_dir.join(format!(
+        "{}{}",
+        SNAPSHOT_TEMP_DIR_PREFIX,
+        base_n::encode(thread_rng().next_u32() as u128, INT_ENCODE_BASE)
+    ));
+
+    let result = std_fs::create_dir_all(&temp_dir)
+        .map_err(|err| err.to_string())
+        .and_then(|()| {
+            copy_dir_contents(&temp_dir, session_dir)
+                .map_err(|()| "could not copy session directory".to_string())
+        })
+        .and_then(|_| std_fs::rename(&temp_dir, &published_dir).map_err(|err| err.to_string()));
+
+    match result {
+        Ok(()) => {
+            if sess.opts.debugging_opts.incremental_info {
+                println!(
+                    "[incremental] session directory published as snapshot `{}`",
+                    published_dir.display()
+                );
+            }
+        }
+        Err(err) => {
+            let _ = safe_remove_dir_all(&temp_dir);
+
+            // Somebody else publishing the same SVH concurrently is not a
+            // problem, we only lost the race.
+            if !published_dir.exists() {
+                sess.warn(&format!(
+                    "Error publishing snapshot `{}` of incremental compilation \
+                                   session directory: {}",
+                    published_dir.display(),
+                    err
+                ));
+            }
+            return;
+        }
+    }
+
+    garbage_collect_snapshots(&snapshot_crate_dir);
+}
+
+/// Returns the directory for the snapshots of the crate with the given local
+/// crate directory, if snapshots are enabled.
+fn snapshot_crate_dir(sess: &Session, crate_dir: &Path) -> Option<PathBuf> {
+    let snapshot_dir = sess.opts.debugging_opts.incremental_snapshots.as_ref()?;
+    Some(snapshot_dir.join(crate_dir.file_name()?))
+}
+
+/// Lists the snapshots in the snapshot directory of a crate, along with their
+/// modification times.
+fn published_snapshots(snapshot_crate_dir: &Path) -> Vec<(SystemTime, PathBuf)> {
+    let dir_iterator = match snapshot_crate_dir.read_dir() {
+        Ok(it) => it,
+        Err(_) => return vec![],
+    };
+
+    dir_iterator
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with(SNAPSHOT_TEMP_DIR_PREFIX))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_dir() {
+                return None;
+            }
+            Some((metadata.modified().ok()?, entry.path()))
+        })
+        .collect()
+}
+
+/// Deletes all but the most recent snapshots of a crate, as well as temporary
+/// directories left behind by crashed processes. Errors are ignored, the next process will try again.
+fn garbage_collect_snapshots(snapshot_crate_dir: &Path) {
+    let mut published = published_snapshots(snapshot_crate_dir);
+    published.sort_by_key(|&(timestamp, _)| std::cmp::Reverse(timestamp));
+
+    for (_, path) in published.into_iter().skip(MAX_SNAPSHOTS) {
+        debug!("garbage_collect_snapshots() - deleting `{}`", path.display());
+        let _ = safe_remove_dir_all(&path);
+    }
+
+    let dir_iterator = match snapshot_crate_dir.read_dir() {
+        Ok(it) => it,
+        Err(_) => return,
+    };
+
+    for entry in dir_iterator.filter_map(|entry| entry.ok()) {
+        if !entry.file_name().to_string_lossy().starts_with(SNAPSHOT_TEMP_DIR_PREFIX) {
+            continue;
+        }
+
+        // Publishing might legitimately take a while, so only collect
+        // temporary directories that have been around for a long time.
+        let abandoned = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .map(|timestamp| timestamp < SystemTime::now() - Duration::from_secs(60 * 60))
+            .unwrap_or(false);
+        if abandoned {
+            debug!("garbage_collect_snapshots() - deleting `{}`", entry.path().display());
+            let _ = safe_remove_dir_all(&entry.path());
+        }
+    }
+}
+
+fn delete_dir_contents(dir: &Path) -> io::Result<()> {
+    for entry in dir.read_dir()? {
+        safe_remove_file(&entry?.path())?
+    }
+    Ok(())
 }
 
 /// Generates unique directory path of the form:
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 
 use super::data::*;
 use super::dirty_clean;
+use super::explain;
 use super::file_format;
 use super::fs::*;
 use super::work_product;
@@ -45,6 +46,7 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
         );
 
         dirty_clean::check_dirty_clean_annotations(tcx);
+        explain::explain_reexecuted_nodes(tcx);
     })
 }
 
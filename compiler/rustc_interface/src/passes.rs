@@ -11,6 +11,7 @@ use rustc_data_structures::temp_dir::MaybeTempDir;
 use rustc_data_structures::{box_region_allow_access, declare_box_region_type, parallel};
 use rustc_errors::{ErrorReported, PResult};
 use rustc_expand::base::ExtCtxt;
+use rustc_expand::proc_macro_cache::ProcMacroCache;
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_hir::definitions::Definitions;
 use rustc_hir::Crate;
@@ -297,10 +298,15 @@ fn configure_and_expand_inner<'a>(
 
         let extern_mod_loaded = |k: &ast::Crate| pre_expansion_lint(sess, lint_store, k);
         let mut ecx = ExtCtxt::new(&sess, cfg, &mut resolver, Some(&extern_mod_loaded));
+        ecx.proc_macro_cache = ProcMacroCache::load(sess).map(Lrc::new);
 
         // Expand macros now!
         let krate = sess.time("expand_crate", || ecx.monotonic_expander().expand_crate(krate));
 
+        if let Some(proc_macro_cache) = &ecx.proc_macro_cache {
+            sess.time("save_proc_macro_cache", || proc_macro_cache.save(sess));
+        }
+
         // The rest is error reporting
 
         sess.time("check_unused_macros", || {
@@ -551,6 +557,18 @@ fn write_out_deps(
             .map(|fmap| escape_dep_filename(&fmap.unmapped_path.as_ref().unwrap_or(&fmap.name)))
             .collect();
 
+        // Account for explicitly marked-to-track files
+        // (e.g. accessed in proc macros).
+        let file_depinfo = sess.parse_sess.file_depinfo.borrow();
+        let mut extra_tracked_files: Vec<_> = file_depinfo
+            .iter()
+            .map(|path| escape_dep_filename(&FileName::from(PathBuf::from(&*path.as_str()))))
+            .filter(|path| !files.contains(path))
+            .collect();
+        extra_tracked_files.sort_unstable();
+        extra_tracked_files.dedup();
+        files.extend(extra_tracked_files);
+
         if sess.binary_dep_depinfo() {
             boxed_resolver.borrow().borrow_mut().access(|resolver| {
                 for cnum in resolver.cstore().crates_untracked() {
@@ -620,6 +638,45 @@ fn write_out_deps(
     }
 }
 
+fn write_out_expanded(
+    sess: &Session,
+    compiler: &Compiler,
+    krate: &ast::Crate,
+    boxed_resolver: &Steal<Rc<RefCell<BoxedResolver>>>,
+    outputs: &OutputFilenames,
+) {
+    if !sess.opts.output_types.contains_key(&OutputType::Expanded) {
+        return;
+    }
+    let expanded_filename = outputs.path(OutputType::Expanded);
+
+    let src_name = compiler.input.source_name();
+    let src = sess
+        .source_map()
+        .get_source_file(&src_name)
+        .and_then(|file| file.src.as_ref().map(|src| String::clone(src)))
+        .unwrap_or_default();
+    let expanded = boxed_resolver
+        .borrow()
+        .borrow_mut()
+        .access(|resolver| resolver.print_expanded_crate(krate, src_name, src));
+
+    match fs::write(&expanded_filename, expanded) {
+        Ok(()) => {
+            if sess.opts.json_artifact_notifications {
+                sess.parse_sess
+                    .span_diagnostic
+                    .emit_artifact_notification(&expanded_filename, "expanded");
+            }
+        }
+        Err(e) => sess.fatal(&format!(
+            "error writing expanded crate to `{}`: {}",
+            expanded_filename.display(),
+            e
+        )),
+    }
+}
+
 pub fn prepare_outputs(
     sess: &Session,
     compiler: &Compiler,
@@ -678,6 +735,8 @@ pub fn prepare_outputs(
         }
     }
 
+    write_out_expanded(sess, compiler, krate, boxed_resolver, &outputs);
+
     Ok(outputs)
 }
 
@@ -468,6 +468,7 @@ fn test_debugging_options_tracking_hash() {
     untracked!(ast_json_noexpand, true);
     untracked!(borrowck, String::from("other"));
     untracked!(borrowck_stats, true);
+    untracked!(cache_proc_macros, true);
     untracked!(deduplicate_diagnostics, true);
     untracked!(dep_tasks, true);
     untracked!(dont_buffer_diagnostics, true);
@@ -480,8 +481,10 @@ fn test_debugging_options_tracking_hash() {
     untracked!(emit_stack_sizes, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
+    untracked!(incremental_explain, Some(String::from("foo::bar")));
     untracked!(incremental_ignore_spans, true);
     untracked!(incremental_info, true);
+    untracked!(incremental_snapshots, Some(PathBuf::from("incr-snapshots")));
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
     untracked!(keep_hygiene_data, true);
@@ -505,6 +508,7 @@ fn test_debugging_options_tracking_hash() {
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
     untracked!(proc_macro_backtrace, true);
+    untracked!(proc_macro_out_of_process, true);
     untracked!(query_dep_graph, true);
     untracked!(query_stats, true);
     untracked!(save_analysis, true);
@@ -519,6 +523,7 @@ fn test_debugging_options_tracking_hash() {
     untracked!(time_llvm_passes, true);
     untracked!(time_passes, true);
     untracked!(trace_macros, true);
+    untracked!(trace_macros_json, true);
     untracked!(trim_diagnostic_paths, false);
     untracked!(ui_testing, true);
     untracked!(unpretty, Some("expanded".to_string()));
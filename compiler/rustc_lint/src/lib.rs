@@ -63,9 +63,9 @@ use rustc_hir::def_id::LocalDefId;
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::lint::builtin::{
-    BARE_TRAIT_OBJECTS, BROKEN_INTRA_DOC_LINKS, ELIDED_LIFETIMES_IN_PATHS,
+    BARE_TRAIT_OBJECTS, BROKEN_INTRA_DOC_LINKS, CONTRADICTORY_DOC_CFG, ELIDED_LIFETIMES_IN_PATHS,
     EXPLICIT_OUTLIVES_REQUIREMENTS, INVALID_CODEBLOCK_ATTRIBUTES, MISSING_DOC_CODE_EXAMPLES,
-    PRIVATE_DOC_TESTS,
+    MISSING_DOC_SECTIONS, PRIVATE_DOC_TESTS, UNUSED_DOC_SECTIONS,
 };
 use rustc_span::symbol::{Ident, Symbol};
 use rustc_span::Span;
@@ -311,7 +311,10 @@ fn register_builtins(store: &mut LintStore, no_interleave_lints: bool) {
         PRIVATE_INTRA_DOC_LINKS,
         INVALID_CODEBLOCK_ATTRIBUTES,
         MISSING_DOC_CODE_EXAMPLES,
-        PRIVATE_DOC_TESTS
+        PRIVATE_DOC_TESTS,
+        MISSING_DOC_SECTIONS,
+        UNUSED_DOC_SECTIONS,
+        CONTRADICTORY_DOC_CFG
     );
 
     // Register renamed and removed lints.
@@ -1,7 +1,7 @@
 use crate::Lint;
 use crate::{EarlyContext, EarlyLintPass, LateContext, LateLintPass, LintContext};
 use rustc_ast as ast;
-use rustc_ast::util::parser;
+use rustc_ast::util::{classify, parser};
 use rustc_ast::{ExprKind, StmtKind};
 use rustc_ast_pretty::pprust;
 use rustc_data_structures::fx::FxHashMap;
@@ -665,6 +665,12 @@ trait UnusedDelimLint {
         match s.kind {
             StmtKind::Local(ref local) if Self::LINT_EXPR_IN_PATTERN_MATCHING_CTX => {
                 if let Some(ref value) = local.init {
+                    // The initializer of a `let...else` must not end with `}`.
+                    if let (Some(_), ExprKind::Paren(ref inner)) = (&local.els, &value.kind) {
+                        if !classify::expr_requires_semi_to_be_stmt(inner) {
+                            return;
+                        }
+                    }
                     self.check_unused_delims_expr(
                         cx,
                         &value,
@@ -2,6 +2,7 @@
 
 use crate::dynamic_lib::DynamicLibrary;
 use crate::locator::{CrateError, CrateLocator, CratePaths};
+use crate::proc_macro_process::{self, RemoteProcMacroCrate};
 use crate::rmeta::{CrateDep, CrateMetadata, CrateNumMap, CrateRoot, MetadataBlob};
 
 use rustc_ast::expand::allocator::AllocatorKind;
@@ -20,13 +21,14 @@ use rustc_session::config::{self, CrateType, ExternLocation};
 use rustc_session::lint;
 use rustc_session::output::validate_crate_name;
 use rustc_session::search_paths::PathKind;
-use rustc_session::{CrateDisambiguator, Session};
+use rustc_session::Session;
 use rustc_span::edition::Edition;
 use rustc_span::symbol::{sym, Symbol};
 use rustc_span::{Span, DUMMY_SP};
 use rustc_target::spec::{PanicStrategy, TargetTriple};
 
 use proc_macro::bridge::client::ProcMacro;
+use rustc_expand::proc_macro::ProcMacroServer;
 use std::path::Path;
 use std::{cmp, env, fs};
 use tracing::{debug, info};
@@ -40,6 +42,9 @@ pub struct CStore {
     allocator_kind: Option<AllocatorKind>,
     /// This crate has a `#[global_allocator]` item.
     has_global_allocator: bool,
+    /// The server running proc macros with `-Z proc-macro-out-of-process`,
+    /// once there has been a need for it.
+    proc_macro_server: Option<Lrc<ProcMacroServer>>,
 }
 
 pub struct CrateLoader<'a> {
@@ -205,6 +210,7 @@ impl<'a> CrateLoader<'a> {
                 injected_panic_runtime: None,
                 allocator_kind: None,
                 has_global_allocator: false,
+                proc_macro_server: None,
             },
             used_extern_options: Default::default(),
         }
@@ -350,7 +356,7 @@ impl<'a> CrateLoader<'a> {
 
         let cnum_map = self.resolve_crate_deps(root, &crate_root, &metadata, cnum, dep_kind)?;
 
-        let raw_proc_macros = if crate_root.is_proc_macro_crate() {
+        let (raw_proc_macros, remote_proc_macros) = if crate_root.is_proc_macro_crate() {
             let temp_root;
             let (dlsym_source, dlsym_root) = match &host_lib {
                 Some(host_lib) => (&host_lib.source, {
@@ -360,9 +366,17 @@ impl<'a> CrateLoader<'a> {
                 None => (&source, &crate_root),
             };
             let dlsym_dylib = dlsym_source.dylib.as_ref().expect("no dylib for a proc-macro crate");
-            Some(self.dlsym_proc_macros(&dlsym_dylib.0, dlsym_root.disambiguator())?)
+            // Make sure the path contains a / or the linker will search for it.
+            let dlsym_path = env::current_dir().unwrap().join(&dlsym_dylib.0);
+            let sym = self.sess.generate_proc_macro_decls_symbol(dlsym_root.disambiguator());
+            if self.sess.opts.debugging_opts.proc_macro_out_of_process {
+                let server = self.proc_macro_server();
+                (None, Some(RemoteProcMacroCrate::load(server, &dlsym_path, &sym)?))
+            } else {
+                (Some(dlsym_proc_macros(&dlsym_path, &sym)?), None)
+            }
         } else {
-            None
+            (None, None)
         };
 
         let crate_metadata = CrateMetadata::new(
@@ -370,6 +384,7 @@ impl<'a> CrateLoader<'a> {
             metadata,
             crate_root,
             raw_proc_macros,
+            remote_proc_macros,
             cnum,
             cnum_map,
             dep_kind,
@@ -599,32 +614,13 @@ impl<'a> CrateLoader<'a> {
         Ok(crate_num_map)
     }
 
-    fn dlsym_proc_macros(
-        &self,
-        path: &Path,
-        disambiguator: CrateDisambiguator,
-    ) -> Result<&'static [ProcMacro], CrateError> {
-        // Make sure the path contains a / or the linker will search for it.
-        let path = env::current_dir().unwrap().join(path);
-        let lib = match DynamicLibrary::open(&path) {
-            Ok(lib) => lib,
-            Err(s) => return Err(CrateError::DlOpen(s)),
-        };
-
-        let sym = self.sess.generate_proc_macro_decls_symbol(disambiguator);
-        let decls = unsafe {
-            let sym = match lib.symbol(&sym) {
-                Ok(f) => f,
-                Err(s) => return Err(CrateError::DlSym(s)),
-            };
-            *(sym as *const &[ProcMacro])
-        };
-
-        // Intentionally leak the dynamic library. We can't ever unload it
-        // since the library can make things that will live arbitrarily long.
-        std::mem::forget(lib);
-
-        Ok(decls)
+    /// Returns the proc macro server to run proc macros in. Its process
+    /// is only started once a proc macro crate is loaded.
+    fn proc_macro_server(&mut self) -> Lrc<ProcMacroServer> {
+        self.cstore
+            .proc_macro_server
+            .get_or_insert_with(|| Lrc::new(proc_macro_process::server()))
+            .clone()
     }
 
     fn inject_panic_runtime(&mut self, krate: &ast::Crate) {
@@ -978,3 +974,24 @@ fn global_allocator_spans(sess: &Session, krate: &ast::Crate) -> Vec<Span> {
     visit::walk_crate(&mut f, krate);
     f.spans
 }
+
+crate fn dlsym_proc_macros(path: &Path, sym: &str) -> Result<&'static [ProcMacro], CrateError> {
+    let lib = match DynamicLibrary::open(path) {
+        Ok(lib) => lib,
+        Err(s) => return Err(CrateError::DlOpen(s)),
+    };
+
+    let decls = unsafe {
+        let sym = match lib.symbol(sym) {
+            Ok(f) => f,
+            Err(s) => return Err(CrateError::DlSym(s)),
+        };
+        *(sym as *const &[ProcMacro])
+    };
+
+    // Intentionally leak the dynamic library. We can't ever unload it
+    // since the library can make things that will live arbitrarily long.
+    std::mem::forget(lib);
+
+    Ok(decls)
+}
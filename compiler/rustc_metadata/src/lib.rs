@@ -34,3 +34,4 @@ mod rmeta;
 pub mod creader;
 pub mod dynamic_lib;
 pub mod locator;
+pub mod proc_macro_process;
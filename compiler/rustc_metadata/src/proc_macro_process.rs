@@ -0,0 +1,198 @@
+//! Support for `-Z proc-macro-out-of-process`, which runs proc macros in a
+//! separate "proc macro server" process, so that crashes, leaks and global
+//! state in proc macros can't affect the compiler itself.
+//!
+//! The proc macro server is the `rustc` executable of the sysroot the compiler
+//! was started from (rather than the current executable, which may be another
+//! driver built on `rustc_driver`, like rustdoc or clippy), started with
+//! `RUSTC_PROC_MACRO_SERVER` set, which makes `maybe_serve` (called at the very
+//! start of `rustc_driver::main`) handle requests instead of compiling.
+//!
+//! Proc macro crates are never loaded by the compiler itself: it asks the server
+//! for the names and kinds of their proc macros, and only the server runs them.
+
+use crate::creader;
+use crate::locator::CrateError;
+
+use proc_macro::bridge::client::ProcMacro;
+use proc_macro::bridge::process::ProcMacroDecl;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_expand::proc_macro::{ProcMacroServer, RemoteProcMacro};
+use rustc_session::filesearch;
+
+use std::env;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+const SERVER_ENV_VAR: &str = "RUSTC_PROC_MACRO_SERVER";
+
+crate fn server() -> ProcMacroServer {
+    let rustc = filesearch::get_or_default_sysroot()
+        .join("bin")
+        .join(format!("rustc{}", env::consts::EXE_SUFFIX));
+    ProcMacroServer::new(move || {
+        let mut command = Command::new(&rustc);
+        command.env(SERVER_ENV_VAR, "1");
+        command
+    })
+}
+
+/// The proc macros of a crate, as listed by the proc macro server, along with
+/// what the server needs to find them again, i.e. the path of the crate's dylib
+/// and the symbol its proc macros are in.
+crate struct RemoteProcMacroCrate {
+    server: Lrc<ProcMacroServer>,
+    locator: Lrc<[u8]>,
+    decls: Vec<ProcMacroDecl>,
+}
+
+impl RemoteProcMacroCrate {
+    /// Has the proc macro server load the proc macros in `dylib`, failing
+    /// the same way as loading them in this process would.
+    crate fn load(
+        server: Lrc<ProcMacroServer>,
+        dylib: &Path,
+        decls_symbol: &str,
+    ) -> Result<RemoteProcMacroCrate, CrateError> {
+        let dylib = dylib.to_str().ok_or_else(|| {
+            CrateError::DlOpen(format!(
+                "`{}` can't be sent to the proc macro server",
+                dylib.display()
+            ))
+        })?;
+        let locator: Lrc<[u8]> = format!("{}\0{}", dylib, decls_symbol).into_bytes().into();
+        match server.list(&locator) {
+            Ok(Ok(decls)) => Ok(RemoteProcMacroCrate { server, locator, decls }),
+            Ok(Err(e)) => Err(CrateError::DlOpen(e)),
+            Err(e) => Err(CrateError::DlOpen(e.to_string())),
+        }
+    }
+
+    /// The proc macros of the crate, in the order of its `&[ProcMacro]` symbol.
+    crate fn decls(&self) -> &[ProcMacroDecl] {
+        &self.decls
+    }
+
+    /// Returns the `index`-th proc macro of the crate.
+    crate fn proc_macro(&self, index: usize) -> RemoteProcMacro {
+        RemoteProcMacro {
+            server: self.server.clone(),
+            locator: self.locator.clone(),
+            index: index as u32,
+        }
+    }
+}
+
+fn decode_locator(locator: &[u8]) -> Option<(PathBuf, String)> {
+    let locator = std::str::from_utf8(locator).ok()?;
+    let mut parts = locator.splitn(2, '\0');
+    let dylib = PathBuf::from(parts.next()?);
+    let decls_symbol = parts.next()?.to_owned();
+    Some((dylib, decls_symbol))
+}
+
+/// Takes over the standard input and output of the process to talk to the
+/// compiler, replacing them with `/dev/null` and standard error, so that proc
+/// macros using them can't get in the way of the protocol.
+#[cfg(unix)]
+fn take_stdio() -> io::Result<(File, File)> {
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+
+    unsafe {
+        let input = libc::fcntl(libc::STDIN_FILENO, libc::F_DUPFD_CLOEXEC, 3);
+        if input < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let input = File::from_raw_fd(input);
+        let output = libc::fcntl(libc::STDOUT_FILENO, libc::F_DUPFD_CLOEXEC, 3);
+        if output < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let output = File::from_raw_fd(output);
+
+        let null = File::open("/dev/null")?;
+        if libc::dup2(null.as_raw_fd(), libc::STDIN_FILENO) < 0
+            || libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((input, output))
+    }
+}
+
+#[cfg(windows)]
+fn take_stdio() -> io::Result<(File, File)> {
+    use std::os::windows::io::FromRawHandle;
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+    use winapi::um::processenv::{GetStdHandle, SetStdHandle};
+    use winapi::um::winbase::{STD_ERROR_HANDLE, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE};
+
+    unsafe {
+        let input = GetStdHandle(STD_INPUT_HANDLE);
+        let output = GetStdHandle(STD_OUTPUT_HANDLE);
+        if input == INVALID_HANDLE_VALUE || output == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        // The standard library reads the standard handles on every use, and
+        // treats a missing standard input as an empty one.
+        if SetStdHandle(STD_INPUT_HANDLE, INVALID_HANDLE_VALUE) == 0
+            || SetStdHandle(STD_OUTPUT_HANDLE, GetStdHandle(STD_ERROR_HANDLE)) == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((File::from_raw_handle(input as _), File::from_raw_handle(output as _)))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn take_stdio() -> io::Result<(File, File)> {
+    Err(io::Error::new(io::ErrorKind::Other, "unsupported on this platform"))
+}
+
+/// If this process was started as a proc macro server, serves requests
+/// from the compiler until it goes away, then exits.
+pub fn maybe_serve() {
+    if env::var_os(SERVER_ENV_VAR).is_none() {
+        return;
+    }
+    // Don't make processes started by proc macros proc macro servers too.
+    env::remove_var(SERVER_ENV_VAR);
+
+    let (input, output) = match take_stdio() {
+        Ok(stdio) => stdio,
+        Err(e) => {
+            eprintln!("proc macro server failed to set up its standard streams: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut loaded: FxHashMap<Vec<u8>, &'static [ProcMacro]> = Default::default();
+    let result = proc_macro::bridge::process::serve(
+        io::BufReader::new(input),
+        io::BufWriter::new(output),
+        |locator| {
+            if let Some(decls) = loaded.get(locator) {
+                return Ok(*decls);
+            }
+            let (dylib, decls_symbol) =
+                decode_locator(locator).ok_or_else(|| "malformed proc macro locator".to_owned())?;
+            let decls = creader::dlsym_proc_macros(&dylib, &decls_symbol).map_err(|e| match e {
+                CrateError::DlOpen(s) | CrateError::DlSym(s) => s,
+                _ => unreachable!(),
+            })?;
+            loaded.insert(locator.to_owned(), decls);
+            Ok(decls)
+        },
+    );
+
+    match result {
+        Ok(()) => process::exit(0),
+        Err(e) => {
+            eprintln!("proc macro server failed: {}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // Decoding metadata from a single crate's metadata
 
 use crate::creader::CrateMetadataRef;
+use crate::proc_macro_process::RemoteProcMacroCrate;
 use crate::rmeta::table::{FixedSizeEncoding, Table};
 use crate::rmeta::*;
 
@@ -13,7 +14,8 @@ use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::{AtomicCell, Lock, LockGuard, Lrc, OnceCell};
 use rustc_errors::ErrorReported;
 use rustc_expand::base::{SyntaxExtension, SyntaxExtensionKind};
-use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, ProcMacroDerive};
+use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, ProcMacroClient, ProcMacroDerive};
+use rustc_expand::proc_macro_cache::ProcMacroId;
 use rustc_hir as hir;
 use rustc_hir::def::{CtorKind, CtorOf, DefKind, Res};
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, CRATE_DEF_INDEX, LOCAL_CRATE};
@@ -37,6 +39,7 @@ use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_span::{self, hygiene::MacroKind, BytePos, ExpnId, Pos, Span, SyntaxContext, DUMMY_SP};
 
 use proc_macro::bridge::client::ProcMacro;
+use proc_macro::bridge::process::ProcMacroDecl;
 use std::cell::Cell;
 use std::io;
 use std::mem;
@@ -75,6 +78,9 @@ crate struct CrateMetadata {
         FxHashMap<(u32, DefIndex), Lazy<[(DefIndex, Option<ty::fast_reject::SimplifiedType>)]>>,
     /// Proc macro descriptions for this crate, if it's a proc macro crate.
     raw_proc_macros: Option<&'static [ProcMacro]>,
+    /// How to run the proc macros of this crate in the proc macro server,
+    /// if they're run out of process (see `-Z proc-macro-out-of-process`).
+    remote_proc_macros: Option<RemoteProcMacroCrate>,
     /// Source maps for code from the crate.
     source_map_import_info: OnceCell<Vec<ImportedSourceFile>>,
     /// Used for decoding interpret::AllocIds in a cached & thread-safe manner.
@@ -730,19 +736,41 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
         })
     }
 
-    fn raw_proc_macro(&self, id: DefIndex) -> &ProcMacro {
+    fn proc_macro_position(&self, id: DefIndex) -> usize {
         // DefIndex's in root.proc_macro_data have a one-to-one correspondence
         // with items in 'raw_proc_macros'.
-        let pos = self
-            .root
+        self.root
             .proc_macro_data
             .as_ref()
             .unwrap()
             .macros
             .decode(self)
             .position(|i| i == id)
-            .unwrap();
-        &self.raw_proc_macros.unwrap()[pos]
+            .unwrap()
+    }
+
+    fn proc_macro_name(&self, id: DefIndex) -> &str {
+        let pos = self.proc_macro_position(id);
+        match &self.remote_proc_macros {
+            Some(remote) => remote.decls()[pos].name(),
+            None => self.raw_proc_macros.unwrap()[pos].name(),
+        }
+    }
+
+    fn proc_macro_kind(&self, id: DefIndex) -> MacroKind {
+        let pos = self.proc_macro_position(id);
+        match &self.remote_proc_macros {
+            Some(remote) => match remote.decls()[pos] {
+                ProcMacroDecl::CustomDerive { .. } => MacroKind::Derive,
+                ProcMacroDecl::Attr { .. } => MacroKind::Attr,
+                ProcMacroDecl::Bang { .. } => MacroKind::Bang,
+            },
+            None => match self.raw_proc_macros.unwrap()[pos] {
+                ProcMacro::CustomDerive { .. } => MacroKind::Derive,
+                ProcMacro::Attr { .. } => MacroKind::Attr,
+                ProcMacro::Bang { .. } => MacroKind::Bang,
+            },
+        }
     }
 
     fn item_ident(&self, item_index: DefIndex, sess: &Session) -> Ident {
@@ -763,7 +791,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
             Ident::new(name, span)
         } else {
             Ident::new(
-                Symbol::intern(self.raw_proc_macro(item_index).name()),
+                Symbol::intern(self.proc_macro_name(item_index)),
                 self.get_span(item_index, sess),
             )
         }
@@ -773,7 +801,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
         if !self.is_proc_macro(index) {
             self.kind(index).def_kind()
         } else {
-            DefKind::Macro(macro_kind(self.raw_proc_macro(index)))
+            DefKind::Macro(self.proc_macro_kind(index))
         }
     }
 
@@ -787,22 +815,48 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
     }
 
     fn load_proc_macro(&self, id: DefIndex, sess: &Session) -> SyntaxExtension {
-        let (name, kind, helper_attrs) = match *self.raw_proc_macro(id) {
-            ProcMacro::CustomDerive { trait_name, attributes, client } => {
-                let helper_attrs =
-                    attributes.iter().cloned().map(Symbol::intern).collect::<Vec<_>>();
-                (
-                    trait_name,
-                    SyntaxExtensionKind::Derive(Box::new(ProcMacroDerive { client })),
-                    helper_attrs,
-                )
-            }
-            ProcMacro::Attr { name, client } => {
-                (name, SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client })), Vec::new())
-            }
-            ProcMacro::Bang { name, client } => {
-                (name, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })), Vec::new())
+        let name = Symbol::intern(self.proc_macro_name(id));
+        let cache_id = Some(ProcMacroId { crate_hash: self.hash(), name });
+        let pos = self.proc_macro_position(id);
+        let (kind, helper_attrs) = match &self.remote_proc_macros {
+            Some(remote) => {
+                let client = || ProcMacroClient::OutOfProcess(remote.proc_macro(pos));
+                match &remote.decls()[pos] {
+                    ProcMacroDecl::CustomDerive { attributes, .. } => {
+                        let helper_attrs =
+                            attributes.iter().map(|attr| Symbol::intern(attr)).collect::<Vec<_>>();
+                        let expander = ProcMacroDerive { client: client(), cache_id };
+                        (SyntaxExtensionKind::Derive(Box::new(expander)), helper_attrs)
+                    }
+                    ProcMacroDecl::Attr { .. } => {
+                        let expander = AttrProcMacro { client: client(), cache_id };
+                        (SyntaxExtensionKind::Attr(Box::new(expander)), Vec::new())
+                    }
+                    ProcMacroDecl::Bang { .. } => {
+                        let expander = BangProcMacro { client: client(), cache_id };
+                        (SyntaxExtensionKind::Bang(Box::new(expander)), Vec::new())
+                    }
+                }
             }
+            None => match self.raw_proc_macros.unwrap()[pos] {
+                ProcMacro::CustomDerive { attributes, client, .. } => {
+                    let helper_attrs =
+                        attributes.iter().cloned().map(Symbol::intern).collect::<Vec<_>>();
+                    let client = ProcMacroClient::InProcess(client);
+                    let expander = ProcMacroDerive { client, cache_id };
+                    (SyntaxExtensionKind::Derive(Box::new(expander)), helper_attrs)
+                }
+                ProcMacro::Attr { client, .. } => {
+                    let client = ProcMacroClient::InProcess(client);
+                    let expander = AttrProcMacro { client, cache_id };
+                    (SyntaxExtensionKind::Attr(Box::new(expander)), Vec::new())
+                }
+                ProcMacro::Bang { client, .. } => {
+                    let client = ProcMacroClient::InProcess(client);
+                    let expander = BangProcMacro { client, cache_id };
+                    (SyntaxExtensionKind::Bang(Box::new(expander)), Vec::new())
+                }
+            },
         };
 
         SyntaxExtension::new(
@@ -811,7 +865,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
             self.get_span(id, sess),
             helper_attrs,
             self.root.edition,
-            Symbol::intern(name),
+            name,
             &self.get_item_attrs(id, sess),
         )
     }
@@ -1076,9 +1130,8 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
             if id == CRATE_DEF_INDEX {
                 let macros = data.macros.decode(self);
                 for def_index in macros {
-                    let raw_macro = self.raw_proc_macro(def_index);
                     let res = Res::Def(
-                        DefKind::Macro(macro_kind(raw_macro)),
+                        DefKind::Macro(self.proc_macro_kind(def_index)),
                         self.local_def_id(def_index),
                     );
                     let ident = self.item_ident(def_index, sess);
@@ -1558,7 +1611,7 @@ impl<'a, 'tcx> CrateMetadataRef<'a> {
         *self.def_key_cache.lock().entry(index).or_insert_with(|| {
             let mut key = self.root.tables.def_keys.get(self, index).unwrap().decode(self);
             if self.is_proc_macro(index) {
-                let name = self.raw_proc_macro(index).name();
+                let name = self.proc_macro_name(index);
                 key.disambiguated_data.data = DefPathData::MacroNs(Symbol::intern(name));
             }
             key
@@ -1822,6 +1875,7 @@ impl CrateMetadata {
         blob: MetadataBlob,
         root: CrateRoot<'static>,
         raw_proc_macros: Option<&'static [ProcMacro]>,
+        remote_proc_macros: Option<RemoteProcMacroCrate>,
         cnum: CrateNum,
         cnum_map: CrateNumMap,
         dep_kind: CrateDepKind,
@@ -1842,6 +1896,7 @@ impl CrateMetadata {
             root,
             trait_impls,
             raw_proc_macros,
+            remote_proc_macros,
             source_map_import_info: OnceCell::new(),
             alloc_decoding_state,
             dep_node_index: AtomicCell::new(DepNodeIndex::INVALID),
@@ -1952,13 +2007,3 @@ impl CrateMetadata {
         None
     }
 }
-
-// Cannot be implemented on 'ProcMacro', as libproc_macro
-// does not depend on librustc_ast
-fn macro_kind(raw: &ProcMacro) -> MacroKind {
-    match raw {
-        ProcMacro::CustomDerive { .. } => MacroKind::Derive,
-        ProcMacro::Attr { .. } => MacroKind::Attr,
-        ProcMacro::Bang { .. } => MacroKind::Bang,
-    }
-}
@@ -150,6 +150,7 @@ impl<'a, 'hir> NodeCollector<'a, 'hir> {
         crate_disambiguator: CrateDisambiguator,
         cstore: &dyn CrateStore,
         commandline_args_hash: u64,
+        tracked_files: Vec<String>,
     ) -> (IndexVec<LocalDefId, HirOwnerData<'hir>>, Svh) {
         // Insert bodies into the map
         for (id, body) in self.krate.bodies.iter() {
@@ -183,8 +184,13 @@ impl<'a, 'hir> NodeCollector<'a, 'hir> {
 
         source_file_names.sort_unstable();
 
+        // Files tracked by proc macros are not in the source map, so hash their
+        // names separately. Changes to their contents show up in the HIR.
+        let mut tracked_files = tracked_files;
+        tracked_files.sort_unstable();
+
         let crate_hash_input = (
-            ((node_hashes, upstream_crates), source_file_names),
+            ((node_hashes, upstream_crates), (source_file_names, tracked_files)),
             (commandline_args_hash, crate_disambiguator.to_fingerprint()),
         );
 
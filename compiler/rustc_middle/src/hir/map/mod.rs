@@ -985,7 +985,14 @@ pub(super) fn index_hir<'tcx>(tcx: TyCtxt<'tcx>, cnum: CrateNum) -> &'tcx Indexe
 
         let crate_disambiguator = tcx.sess.local_crate_disambiguator();
         let cmdline_args = tcx.sess.opts.dep_tracking_hash();
-        collector.finalize_and_compute_crate_hash(crate_disambiguator, &*tcx.cstore, cmdline_args)
+        let tracked_files =
+            tcx.sess.parse_sess.file_depinfo.borrow().iter().map(|path| path.to_string()).collect();
+        collector.finalize_and_compute_crate_hash(
+            crate_disambiguator,
+            &*tcx.cstore,
+            cmdline_args,
+            tracked_files,
+        )
     };
 
     tcx.arena.alloc(IndexedHir { crate_hash, map })
@@ -1618,6 +1618,14 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                 return;
             }
 
+            ty::Generator(def_id, substs, _) => {
+                debug!("print-type-size t: `{:?}` record generator", layout.ty);
+                let (variant_infos, opt_discr_size) =
+                    self.variant_info_for_generator(layout, def_id, substs);
+                record(DataTypeKind::Generator, false, opt_discr_size, variant_infos);
+                return;
+            }
+
             _ => {
                 debug!("print-type-size t: `{:?}` skip non-nominal", layout.ty);
                 return;
@@ -1711,6 +1719,85 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
             }
         }
     }
+
+    /// Describes each state of a generator for `-Zprint-type-sizes`. The upvars are reported as
+    /// part of every state, since they are stored for as long as the generator lives, followed by
+    /// the locals saved across the suspension point of that state.
+    fn variant_info_for_generator(
+        &self,
+        layout: TyAndLayout<'tcx>,
+        def_id: hir::def_id::DefId,
+        substs: SubstsRef<'tcx>,
+    ) -> (Vec<VariantInfo>, Option<Size>) {
+        let tag_size = match layout.variants {
+            Variants::Multiple { ref tag, tag_encoding: TagEncoding::Direct, .. } => {
+                tag.value.size(self)
+            }
+            _ => return (vec![], None),
+        };
+
+        let tcx = self.tcx;
+        let generator = tcx.generator_layout(def_id);
+        let upvar_names: Vec<_> = match tcx.upvars_mentioned(def_id) {
+            Some(upvars) => upvars.keys().map(|&var_id| Some(tcx.hir().name(var_id))).collect(),
+            None => vec![],
+        };
+
+        let field_info = |name: Option<Symbol>, i: usize, layout: TyAndLayout<'tcx>| {
+            let field_layout = match layout.field(self, i) {
+                Ok(field_layout) => field_layout,
+                Err(err) => bug!("no layout found for generator field {}: `{:?}`", i, err),
+            };
+            FieldInfo {
+                name: match name {
+                    Some(name) => name.to_string(),
+                    None => format!("__{}", i),
+                },
+                offset: layout.fields.offset(i).bytes(),
+                size: field_layout.size.bytes(),
+                align: field_layout.align.abi.bytes(),
+            }
+        };
+
+        let upvar_fields: Vec<_> = substs
+            .as_generator()
+            .upvar_tys()
+            .enumerate()
+            .map(|(i, _)| {
+                let mut info = field_info(upvar_names.get(i).copied().flatten(), i, layout);
+                info.name = format!("upvar.{}", info.name);
+                info
+            })
+            .collect();
+        // The upvars and the tag make up the prefix shared by all states.
+        let prefix_size = (0..layout.fields.count())
+            .map(|i| field_info(None, i, layout))
+            .map(|f| f.offset + f.size)
+            .max()
+            .unwrap_or(0);
+
+        let variant_infos = generator
+            .variant_fields
+            .iter_enumerated()
+            .map(|(variant_idx, variant_fields)| {
+                let variant_layout = layout.for_variant(self, variant_idx);
+                let mut fields = upvar_fields.clone();
+                fields.extend(variant_fields.iter().enumerate().map(|(i, &saved_local)| {
+                    field_info(generator.field_names[saved_local], i, variant_layout)
+                }));
+                let size = fields.iter().map(|f| f.offset + f.size).max().unwrap_or(0);
+                VariantInfo {
+                    name: Some(ty::GeneratorSubsts::variant_name(variant_idx).to_string()),
+                    kind: SizeKind::Exact,
+                    align: variant_layout.align.abi.bytes(),
+                    size: size.max(prefix_size),
+                    fields,
+                }
+            })
+            .collect();
+
+        (variant_infos, Some(tag_size))
+    }
 }
 
 /// Type size "skeleton", i.e., the only information determining a type's size.
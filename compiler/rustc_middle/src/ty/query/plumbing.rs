@@ -69,7 +69,13 @@ impl QueryContext for TyCtxt<'tcx> {
 
             // Use the `ImplicitCtxt` while we execute the query.
             tls::enter_context(&new_icx, |_| {
-                rustc_data_structures::stack::ensure_sufficient_stack(|| compute(*self))
+                rustc_data_structures::stack::ensure_sufficient_stack(|| {
+                    if self.sess.opts.debugging_opts.query_stats {
+                        self.queries.memory_stats.record(*self, token.kind, || compute(*self))
+                    } else {
+                        compute(*self)
+                    }
+                })
             })
         })
     }
@@ -519,6 +525,9 @@ macro_rules! define_queries_struct {
             providers: IndexVec<CrateNum, Providers>,
             fallback_extern_providers: Box<Providers>,
 
+            /// The memory used by each query, only collected for `-Zquery-stats`.
+            pub(crate) memory_stats: stats::QueryMemoryStats,
+
             $($(#[$attr])*  $name: QueryState<
                 TyCtxt<$tcx>,
                 <queries::$name<$tcx> as QueryAccessors<TyCtxt<'tcx>>>::Cache,
@@ -535,6 +544,7 @@ macro_rules! define_queries_struct {
                     providers,
                     fallback_extern_providers: Box::new(fallback_extern_providers),
                     on_disk_cache,
+                    memory_stats: Default::default(),
                     $($name: Default::default()),*
                 }
             }
@@ -1,11 +1,15 @@
+use crate::dep_graph::DepKind;
 use crate::ty::query::queries;
 use crate::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::sync::Lock;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_query_system::query::QueryCache;
 use rustc_query_system::query::QueryState;
 use rustc_query_system::query::{QueryAccessors, QueryContext};
 
 use std::any::type_name;
+use std::cell::Cell;
 use std::mem;
 #[cfg(debug_assertions)]
 use std::sync::atomic::Ordering;
@@ -63,6 +67,65 @@ fn stats<CTX: QueryContext, C: QueryCache>(
     stats
 }
 
+/// The memory used by all executions of a query, not counting the memory used
+/// by the queries they executed in turn.
+#[derive(Clone, Copy, Default)]
+struct QueryMemory {
+    executions: usize,
+    arena_bytes: usize,
+}
+
+/// Memory accounting for `-Zquery-stats`. Every query execution is charged with
+/// the arena allocations (which includes everything interned in the `TyCtxt`)
+/// that happened while it was running.
+#[derive(Default)]
+pub(crate) struct QueryMemoryStats {
+    by_kind: Lock<FxHashMap<DepKind, QueryMemory>>,
+}
+
+thread_local! {
+    /// The arena bytes allocated by the queries that were executed by the
+    /// query currently running on this thread.
+    static NESTED_ARENA_BYTES: Cell<usize> = Cell::new(0);
+}
+
+impl QueryMemoryStats {
+    pub(crate) fn record<R>(&self, tcx: TyCtxt<'_>, kind: DepKind, f: impl FnOnce() -> R) -> R {
+        let _execution = QueryExecution {
+            stats: self,
+            tcx,
+            kind,
+            outer_nested_arena_bytes: NESTED_ARENA_BYTES.with(|nested| nested.replace(0)),
+            arena_start: tcx.arena.allocated_bytes(),
+        };
+        f()
+    }
+}
+
+/// A running query execution. Its memory usage is recorded when it is dropped,
+/// which also happens if the query unwinds (e.g. on a cycle error), so that
+/// the accounting of the enclosing query is never left in a corrupted state.
+struct QueryExecution<'a, 'tcx> {
+    stats: &'a QueryMemoryStats,
+    tcx: TyCtxt<'tcx>,
+    kind: DepKind,
+    outer_nested_arena_bytes: usize,
+    arena_start: usize,
+}
+
+impl Drop for QueryExecution<'_, '_> {
+    fn drop(&mut self) {
+        let arena_bytes = self.tcx.arena.allocated_bytes().saturating_sub(self.arena_start);
+        let nested_arena_bytes = NESTED_ARENA_BYTES
+            .with(|nested| nested.replace(self.outer_nested_arena_bytes + arena_bytes));
+
+        let mut by_kind = self.stats.by_kind.lock();
+        let memory = by_kind.entry(self.kind).or_default();
+        memory.executions += 1;
+        memory.arena_bytes += arena_bytes.saturating_sub(nested_arena_bytes);
+    }
+}
+
 pub fn print_stats(tcx: TyCtxt<'_>) {
     let queries = query_stats(tcx);
 
@@ -107,6 +170,26 @@ pub fn print_stats(tcx: TyCtxt<'_>) {
         println!("   {} - {}", q.name, q.entry_count);
     }
 
+    let memory: Vec<_> = tcx.queries.memory_stats.by_kind.lock().clone().into_iter().collect();
+    let total_arena_bytes = tcx.arena.allocated_bytes();
+    let query_arena_bytes: usize = memory.iter().map(|(_, m)| m.arena_bytes).sum();
+
+    let mut query_arena_memory = memory;
+    query_arena_memory.sort_by_key(|(_, m)| m.arena_bytes);
+    println!(
+        "\nQuery arena memory ({} of {} bytes allocated in the arena):",
+        query_arena_bytes, total_arena_bytes
+    );
+    for (kind, m) in query_arena_memory.iter().rev().filter(|(_, m)| m.arena_bytes > 0) {
+        println!(
+            "   {:?} - {} bytes in {} executions ({}%)",
+            kind,
+            m.arena_bytes,
+            m.executions,
+            (m.arena_bytes as f64 * 100.0) / total_arena_bytes as f64
+        );
+    }
+
     let mut def_id_density: Vec<_> =
         queries.iter().filter(|q| q.local_def_id_keys.is_some()).collect();
     def_id_density.sort_by_key(|q| q.local_def_id_keys.unwrap());
@@ -263,7 +263,7 @@ impl<'tcx> Partitioner<'tcx> for DefaultPartitioning {
     }
 }
 
-fn characteristic_def_id_of_mono_item<'tcx>(
+pub(super) fn characteristic_def_id_of_mono_item<'tcx>(
     tcx: TyCtxt<'tcx>,
     mono_item: MonoItem<'tcx>,
 ) -> Option<DefId> {
@@ -325,7 +325,18 @@ fn compute_codegen_unit_name(
     volatile: bool,
     cache: &mut CguNameCache,
 ) -> Symbol {
-    // Find the innermost module that is not nested within a function.
+    let cgu_def_id = codegen_unit_module(tcx, def_id);
+
+    *cache.entry((cgu_def_id, volatile)).or_insert_with(|| {
+        let components = module_path_components(tcx, cgu_def_id);
+        let volatile_suffix = volatile.then_some("volatile");
+
+        name_builder.build_cgu_name(cgu_def_id.krate, components, volatile_suffix)
+    })
+}
+
+/// Finds the innermost module that contains `def_id` and is not nested within a function.
+pub(super) fn codegen_unit_module(tcx: TyCtxt<'_>, def_id: DefId) -> DefId {
     let mut current_def_id = def_id;
     let mut cgu_def_id = None;
     // Walk backwards from the item we want to find the module for.
@@ -350,20 +361,19 @@ fn compute_codegen_unit_name(
         current_def_id = tcx.parent(current_def_id).unwrap();
     }
 
-    let cgu_def_id = cgu_def_id.unwrap();
-
-    *cache.entry((cgu_def_id, volatile)).or_insert_with(|| {
-        let def_path = tcx.def_path(cgu_def_id);
+    cgu_def_id.unwrap()
+}
 
-        let components = def_path.data.iter().map(|part| match part.data.name() {
+/// The names of the modules leading to `module_def_id`, without the crate name.
+pub(super) fn module_path_components(tcx: TyCtxt<'_>, module_def_id: DefId) -> Vec<Symbol> {
+    tcx.def_path(module_def_id)
+        .data
+        .iter()
+        .map(|part| match part.data.name() {
             DefPathDataName::Named(name) => name,
             DefPathDataName::Anon { .. } => unreachable!(),
-        });
-
-        let volatile_suffix = volatile.then_some("volatile");
-
-        name_builder.build_cgu_name(def_path.krate, components, volatile_suffix)
-    })
+        })
+        .collect()
 }
 
 // Anything we can't find a proper codegen unit for goes into this.
@@ -0,0 +1,122 @@
+//! A partitioning strategy for incremental builds which gives every root mono item its own
+//! codegen unit.
+//!
+//! With the default strategy, editing a single function invalidates the codegen unit of its
+//! whole module, which then has to be re-codegened and re-optimized from scratch. Here, the
+//! codegen unit of an edited function only contains that function (and whatever gets inlined
+//! into it), so everything else can still be reused from the incremental cache. The cost is that
+//! LLVM can no longer optimize across the functions of a module, so this is only meant for
+//! debug-like builds where rebuild times matter most.
+//!
+//! The codegen unit of a function is named after its module and the function's own name, e.g.
+//! `krate-module-function`, with the same `.volatile` suffix as the default strategy for generic
+//! functions. Items with the same name in the same module, like methods of different impls, share
+//! a codegen unit. Items without a name of their own, like drop glue and shims, stay in the
+//! codegen unit of their module.
+//!
+//! Non-incremental builds are partitioned exactly like the default strategy does.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::mir::mono::{CodegenUnit, CodegenUnitNameBuilder, MonoItem};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::Symbol;
+
+use super::PartitioningCx;
+use crate::monomorphize::partitioning::default::{
+    characteristic_def_id_of_mono_item, codegen_unit_module, module_path_components,
+    DefaultPartitioning,
+};
+use crate::monomorphize::partitioning::merging;
+use crate::monomorphize::partitioning::{
+    Partitioner, PostInliningPartitioning, PreInliningPartitioning,
+};
+
+pub struct FunctionPartitioning;
+
+impl<'tcx> Partitioner<'tcx> for FunctionPartitioning {
+    fn place_root_mono_items(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        mono_items: &mut dyn Iterator<Item = MonoItem<'tcx>>,
+    ) -> PreInliningPartitioning<'tcx> {
+        let mut partitioning = DefaultPartitioning.place_root_mono_items(cx, mono_items);
+        if cx.tcx.sess.opts.incremental.is_none() {
+            return partitioning;
+        }
+
+        // Split the per-module codegen units into one codegen unit per root. The names only
+        // depend on the paths of the roots, which are stable across compilation sessions, so
+        // that the work products of the previous session can be found again.
+        let cgu_name_builder = &mut CodegenUnitNameBuilder::new(cx.tcx);
+        let mut codegen_units = FxHashMap::default();
+        for module_cgu in partitioning.codegen_units.drain(..) {
+            for (&mono_item, &linkage) in module_cgu.items() {
+                let name = function_cgu_name(cx.tcx, cgu_name_builder, mono_item)
+                    .unwrap_or_else(|| module_cgu.name());
+                codegen_units
+                    .entry(name)
+                    .or_insert_with(|| CodegenUnit::new(name))
+                    .items_mut()
+                    .insert(mono_item, linkage);
+            }
+
+            // Keep empty codegen units around, like the fallback codegen unit of a crate
+            // without any functions.
+            if module_cgu.items().is_empty() {
+                codegen_units.entry(module_cgu.name()).or_insert(module_cgu);
+            }
+        }
+
+        partitioning.codegen_units = codegen_units.into_iter().map(|(_, cgu)| cgu).collect();
+        partitioning
+    }
+
+    fn merge_codegen_units(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        initial_partitioning: &mut PreInliningPartitioning<'tcx>,
+    ) {
+        // Merging codegen units would make functions share codegen units again, defeating the
+        // purpose of this strategy.
+        if cx.tcx.sess.opts.incremental.is_none() {
+            merging::merge_codegen_units(cx, initial_partitioning);
+        }
+    }
+
+    fn place_inlined_mono_items(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        initial_partitioning: PreInliningPartitioning<'tcx>,
+    ) -> PostInliningPartitioning<'tcx> {
+        DefaultPartitioning.place_inlined_mono_items(cx, initial_partitioning)
+    }
+
+    fn internalize_symbols(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        partitioning: &mut PostInliningPartitioning<'tcx>,
+    ) {
+        DefaultPartitioning.internalize_symbols(cx, partitioning)
+    }
+}
+
+fn function_cgu_name<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    name_builder: &mut CodegenUnitNameBuilder<'tcx>,
+    mono_item: MonoItem<'tcx>,
+) -> Option<Symbol> {
+    let item_def_id = match mono_item {
+        MonoItem::Fn(instance) => instance.def_id(),
+        MonoItem::Static(def_id) => def_id,
+        MonoItem::GlobalAsm(..) => return None,
+    };
+    let item_name = tcx.def_key(item_def_id).disambiguated_data.data.get_opt_name()?;
+    let module_def_id =
+        codegen_unit_module(tcx, characteristic_def_id_of_mono_item(tcx, mono_item)?);
+
+    let mut components = module_path_components(tcx, module_def_id);
+    components.push(item_name);
+    let volatile_suffix = mono_item.is_generic_fn().then_some("volatile");
+
+    Some(name_builder.build_cgu_name(module_def_id.krate, components, volatile_suffix))
+}
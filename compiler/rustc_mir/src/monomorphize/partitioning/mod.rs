@@ -91,8 +91,16 @@
 //! Note though that as a side-effect of creating a codegen units per
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked `#[inline]`.
+//!
+//! Partitioning Strategies
+//! -----------------------
+//! The heuristic above is implemented by the "default" strategy. With
+//! `-Z cgu-partitioning-strategy=function`, incremental builds instead give
+//! every non-inlined function and static its own codegen unit, trading
+//! runtime performance for the finest granularity of reuse.
 
 mod default;
+mod function;
 mod merging;
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
@@ -148,6 +156,7 @@ fn get_partitioner<'tcx>(tcx: TyCtxt<'tcx>) -> Box<dyn Partitioner<'tcx>> {
 
     match strategy {
         "default" => Box::new(default::DefaultPartitioning),
+        "function" => Box::new(function::FunctionPartitioning),
         _ => tcx.sess.fatal("unknown partitioning strategy"),
     }
 }
@@ -427,6 +427,150 @@ fn replace_local<'tcx>(
     new_local
 }
 
+/// Finds locals which are initialized in the entry block by moving an upvar out of the generator
+/// argument, where the upvar is not used anywhere else in the body.
+///
+/// Every argument of an `async fn` is captured by its generator and then immediately moved into a
+/// local binding, so without this both the upvar and the local would be stored in the generator
+/// state whenever the argument is held across an `await`. Since the upvar already has storage for
+/// as long as the generator exists, such locals can simply refer to the upvar instead.
+///
+/// Returns the place of the upvar each local can be replaced with, along with the location of the
+/// initializing statement.
+fn find_upvar_aliases<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+) -> FxHashMap<Local, (Place<'tcx>, Location)> {
+    let mut aliases = FxHashMap::default();
+
+    // If the entry block can be reentered, the initializing statement does not dominate the
+    // other uses of the local.
+    if !body.predecessors()[START_BLOCK].is_empty() {
+        return aliases;
+    }
+
+    let mut uses = UpvarUseCollector {
+        field_uses: FxHashMap::default(),
+        whole_self_used: false,
+        index_locals: BitSet::new_empty(body.local_decls.len()),
+    };
+    uses.visit_body(body);
+    if uses.whole_self_used {
+        return aliases;
+    }
+
+    let mut mentioned = LocalMentionCollector(BitSet::new_empty(body.local_decls.len()));
+    for (statement_index, statement) in body[START_BLOCK].statements.iter().enumerate() {
+        let location = Location { block: START_BLOCK, statement_index };
+        match &statement.kind {
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => continue,
+            StatementKind::Assign(box (
+                lhs,
+                Rvalue::Use(Operand::Move(rhs) | Operand::Copy(rhs)),
+            )) => {
+                if let (Some(local), &[ProjectionElem::Field(field, ty)]) =
+                    (lhs.as_local(), &rhs.projection[..])
+                {
+                    let eligible = rhs.local == SELF_ARG
+                        && body.local_kind(local) != LocalKind::Arg
+                        && local != RETURN_PLACE
+                        && !mentioned.0.contains(local)
+                        && !uses.index_locals.contains(local)
+                        && uses.field_uses.get(&field) == Some(&1)
+                        && tcx.erase_regions(&body.local_decls[local].ty) == tcx.erase_regions(&ty);
+                    if eligible {
+                        debug!("aliasing {:?} to upvar {:?}", local, rhs);
+                        aliases.insert(local, (*rhs, location));
+                    }
+                }
+            }
+            _ => {}
+        }
+        mentioned.visit_statement(statement, location);
+    }
+
+    aliases
+}
+
+/// Counts the uses of each upvar of the generator argument.
+struct UpvarUseCollector {
+    field_uses: FxHashMap<Field, usize>,
+    /// Whether the generator argument is used as a whole, rather than through one of its upvars.
+    whole_self_used: bool,
+    /// Locals used as an index in some place, which can only ever be a plain local.
+    index_locals: BitSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for UpvarUseCollector {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _: Location) {
+        for elem in place.projection.iter() {
+            if let ProjectionElem::Index(local) = elem {
+                self.index_locals.insert(local);
+            }
+        }
+
+        if place.local != SELF_ARG || !context.is_use() {
+            return;
+        }
+
+        match place.projection.first() {
+            Some(&ProjectionElem::Field(field, _)) => {
+                *self.field_uses.entry(field).or_insert(0) += 1;
+            }
+            _ => self.whole_self_used = true,
+        }
+    }
+}
+
+/// Collects every local mentioned by the visited MIR.
+struct LocalMentionCollector(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for LocalMentionCollector {
+    fn visit_local(&mut self, local: &Local, _: PlaceContext, _: Location) {
+        self.0.insert(*local);
+    }
+}
+
+/// Replaces every use of the locals found by `find_upvar_aliases` with the upvar they were
+/// initialized from, and removes their initialization and storage statements.
+struct UpvarAliasVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    aliases: FxHashMap<Local, (Place<'tcx>, Location)>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for UpvarAliasVisitor<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Location) {
+        match statement.kind {
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if self.aliases.contains_key(&local) =>
+            {
+                statement.make_nop();
+            }
+            StatementKind::Assign(box (lhs, _))
+                if lhs
+                    .as_local()
+                    .and_then(|local| self.aliases.get(&local))
+                    .map(|&(_, loc)| loc)
+                    == Some(location) =>
+            {
+                // This is the move out of the upvar which initialized the local.
+                statement.make_nop();
+            }
+            _ => self.super_statement(statement, location),
+        }
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, _: PlaceContext, _: Location) {
+        if let Some(&(upvar, _)) = self.aliases.get(&place.local) {
+            replace_base(place, upvar, self.tcx);
+        }
+    }
+}
+
 struct LivenessInfo {
     /// Which locals are live across any suspension point.
     saved_locals: GeneratorSavedLocals,
@@ -776,12 +920,22 @@ fn compute_layout<'tcx>(
         storage_liveness,
     } = liveness;
 
+    // Find the names of user variables, to be reported by `-Zprint-type-sizes`.
+    let mut local_names = FxHashMap::default();
+    for var in &body.var_debug_info {
+        if let Some(local) = var.place.as_local() {
+            local_names.entry(local).or_insert(var.name);
+        }
+    }
+
     // Gather live local types and their indices.
     let mut locals = IndexVec::<GeneratorSavedLocal, _>::new();
     let mut tys = IndexVec::<GeneratorSavedLocal, _>::new();
+    let mut names = IndexVec::<GeneratorSavedLocal, _>::new();
     for (saved_local, local) in saved_locals.iter_enumerated() {
         locals.push(local);
         tys.push(body.local_decls[local].ty);
+        names.push(local_names.get(&local).copied());
         debug!("generator saved local {:?} => {:?}", saved_local, local);
     }
 
@@ -821,8 +975,13 @@ fn compute_layout<'tcx>(
     debug!("generator variant_fields = {:?}", variant_fields);
     debug!("generator storage_conflicts = {:#?}", storage_conflicts);
 
-    let layout =
-        GeneratorLayout { field_tys: tys, variant_fields, variant_source_info, storage_conflicts };
+    let layout = GeneratorLayout {
+        field_tys: tys,
+        field_names: names,
+        variant_fields,
+        variant_source_info,
+        storage_conflicts,
+    };
 
     (remap, layout, storage_liveness)
 }
@@ -1298,6 +1457,13 @@ impl<'tcx> MirPass<'tcx> for StateTransform {
             },
         );
 
+        // Make locals which are just moved-out upvars use the storage of the upvar, so that they
+        // don't take up additional space in the generator state.
+        let upvar_aliases = find_upvar_aliases(tcx, body);
+        if !upvar_aliases.is_empty() {
+            UpvarAliasVisitor { tcx, aliases: upvar_aliases }.visit_body(body);
+        }
+
         let always_live_locals = storage::AlwaysLiveLocals::new(&body);
 
         let liveness_info =
@@ -351,6 +351,7 @@ fn irrefutable_let_pattern(tcx: TyCtxt<'_>, span: Span, id: HirId, source: hir::
         let msg = match source {
             hir::MatchSource::IfLetDesugar { .. } => "irrefutable if-let pattern",
             hir::MatchSource::WhileLetDesugar => "irrefutable while-let pattern",
+            hir::MatchSource::LetElseDesugar => "irrefutable let...else pattern",
             _ => bug!(),
         };
         lint.build(msg).emit()
@@ -372,7 +373,9 @@ fn check_arms<'p, 'tcx>(
                 match source {
                     hir::MatchSource::IfDesugar { .. } | hir::MatchSource::WhileDesugar => bug!(),
 
-                    hir::MatchSource::IfLetDesugar { .. } | hir::MatchSource::WhileLetDesugar => {
+                    hir::MatchSource::IfLetDesugar { .. }
+                    | hir::MatchSource::WhileLetDesugar
+                    | hir::MatchSource::LetElseDesugar => {
                         // Check which arm we're on.
                         match arm_index {
                             // The arm with the user-specified pattern.
@@ -106,6 +106,8 @@ pub struct Parser<'a> {
     pub last_type_ascription: Option<(Span, bool /* likely path typo */)>,
     /// If present, this `Parser` is not parsing Rust code but rather a macro call.
     subparser_name: Option<&'static str>,
+    /// The number of tokens the parser has advanced past (see `num_bumps`).
+    num_bumps: usize,
 }
 
 impl<'a> Drop for Parser<'a> {
@@ -365,6 +367,7 @@ impl<'a> Parser<'a> {
             last_unexpected_token_span: None,
             last_type_ascription: None,
             subparser_name,
+            num_bumps: 0,
         };
 
         // Make parser point to the first token.
@@ -807,6 +810,7 @@ impl<'a> Parser<'a> {
 
         // Update the current and previous tokens.
         self.prev_token = mem::replace(&mut self.token, next_token);
+        self.num_bumps += 1;
 
         // Diagnostics.
         self.expected_tokens.clear();
@@ -818,6 +822,12 @@ impl<'a> Parser<'a> {
         self.bump_with(next_token);
     }
 
+    /// Returns how many times the parser has been advanced, i.e. roughly how many tokens it
+    /// has consumed. Used by macro matching to tell how far an attempt to match a rule got.
+    pub fn num_bumps(&self) -> usize {
+        self.num_bumps
+    }
+
     /// Look-ahead `dist` tokens of `self.token` and get access to that token there.
     /// When `dist == 0` then the current token is looked at.
     pub fn look_ahead<R>(&self, dist: usize, looker: impl FnOnce(&Token) -> R) -> R {
@@ -218,8 +218,39 @@ impl<'a> Parser<'a> {
                 return Err(err);
             }
         };
+        let els = match init {
+            Some(ref init) if self.eat_keyword(kw::Else) => {
+                self.check_let_else_init_trailing_brace(init);
+                let els = self.parse_block()?;
+                self.sess.gated_spans.gate(sym::let_else, lo.to(self.prev_token.span));
+                Some(els)
+            }
+            _ => None,
+        };
         let hi = if self.token == token::Semi { self.token.span } else { self.prev_token.span };
-        Ok(P(ast::Local { ty, pat, init, id: DUMMY_NODE_ID, span: lo.to(hi), attrs }))
+        Ok(P(ast::Local { ty, pat, init, els, id: DUMMY_NODE_ID, span: lo.to(hi), attrs }))
+    }
+
+    /// In `let x = if c { a } else { b } else { return };`, it is not obvious
+    /// which `else` belongs to the `let`, so such initializers must be wrapped
+    /// in parentheses.
+    fn check_let_else_init_trailing_brace(&self, init: &Expr) {
+        if classify::expr_requires_semi_to_be_stmt(init) {
+            return;
+        }
+        self.struct_span_err(
+            init.span.shrink_to_hi(),
+            "right curly brace `}` before `else` in a `let...else` statement not allowed",
+        )
+        .multipart_suggestion(
+            "try wrapping the expression in parentheses",
+            vec![
+                (init.span.shrink_to_lo(), "(".to_string()),
+                (init.span.shrink_to_hi(), ")".to_string()),
+            ],
+            Applicability::MachineApplicable,
+        )
+        .emit();
     }
 
     /// Parses the RHS of a local variable declaration (e.g., '= 14;').
@@ -49,7 +49,12 @@ impl NonConstExpr {
             // All other expressions are allowed.
             Self::Loop(Loop | While | WhileLet)
             | Self::Match(
-                WhileDesugar | WhileLetDesugar | Normal | IfDesugar { .. } | IfLetDesugar { .. },
+                WhileDesugar
+                | WhileLetDesugar
+                | Normal
+                | IfDesugar { .. }
+                | IfLetDesugar { .. }
+                | LetElseDesugar,
             ) => &[],
         };
 
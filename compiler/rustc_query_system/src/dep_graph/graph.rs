@@ -90,6 +90,10 @@ struct DepGraphData<K: DepKind> {
     previous_work_products: FxHashMap<WorkProductId, WorkProduct>,
 
     dep_node_debug: Lock<FxHashMap<DepNode<K>, String>>,
+
+    /// For every node that could not be marked green, the dependency that
+    /// prevented it. Only filled in if `DepContext::explain_dep_nodes` is set.
+    red_causes: Lock<FxHashMap<DepNode<K>, DepNode<K>>>,
 }
 
 pub fn hash_result<HashCtxt, R>(hcx: &mut HashCtxt, result: &R) -> Option<Fingerprint>
@@ -113,6 +117,7 @@ impl<K: DepKind> DepGraph<K> {
             data: Some(Lrc::new(DepGraphData {
                 previous_work_products: prev_work_products,
                 dep_node_debug: Default::default(),
+                red_causes: Default::default(),
                 current: CurrentDepGraph::new(prev_graph_node_count),
                 emitting_diagnostics: Default::default(),
                 emitting_diagnostics_cond_var: Condvar::new(),
@@ -492,6 +497,13 @@ impl<K: DepKind> DepGraph<K> {
         None
     }
 
+    /// Returns the dependency which prevented `dep_node` from being marked
+    /// green, if it had to be re-executed because of one. This is only
+    /// recorded if `DepContext::explain_dep_nodes` is set.
+    pub fn red_cause(&self, dep_node: &DepNode<K>) -> Option<DepNode<K>> {
+        self.data.as_ref().and_then(|data| data.red_causes.lock().get(dep_node).copied())
+    }
+
     /// Try to read a node index for the node dep_node.
     /// A node will have an index, when it's already been marked green, or when we can mark it
     /// green. This function will mark the current task as a reader of the specified node, when
@@ -594,6 +606,10 @@ impl<K: DepKind> DepGraph<K> {
                         dep_node,
                         data.previous.index_to_node(dep_dep_node_index)
                     );
+                    if tcx.explain_dep_nodes() {
+                        let dep_dep_node = data.previous.index_to_node(dep_dep_node_index);
+                        data.red_causes.lock().insert(*dep_node, dep_dep_node);
+                    }
                     return None;
                 }
                 None => {
@@ -649,6 +665,9 @@ impl<K: DepKind> DepGraph<K> {
                                         dependency {:?} was red after forcing",
                                     dep_node, dep_dep_node
                                 );
+                                if tcx.explain_dep_nodes() {
+                                    data.red_causes.lock().insert(*dep_node, *dep_dep_node);
+                                }
                                 return None;
                             }
                             None => {
@@ -684,6 +703,9 @@ impl<K: DepKind> DepGraph<K> {
                                 could not be forced",
                             dep_node, dep_dep_node
                         );
+                        if tcx.explain_dep_nodes() {
+                            data.red_causes.lock().insert(*dep_node, *dep_dep_node);
+                        }
                         return None;
                     }
                 }
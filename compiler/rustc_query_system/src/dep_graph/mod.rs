@@ -29,6 +29,9 @@ pub trait DepContext: Copy {
     fn debug_dep_tasks(&self) -> bool;
     fn debug_dep_node(&self) -> bool;
 
+    /// Whether to record why dep nodes could not be marked green.
+    fn explain_dep_nodes(&self) -> bool;
+
     /// Try to force a dep node to execute and see if it's green.
     fn try_force_from_dep_node(&self, dep_node: &DepNode<Self::DepKind>) -> bool;
 
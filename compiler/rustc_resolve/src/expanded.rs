@@ -0,0 +1,540 @@
+//! Printing the expanded crate for `--emit=expanded`, as source code which compiles
+//! to the same crate.
+//!
+//! Printing the AST as it is (which is what `-Z unpretty=expanded` does) isn't enough:
+//!
+//! - Identifiers lose their hygiene, so the local variables and labels introduced by
+//!   macros could clash with each other, or with those of the user. They're renamed
+//!   (e.g. `x` to `x_1`) according to what they were resolved to, and so are the local
+//!   variables which would otherwise shadow an item that a macro refers to.
+//! - `$crate` is printed as the name of the crate it refers to, which is only reachable
+//!   by that name in every edition if it's declared with `extern crate` at the crate root.
+//! - Macros may use unstable features in their output (see `#[allow_internal_unstable]`),
+//!   like the internals of `format_args!` or the code of the built-in derives. The
+//!   invocations of such macros written in the crate are printed as they're written (with
+//!   the local variables they refer to renamed like elsewhere), so that the output compiles
+//!   on the stable channel. This is done for the macros invoked in expression, statement
+//!   and item position, and for derives; the other macros are printed expanded.
+//! - The standard library and the test and proc macro harnesses are injected again when
+//!   the output is compiled, so they're left out.
+
+use crate::{Res, Resolver};
+
+use rustc_ast::mut_visit::{self, MutVisitor};
+use rustc_ast::ptr::P;
+use rustc_ast::visit::{self, Visitor};
+use rustc_ast::{self as ast, attr, Expr, ExprKind, Label, NodeId, Pat, PatKind};
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::PResult;
+use rustc_parse::parser::Parser;
+use rustc_span::hygiene::{ExpnId, ExpnKind, MacroKind, SyntaxContext};
+use rustc_span::symbol::{kw, sym, Ident, Symbol};
+use rustc_span::{FileName, Pos, Span, DUMMY_SP};
+use smallvec::{smallvec, SmallVec};
+
+use std::cmp::Reverse;
+
+/// Whether `ident` only refers to local variables and labels of the same
+/// macro expansion, which it won't once it's printed.
+fn is_hygienic(ident: Ident) -> bool {
+    ident.span.normalize_to_macro_rules().ctxt() != SyntaxContext::root()
+}
+
+/// Goes up from the macro invocation `span` has been expanded from to the invocation written in
+/// the crate which it's part of, if any, and returns the latter, and whether any of these
+/// macros uses unstable features in its output.
+fn written_invocation(span: Span) -> Option<(ExpnId, bool)> {
+    let mut expn_id = span.ctxt().outer_expn();
+    let mut unstable = false;
+    while expn_id != ExpnId::root() {
+        let expn_data = expn_id.expn_data();
+        unstable |=
+            expn_data.allow_internal_unstable.map_or(false, |features| !features.is_empty());
+        let parent = expn_data.call_site.ctxt().outer_expn();
+        if parent == ExpnId::root() {
+            return Some((expn_id, unstable));
+        }
+        expn_id = parent;
+    }
+    None
+}
+
+/// Gathers what has to be changed in the crate before it can be printed.
+struct Collector<'a, 'b> {
+    r: &'b Resolver<'a>,
+    /// Every name used in the crate, which new names mustn't clash with.
+    names: FxHashSet<Symbol>,
+    /// The local variables (their first binding), in order.
+    bindings: Vec<(NodeId, Ident)>,
+    /// The labels of loops and blocks, in order.
+    labels: Vec<(NodeId, Ident)>,
+    /// The names which macros use to refer to something other than a local
+    /// variable, and so mustn't be shadowed by a local variable.
+    names_of_items_in_macros: Vec<(Symbol, Span)>,
+    seen_ctxts: FxHashSet<SyntaxContext>,
+    /// The macro invocations written in the crate which are printed unexpanded.
+    kept: FxHashMap<ExpnId, MacroKind>,
+    crates: Vec<(Symbol, Span)>,
+}
+
+impl Collector<'_, '_> {
+    fn res(&self, id: NodeId) -> Option<Res> {
+        self.r.partial_res_map.get(&id).map(|res| res.base_res())
+    }
+
+    fn visit_span(&mut self, span: Span) {
+        let ctxt = span.ctxt();
+        if !self.seen_ctxts.insert(ctxt) {
+            return;
+        }
+        if let Some((expn_id, true)) = written_invocation(span) {
+            if let ExpnKind::Macro(kind, _) = expn_id.expn_data().kind {
+                if kind != MacroKind::Attr {
+                    self.kept.insert(expn_id, kind);
+                }
+            }
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for Collector<'_, '_> {
+    fn visit_ident(&mut self, ident: Ident) {
+        self.visit_span(ident.span);
+        if ident.name == kw::DollarCrate {
+            let name = ident.span.ctxt().dollar_crate_name();
+            if !name.is_path_segment_keyword() {
+                self.crates.push((name, ident.span));
+            }
+        }
+        self.names.insert(ident.name);
+    }
+
+    fn visit_item(&mut self, item: &'ast ast::Item) {
+        self.visit_span(item.span);
+        visit::walk_item(self, item);
+    }
+
+    fn visit_ty(&mut self, ty: &'ast ast::Ty) {
+        self.visit_span(ty.span);
+        visit::walk_ty(self, ty);
+    }
+
+    fn visit_pat(&mut self, pat: &'ast Pat) {
+        self.visit_span(pat.span);
+        if let PatKind::Ident(_, ident, _) = pat.kind {
+            if self.res(pat.id) == Some(Res::Local(pat.id)) {
+                self.bindings.push((pat.id, ident));
+            }
+        }
+        visit::walk_pat(self, pat);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        self.visit_span(expr.span);
+        match &expr.kind {
+            ExprKind::Path(None, path) if path.segments.len() == 1 => {
+                let ident = path.segments[0].ident;
+                if is_hygienic(ident) && !matches!(self.res(expr.id), Some(Res::Local(_))) {
+                    self.names_of_items_in_macros.push((ident.name, ident.span));
+                }
+            }
+            ExprKind::While(_, _, Some(label))
+            | ExprKind::ForLoop(_, _, _, Some(label))
+            | ExprKind::Loop(_, Some(label)) => self.labels.push((expr.id, label.ident)),
+            ExprKind::Block(block, Some(label)) => self.labels.push((block.id, label.ident)),
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Renames local variables and labels, removes injected items, and puts back the
+/// kept macro invocations.
+struct Renamer<'a, 'b> {
+    r: &'b Resolver<'a>,
+    /// The new names of local variables, by their first binding.
+    bindings: FxHashMap<NodeId, Symbol>,
+    /// The new names of labels, by the loop or block they're on.
+    labels: FxHashMap<NodeId, Symbol>,
+    kept: FxHashMap<ExpnId, MacroKind>,
+    /// The kept invocations which have been put back in place of the first of the
+    /// statements or items they expanded to, so the others are removed.
+    printed: FxHashSet<ExpnId>,
+}
+
+impl<'a, 'b> Renamer<'a, 'b> {
+    /// Returns the new name of the local variable which the binding or use with the
+    /// given `id` refers to, if it's renamed.
+    fn local_name(&self, id: NodeId) -> Option<Symbol> {
+        match self.r.partial_res_map.get(&id).map(|res| res.base_res()) {
+            Some(Res::Local(binding)) => self.bindings.get(&binding).copied(),
+            _ => None,
+        }
+    }
+
+    /// Renames `ident`, the binding or use of a local variable with the given `id`.
+    fn rename_local(&self, id: NodeId, ident: &mut Ident) {
+        if let Some(name) = self.local_name(id) {
+            ident.name = name;
+        }
+    }
+
+    /// Renames `label`, on or referring to the loop or block with the given `id`.
+    fn rename_label(&self, id: NodeId, label: &mut Option<Label>) {
+        if let (Some(label), Some(&name)) = (label, self.labels.get(&id)) {
+            label.ident.name = name;
+        }
+    }
+
+    /// Returns the kept invocation which `span` has been expanded from, if any.
+    fn kept_invocation(&self, span: Span) -> Option<(ExpnId, MacroKind)> {
+        let (expn_id, _) = written_invocation(span)?;
+        self.kept.get(&expn_id).map(|&kind| (expn_id, kind))
+    }
+
+    /// Finds the local variables written in a kept invocation which are renamed, in what
+    /// it expanded to.
+    fn renamed_locals(
+        &self,
+        visit: impl FnOnce(&mut RenamedLocals<'_, 'a, 'b>),
+    ) -> Vec<(Span, Symbol)> {
+        let mut visitor = RenamedLocals { renamer: self, renamed: Vec::new() };
+        visit(&mut visitor);
+        visitor.renamed
+    }
+
+    /// Parses the kept invocation `expn_id` from the source of the crate, with the
+    /// `renamed` local variables renamed.
+    fn parse_invocation<T>(
+        &self,
+        expn_id: ExpnId,
+        mut renamed: Vec<(Span, Symbol)>,
+        parse: impl for<'p> FnOnce(&mut Parser<'p>) -> PResult<'p, T>,
+    ) -> Option<T> {
+        let call_site = expn_id.expn_data().call_site;
+        let mut source = self.r.session.source_map().span_to_snippet(call_site).ok()?;
+        // From the last one, so that the others stay at the same place in the source.
+        renamed.retain(|&(span, _)| call_site.contains(span));
+        renamed.sort_by_key(|&(span, _)| Reverse(span.lo()));
+        renamed.dedup_by_key(|&mut (span, _)| span.lo());
+        for (span, name) in renamed {
+            let lo = (span.lo() - call_site.lo()).to_usize();
+            let hi = (span.hi() - call_site.lo()).to_usize();
+            source.replace_range(lo..hi, &name.as_str());
+        }
+        let filename = FileName::macro_expansion_source_code(&source);
+        let mut parser =
+            rustc_parse::new_parser_from_source_str(&self.r.session.parse_sess, filename, source);
+        parse(&mut parser).map_err(|mut err| err.cancel()).ok()
+    }
+
+    /// Takes the items derived by kept derives out of `nodes`, and puts the derives back
+    /// on the items they were derived from, which they follow.
+    fn restore_derives<T>(
+        &self,
+        nodes: &mut Vec<T>,
+        item: fn(&mut T) -> Option<&mut P<ast::Item>>,
+    ) {
+        let mut restored = Vec::with_capacity(nodes.len());
+        let mut last_derive = None;
+        for mut node in nodes.drain(..) {
+            let expn_id = match item(&mut node).and_then(|item| self.kept_invocation(item.span)) {
+                Some((expn_id, MacroKind::Derive)) => expn_id,
+                _ => {
+                    restored.push(node);
+                    continue;
+                }
+            };
+            // A derive may expand to several items.
+            if last_derive == Some(expn_id) {
+                continue;
+            }
+            last_derive = Some(expn_id);
+            if let ExpnKind::Macro(_, path) = expn_id.expn_data().kind {
+                if let Some(derived) = restored.last_mut().and_then(item) {
+                    derived.attrs.push(derive_attr(path));
+                }
+            }
+        }
+        *nodes = restored;
+    }
+}
+
+/// Builds `#[derive(path)]`, where `path` is the path of a derive macro, as it's written.
+fn derive_attr(path: Symbol) -> ast::Attribute {
+    let segments = path
+        .as_str()
+        .split("::")
+        .map(|segment| {
+            let name = if segment.is_empty() { kw::PathRoot } else { Symbol::intern(segment) };
+            ast::PathSegment::from_ident(Ident::with_dummy_span(name))
+        })
+        .collect();
+    let path = ast::Path { span: DUMMY_SP, segments, tokens: None };
+    let derive = ast::MetaItem { path, kind: ast::MetaItemKind::Word, span: DUMMY_SP };
+    let list = attr::mk_list_item(
+        Ident::with_dummy_span(sym::derive),
+        vec![ast::NestedMetaItem::MetaItem(derive)],
+    );
+    attr::mk_attr_outer(list)
+}
+
+/// Finds the local variables written in a kept invocation which are renamed.
+struct RenamedLocals<'r, 'a, 'b> {
+    renamer: &'r Renamer<'a, 'b>,
+    /// The places of the local variables in the source, with their new names.
+    renamed: Vec<(Span, Symbol)>,
+}
+
+impl RenamedLocals<'_, '_, '_> {
+    fn visit_local(&mut self, id: NodeId, ident: Ident) {
+        if ident.span.ctxt() == SyntaxContext::root() {
+            if let Some(name) = self.renamer.local_name(id) {
+                self.renamed.push((ident.span, name));
+            }
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for RenamedLocals<'_, '_, '_> {
+    fn visit_pat(&mut self, pat: &'ast Pat) {
+        if let PatKind::Ident(_, ident, _) = pat.kind {
+            self.visit_local(pat.id, ident);
+        }
+        visit::walk_pat(self, pat);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        match &expr.kind {
+            ExprKind::Path(None, path) if path.segments.len() == 1 => {
+                self.visit_local(expr.id, path.segments[0].ident)
+            }
+            // The shorthand is expanded before the field's expression is renamed (which
+            // is then ignored, being at the same place).
+            ExprKind::Struct(_, fields, _) => {
+                for field in fields.iter().filter(|field| field.is_shorthand) {
+                    if let Some(name) = self.renamer.local_name(field.expr.id) {
+                        let field_name = format!("{}: {}", field.ident, name);
+                        self.renamed.push((field.ident.span, Symbol::intern(&field_name)));
+                    }
+                }
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+impl MutVisitor for Renamer<'_, '_> {
+    fn flat_map_item(&mut self, item: P<ast::Item>) -> SmallVec<[P<ast::Item>; 1]> {
+        if let ExpnKind::AstPass(_) = item.span.ctxt().outer_expn_data().kind {
+            return SmallVec::new();
+        }
+        if let Some((expn_id, MacroKind::Bang)) = self.kept_invocation(item.span) {
+            if self.printed.contains(&expn_id) {
+                return SmallVec::new();
+            }
+            let renamed = self.renamed_locals(|visitor| visitor.visit_item(&item));
+            if let Some(Some(invocation)) =
+                self.parse_invocation(expn_id, renamed, |parser| parser.parse_item())
+            {
+                self.printed.insert(expn_id);
+                return smallvec![invocation];
+            }
+        }
+        mut_visit::noop_flat_map_item(item, self)
+    }
+
+    fn flat_map_stmt(&mut self, stmt: ast::Stmt) -> SmallVec<[ast::Stmt; 1]> {
+        if let Some((expn_id, MacroKind::Bang)) = self.kept_invocation(stmt.span) {
+            if self.printed.contains(&expn_id) {
+                return SmallVec::new();
+            }
+            let renamed = self.renamed_locals(|visitor| visitor.visit_stmt(&stmt));
+            if let Some(Some(invocation)) =
+                self.parse_invocation(expn_id, renamed, |parser| parser.parse_full_stmt())
+            {
+                self.printed.insert(expn_id);
+                return smallvec![invocation];
+            }
+        }
+        mut_visit::noop_flat_map_stmt(stmt, self)
+    }
+
+    fn visit_mod(&mut self, module: &mut ast::Mod) {
+        mut_visit::noop_visit_mod(module, self);
+        self.restore_derives(&mut module.items, |item| Some(item));
+    }
+
+    fn visit_block(&mut self, block: &mut P<ast::Block>) {
+        mut_visit::noop_visit_block(block, self);
+        self.restore_derives(&mut block.stmts, |stmt| match &mut stmt.kind {
+            ast::StmtKind::Item(item) => Some(item),
+            _ => None,
+        });
+    }
+
+    fn visit_pat(&mut self, pat: &mut P<Pat>) {
+        mut_visit::noop_visit_pat(pat, self);
+        let id = pat.id;
+        match &mut pat.kind {
+            PatKind::Ident(_, ident, _) => self.rename_local(id, ident),
+            PatKind::Struct(_, fields, _) => {
+                for field in fields {
+                    if let PatKind::Ident(_, ident, _) = field.pat.kind {
+                        if ident.name != field.ident.name {
+                            field.is_shorthand = false;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &mut P<Expr>) {
+        if let Some((expn_id, MacroKind::Bang)) = self.kept_invocation(expr.span) {
+            let renamed = self.renamed_locals(|visitor| visitor.visit_expr(expr));
+            if let Some(invocation) =
+                self.parse_invocation(expn_id, renamed, |parser| parser.parse_expr())
+            {
+                *expr = invocation;
+                return;
+            }
+        }
+        mut_visit::noop_visit_expr(expr, self);
+        let id = expr.id;
+        match &mut expr.kind {
+            ExprKind::Path(None, path) if path.segments.len() == 1 => {
+                self.rename_local(id, &mut path.segments[0].ident)
+            }
+            ExprKind::Struct(_, fields, _) => {
+                for field in fields {
+                    if let ExprKind::Path(None, path) = &field.expr.kind {
+                        if path.segments[0].ident.name != field.ident.name {
+                            field.is_shorthand = false;
+                        }
+                    }
+                }
+            }
+            ExprKind::While(_, _, label)
+            | ExprKind::ForLoop(_, _, _, label)
+            | ExprKind::Loop(_, label) => self.rename_label(id, label),
+            ExprKind::Block(block, label) => self.rename_label(block.id, label),
+            ExprKind::Break(label, _) | ExprKind::Continue(label) => {
+                if let Some(&target) = self.r.label_res_map.get(&id) {
+                    self.rename_label(target, label);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn sorted(symbols: FxHashSet<Symbol>) -> Vec<Symbol> {
+    let mut symbols: Vec<_> = symbols.into_iter().collect();
+    symbols.sort_by_cached_key(|symbol| symbol.to_string());
+    symbols
+}
+
+impl<'a> Resolver<'a> {
+    /// Prints `krate`, which has been expanded and resolved, as source
+    /// code which compiles to the same crate (see `--emit=expanded`).
+    pub fn print_expanded_crate(
+        &self,
+        krate: &ast::Crate,
+        filename: FileName,
+        src: String,
+    ) -> String {
+        let mut collector = Collector {
+            r: self,
+            names: Default::default(),
+            bindings: Vec::new(),
+            labels: Vec::new(),
+            names_of_items_in_macros: Default::default(),
+            seen_ctxts: Default::default(),
+            kept: Default::default(),
+            crates: Vec::new(),
+        };
+        visit::walk_crate(&mut collector, krate);
+
+        // What's only in the expansion of the kept invocations isn't printed.
+        let kept = collector.kept;
+        let is_printed = |&(_, span): &(Symbol, Span)| match written_invocation(span) {
+            Some((expn_id, _)) => !kept.contains_key(&expn_id),
+            None => true,
+        };
+
+        let mut names = collector.names;
+        let mut fresh_name = |name: Symbol| {
+            (1..)
+                .map(|i| Symbol::intern(&format!("{}_{}", name, i)))
+                .find(|&name| names.insert(name))
+                .unwrap()
+        };
+        let names_of_items_in_macros: FxHashSet<Symbol> = collector
+            .names_of_items_in_macros
+            .into_iter()
+            .filter(is_printed)
+            .map(|(name, _)| name)
+            .collect();
+        let bindings = collector
+            .bindings
+            .into_iter()
+            .filter(|&(_, ident)| {
+                !ident.is_reserved()
+                    && (is_hygienic(ident) || names_of_items_in_macros.contains(&ident.name))
+            })
+            .map(|(id, ident)| (id, fresh_name(ident.name)))
+            .collect();
+        let labels = collector
+            .labels
+            .into_iter()
+            .filter(|&(_, ident)| is_hygienic(ident))
+            .map(|(id, ident)| (id, fresh_name(ident.name)))
+            .collect();
+
+        let mut krate = krate.clone();
+
+        // The crates which are already declared at the crate root (including the
+        // injected standard library, which will be injected again) don't need to be.
+        let mut crates: FxHashSet<Symbol> =
+            collector.crates.into_iter().filter(is_printed).map(|(name, _)| name).collect();
+        for item in &krate.module.items {
+            if let ast::ItemKind::ExternCrate(None) = item.kind {
+                crates.remove(&item.ident.name);
+            }
+        }
+        let extern_crates = sorted(crates).into_iter().map(|name| {
+            P(ast::Item {
+                attrs: Vec::new(),
+                id: ast::DUMMY_NODE_ID,
+                span: DUMMY_SP,
+                vis: ast::Visibility {
+                    kind: ast::VisibilityKind::Inherited,
+                    span: DUMMY_SP,
+                    tokens: None,
+                },
+                ident: Ident::with_dummy_span(name),
+                kind: ast::ItemKind::ExternCrate(None),
+                tokens: None,
+            })
+        });
+        krate.module.items.splice(0..0, extern_crates);
+
+        Renamer { r: self, bindings, labels, kept, printed: Default::default() }
+            .visit_crate(&mut krate);
+
+        pprust::print_crate(
+            self.session.source_map(),
+            &krate,
+            filename,
+            src,
+            &pprust::NoAnn,
+            true,
+            self.session.edition(),
+            false,
+        )
+    }
+}
@@ -1355,6 +1355,10 @@ impl<'a: 'ast, 'b, 'ast> LateResolutionVisitor<'a, 'b, 'ast> {
         // Resolve the initializer.
         walk_list!(self, visit_expr, &local.init);
 
+        // Resolve the `else` block of a `let...else`, which cannot refer to
+        // the bindings of the pattern.
+        walk_list!(self, visit_block, &local.els);
+
         // Resolve the pattern.
         self.resolve_pattern_top(&local.pat, PatternSource::Let);
     }
@@ -72,6 +72,7 @@ mod build_reduced_graph;
 mod check_unused;
 mod def_collector;
 mod diagnostics;
+mod expanded;
 mod imports;
 mod late;
 mod macros;
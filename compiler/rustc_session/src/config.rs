@@ -237,6 +237,7 @@ pub enum OutputType {
     Object,
     Exe,
     DepInfo,
+    Expanded,
 }
 
 impl_stable_hash_via_hash!(OutputType);
@@ -244,7 +245,9 @@ impl_stable_hash_via_hash!(OutputType);
 impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
-            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata => true,
+            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata | OutputType::Expanded => {
+                true
+            }
             OutputType::Bitcode
             | OutputType::Assembly
             | OutputType::LlvmAssembly
@@ -263,6 +266,7 @@ impl OutputType {
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::Expanded => "expanded",
         }
     }
 
@@ -276,13 +280,14 @@ impl OutputType {
             "metadata" => OutputType::Metadata,
             "link" => OutputType::Exe,
             "dep-info" => OutputType::DepInfo,
+            "expanded" => OutputType::Expanded,
             _ => return None,
         })
     }
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
@@ -291,6 +296,7 @@ impl OutputType {
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
             OutputType::DepInfo.shorthand(),
+            OutputType::Expanded.shorthand(),
         )
     }
 
@@ -303,6 +309,7 @@ impl OutputType {
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
+            OutputType::Expanded => "expanded.rs",
             OutputType::Exe => "",
         }
     }
@@ -388,7 +395,7 @@ impl OutputTypes {
             | OutputType::Mir
             | OutputType::Object
             | OutputType::Exe => true,
-            OutputType::Metadata | OutputType::DepInfo => false,
+            OutputType::Metadata | OutputType::DepInfo | OutputType::Expanded => false,
         })
     }
 }
@@ -996,7 +1003,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "emit",
             "Comma separated list of types of output for \
              the compiler to emit",
-            "[asm|llvm-bc|llvm-ir|obj|metadata|link|dep-info|mir]",
+            "[asm|llvm-bc|llvm-ir|obj|metadata|link|dep-info|mir|expanded]",
         ),
         opt::multi_s(
             "",
@@ -1881,6 +1881,42 @@ declare_lint! {
     "detects code samples in docs of private items not documented by rustdoc"
 }
 
+declare_lint! {
+    /// The `missing_doc_sections` lint detects public functions whose
+    /// documentation is missing a `# Safety`, `# Errors` or `# Panics` section
+    /// they call for. This is a `rustdoc` only lint, see the documentation in
+    /// the [rustdoc book].
+    ///
+    /// [rustdoc book]: ../../../rustdoc/lints.html#missing_doc_sections
+    pub MISSING_DOC_SECTIONS,
+    Allow,
+    "detects missing `# Safety`, `# Errors` and `# Panics` sections in docs of public functions"
+}
+
+declare_lint! {
+    /// The `unused_doc_sections` lint detects `# Safety` and `# Errors`
+    /// sections in the documentation of functions which aren't `unsafe` or
+    /// don't return a `Result`. This is a `rustdoc` only lint, see the
+    /// documentation in the [rustdoc book].
+    ///
+    /// [rustdoc book]: ../../../rustdoc/lints.html#unused_doc_sections
+    pub UNUSED_DOC_SECTIONS,
+    Allow,
+    "detects `# Safety` and `# Errors` sections in documentation of functions they don't apply to"
+}
+
+declare_lint! {
+    /// The `contradictory_doc_cfg` lint detects `#[doc(cfg(...))]`
+    /// attributes which can't hold together with the `#[cfg]` the item is
+    /// compiled under. This is a `rustdoc` only lint, see the documentation
+    /// in the [rustdoc book].
+    ///
+    /// [rustdoc book]: ../../../rustdoc/lints.html#contradictory_doc_cfg
+    pub CONTRADICTORY_DOC_CFG,
+    Warn,
+    "detects `#[doc(cfg)]` attributes contradicting the `#[cfg]` of the item"
+}
+
 declare_lint! {
     /// The `where_clauses_object_safety` lint detects for [object safety] of
     /// [where clauses].
@@ -2700,6 +2736,9 @@ declare_lint_pass! {
         MISSING_CRATE_LEVEL_DOCS,
         MISSING_DOC_CODE_EXAMPLES,
         PRIVATE_DOC_TESTS,
+        MISSING_DOC_SECTIONS,
+        UNUSED_DOC_SECTIONS,
+        CONTRADICTORY_DOC_CFG,
         WHERE_CLAUSES_OBJECT_SAFETY,
         PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
         MACRO_USE_EXTERN_CRATE,
@@ -844,8 +844,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "select which borrowck is used (`mir` or `migrate`) (default: `migrate`)"),
     borrowck_stats: bool = (false, parse_bool, [UNTRACKED],
         "gather borrowck statistics (default: no)"),
+    cache_proc_macros: bool = (false, parse_bool, [UNTRACKED],
+        "reuse the outputs of pure proc macros from previous incremental compilations, \
+        if their inputs are unchanged (default: no)"),
     cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED],
-        "the codegen unit partitioning strategy to use"),
+        "the codegen unit partitioning strategy to use: `default`, or `function` to give every \
+        function its own codegen unit in incremental builds (default: `default`)"),
     chalk: bool = (false, parse_bool, [TRACKED],
         "enable the experimental Chalk-based trait solving engine"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -915,11 +919,18 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    incremental_explain: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "explain why the queries and codegen units of the item with the given path \
+        (e.g. `foo::bar`) could not be reused from the incremental cache"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
+    incremental_snapshots: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "a directory of snapshots of finalized incremental session directories, shared \
+        between several incremental directories, to seed sessions without a previous local \
+        session from"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1019,6 +1030,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "print layout information for each type encountered (default: no)"),
     proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
          "show backtraces for panics during proc-macro execution (default: no)"),
+    proc_macro_out_of_process: bool = (false, parse_bool, [UNTRACKED],
+        "run proc macros in a separate process, isolating the compiler from their \
+        crashes and leaks (default: no)"),
     profile: bool = (false, parse_bool, [TRACKED],
         "insert profiling code (default: no)"),
     profile_emit: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
@@ -1027,7 +1041,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "enable queries of the dependency graph for regression testing (default: no)"),
     query_stats: bool = (false, parse_bool, [UNTRACKED],
-        "print some statistics about the query system (default: no)"),
+        "print some statistics about the query system, including the memory used by each \
+        query; this slows down compilation (default: no)"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
         "choose which RELRO level to use"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
@@ -1096,6 +1111,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
     trace_macros: bool = (false, parse_bool, [UNTRACKED],
         "for every macro invocation, print its name and arguments (default: no)"),
+    trace_macros_json: bool = (false, parse_bool, [UNTRACKED],
+        "for every `macro_rules!` invocation, print a JSON trace of which arms \
+        matched or failed, and what their fragments bound to (default: no)"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
         "treat error number `val` that occurs as bug"),
     trim_diagnostic_paths: bool = (true, parse_bool, [UNTRACKED],
@@ -280,6 +280,7 @@ symbols! {
         attr_literals,
         attributes,
         augmented_assignments,
+        auto_cfg,
         automatically_derived,
         avx512_target_feature,
         await_macro,
@@ -321,6 +322,7 @@ symbols! {
         cfg_attr,
         cfg_attr_multi,
         cfg_doctest,
+        cfg_hide,
         cfg_sanitize,
         cfg_target_feature,
         cfg_target_has_atomic,
@@ -427,6 +429,7 @@ symbols! {
         deref_mut,
         deref_target,
         derive,
+        derive_default_enum,
         diagnostic,
         direct,
         discriminant_kind,
@@ -613,6 +616,7 @@ symbols! {
         lazy_normalization_consts,
         le,
         let_chains,
+        let_else,
         lhs,
         lib,
         libc,
@@ -1,5 +1,6 @@
 use crate::check::coercion::CoerceMany;
 use crate::check::{Diverges, Expectation, FnCtxt, Needs};
+use rustc_errors::struct_span_err;
 use rustc_hir::{self as hir, ExprKind};
 use rustc_infer::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
 use rustc_infer::traits::Obligation;
@@ -114,6 +115,15 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
                 // clause to avoid duplicated type errors. (#60254)
                 self.check_expr_with_expectation(&arm.body, expected)
             };
+            // The `else` block of a `let...else` must diverge, as the bindings of the
+            // pattern would be unavailable in the rest of the block otherwise.
+            let is_let_else_block = match_src == LetElseDesugar && i != 0;
+            let arm_ty = if is_let_else_block && !self.diverges.get().is_always() {
+                self.report_non_diverging_let_else(&arm.body, arm_ty);
+                tcx.ty_error()
+            } else {
+                arm_ty
+            };
             all_arms_diverge &= self.diverges.get();
 
             // When we have a `match` as a tail expression in a `fn` with a returned `impl Trait`
@@ -252,6 +262,7 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         let msg = match source {
             IfDesugar { .. } | IfLetDesugar { .. } => "block in `if` expression",
             WhileDesugar { .. } | WhileLetDesugar { .. } => "block in `while` expression",
+            LetElseDesugar => "statement",
             _ => "arm",
         };
         for arm in arms {
@@ -259,6 +270,22 @@ impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
         }
     }
 
+    fn report_non_diverging_let_else(&self, else_expr: &'tcx hir::Expr<'tcx>, else_ty: Ty<'tcx>) {
+        let mut err = struct_span_err!(
+            self.tcx.sess,
+            else_expr.span,
+            E0308,
+            "`else` clause of `let...else` does not diverge"
+        );
+        err.span_label(
+            else_expr.span,
+            format!("expected `!`, found `{}`", self.resolve_vars_if_possible(&else_ty)),
+        );
+        err.help("try adding a diverging expression, such as `return` or `panic!(..)`");
+        err.help("or use `match` instead of `let...else`");
+        err.emit();
+    }
+
     /// Handle the fallback arm of a desugared if(-let) like a missing else.
     ///
     /// Returns `true` if there was an error forcing the coercion to the `()` type.
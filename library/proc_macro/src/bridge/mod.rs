@@ -13,7 +13,7 @@ use std::fmt;
 use std::hash::Hash;
 use std::marker;
 use std::mem;
-use std::ops::Bound;
+use std::ops::{Bound, Range};
 use std::panic;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Once;
@@ -55,6 +55,8 @@ macro_rules! with_api {
             FreeFunctions {
                 fn drop($self: $S::FreeFunctions);
                 fn track_env_var(var: &str, value: Option<&str>);
+                fn track_path(path: &str);
+                fn declare_pure();
             },
             TokenStream {
                 fn drop($self: $S::TokenStream);
@@ -63,6 +65,7 @@ macro_rules! with_api {
                 fn is_empty($self: &$S::TokenStream) -> bool;
                 fn from_str(src: &str) -> $S::TokenStream;
                 fn to_string($self: &$S::TokenStream) -> String;
+                fn expand_expr($self: &$S::TokenStream) -> Result<$S::TokenStream, ()>;
                 fn from_token_tree(
                     tree: TokenTree<$S::Group, $S::Punct, $S::Ident, $S::Literal>,
                 ) -> $S::TokenStream;
@@ -159,6 +162,7 @@ macro_rules! with_api {
                 fn source($self: $S::Span) -> $S::Span;
                 fn start($self: $S::Span) -> LineColumn;
                 fn end($self: $S::Span) -> LineColumn;
+                fn byte_range($self: $S::Span) -> Range<usize>;
                 fn join($self: $S::Span, other: $S::Span) -> Option<$S::Span>;
                 fn resolved_at($self: $S::Span, at: $S::Span) -> $S::Span;
                 fn source_text($self: $S::Span) -> Option<String>;
@@ -195,6 +199,8 @@ pub mod client;
 mod closure;
 #[forbid(unsafe_code)]
 mod handle;
+#[forbid(unsafe_code)]
+pub mod process;
 #[macro_use]
 #[forbid(unsafe_code)]
 mod rpc;
@@ -313,6 +319,19 @@ impl<T: Unmark> Unmark for Option<T> {
     }
 }
 
+impl<T: Mark, E: Mark> Mark for Result<T, E> {
+    type Unmarked = Result<T::Unmarked, E::Unmarked>;
+    fn mark(unmarked: Self::Unmarked) -> Self {
+        unmarked.map(T::mark).map_err(E::mark)
+    }
+}
+impl<T: Unmark, E: Unmark> Unmark for Result<T, E> {
+    type Unmarked = Result<T::Unmarked, E::Unmarked>;
+    fn unmark(self) -> Self::Unmarked {
+        self.map(T::unmark).map_err(E::unmark)
+    }
+}
+
 macro_rules! mark_noop {
     ($($ty:ty),* $(,)?) => {
         $(
@@ -343,6 +362,7 @@ mark_noop! {
     LineColumn,
     Spacing,
     Bound<usize>,
+    Range<usize>,
 }
 
 rpc_encode_decode!(
@@ -0,0 +1,431 @@
+//! Running proc macros in a separate process.
+//!
+//! The bridge protocol itself is unchanged: requests, responses, inputs and
+//! outputs are the same `Buffer<u8>`s used for in-process execution, they're
+//! only framed (see `write_message` and `read_message`) and sent over a pipe,
+//! instead of being passed through the `dispatch` closure of a `Bridge`.
+//!
+//! The compiler (the server) uses a `Connection` to ask a proc macro server
+//! process to list the proc macros of a crate, or to run one of them. Crates
+//! are identified by an opaque "locator" chosen by the compiler, and proc
+//! macros by their index in their crate. The proc macro server process runs
+//! `serve`, which uses the locator to load the crate and then runs the client
+//! of the proc macro with a `Bridge` that forwards all requests back to the
+//! compiler.
+//!
+//! While handling a request, the compiler may need to run another proc macro
+//! (e.g. for `TokenStream::expand_expr`). It then sends `RUN` instead of the
+//! `RESPONSE` the proc macro server process is waiting for, which runs the
+//! nested proc macro to completion before it goes back to waiting. Messages
+//! of nested runs are therefore always strictly nested within the outer run.
+
+use super::client::HandleCounters;
+use super::server::{run_server, DispatcherTrait, ExecutionStrategy, MarkedTypes, Server, Types};
+use super::*;
+
+use std::cell::RefCell;
+use std::convert::TryInto;
+use std::io::{self, Read, Write};
+use std::sync::{Mutex, MutexGuard};
+
+/// Server to proc macro server process: list the proc macros of the crate at
+/// `locator`.
+const LIST: u8 = 0;
+/// Proc macro server process to server: the proc macros of a crate, or why
+/// they couldn't be loaded.
+const LISTED: u8 = 1;
+/// Server to proc macro server process: run the proc macro at `index` in the
+/// crate at `locator` with `input` (and `force_show_panics`).
+const RUN: u8 = 2;
+/// Proc macro server process to server: dispatch a bridge request.
+const REQUEST: u8 = 3;
+/// Server to proc macro server process: the result of a bridge request.
+const RESPONSE: u8 = 4;
+/// Proc macro server process to server: the proc macro finished running.
+const DONE: u8 = 5;
+
+fn write_message(w: &mut impl Write, tag: u8, parts: &[&[u8]]) -> io::Result<()> {
+    w.write_all(&[tag])?;
+    w.write_all(&(parts.len() as u32).to_le_bytes())?;
+    for part in parts {
+        w.write_all(&(part.len() as u64).to_le_bytes())?;
+        w.write_all(part)?;
+    }
+    w.flush()
+}
+
+/// Reads a message written by `write_message`, returning `None` if
+/// the other end was closed (cleanly) before the start of a message.
+fn read_message(r: &mut impl Read) -> io::Result<Option<(u8, Vec<Vec<u8>>)>> {
+    let mut tag = [0; 1];
+    match r.read_exact(&mut tag) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let mut len = [0; 4];
+    r.read_exact(&mut len)?;
+    let count = u32::from_le_bytes(len);
+
+    let mut parts = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let mut len = [0; 8];
+        r.read_exact(&mut len)?;
+        let mut part = vec![0; u64::from_le_bytes(len) as usize];
+        r.read_exact(&mut part)?;
+        parts.push(part);
+    }
+    Ok(Some((tag[0], parts)))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn exited_unexpectedly() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "proc macro server exited unexpectedly")
+}
+
+/// Encodes `Err(msg)` the way a client encodes a panic in its output,
+/// or a server encodes a panic in its response to a request.
+fn encode_panic(msg: String) -> Buffer<u8> {
+    let mut b = Buffer::new();
+    Err::<(), _>(PanicMessage::String(msg)).encode(&mut b, &mut ());
+    b
+}
+
+/// What the server needs to know about a proc macro without loading its crate.
+#[derive(Clone, Debug)]
+pub enum ProcMacroDecl {
+    CustomDerive { trait_name: String, attributes: Vec<String> },
+    Attr { name: String },
+    Bang { name: String },
+}
+
+impl ProcMacroDecl {
+    pub fn name(&self) -> &str {
+        match self {
+            ProcMacroDecl::CustomDerive { trait_name, .. } => trait_name,
+            ProcMacroDecl::Attr { name } | ProcMacroDecl::Bang { name } => name,
+        }
+    }
+
+    fn from_proc_macro(proc_macro: &client::ProcMacro) -> Self {
+        match *proc_macro {
+            client::ProcMacro::CustomDerive { trait_name, attributes, .. } => {
+                ProcMacroDecl::CustomDerive {
+                    trait_name: trait_name.to_owned(),
+                    attributes: attributes.iter().map(|&attr| attr.to_owned()).collect(),
+                }
+            }
+            client::ProcMacro::Attr { name, .. } => ProcMacroDecl::Attr { name: name.to_owned() },
+            client::ProcMacro::Bang { name, .. } => ProcMacroDecl::Bang { name: name.to_owned() },
+        }
+    }
+
+    /// Encodes the declaration as two message parts: the kind followed by the
+    /// name, and the `\0`-separated helper attributes of a derive.
+    fn encode(&self) -> [Vec<u8>; 2] {
+        let (kind, attributes) = match self {
+            ProcMacroDecl::CustomDerive { attributes, .. } => (b'd', attributes.join("\0")),
+            ProcMacroDecl::Attr { .. } => (b'a', String::new()),
+            ProcMacroDecl::Bang { .. } => (b'b', String::new()),
+        };
+        let mut head = vec![kind];
+        head.extend_from_slice(self.name().as_bytes());
+        [head, attributes.into_bytes()]
+    }
+
+    fn decode(head: Vec<u8>, attributes: Vec<u8>) -> io::Result<Self> {
+        let malformed = || invalid_data("malformed proc macro declaration");
+        let (&kind, name) = head.split_first().ok_or_else(malformed)?;
+        let name = String::from_utf8(name.to_vec()).map_err(|_| malformed())?;
+        let attributes = String::from_utf8(attributes).map_err(|_| malformed())?;
+        match kind {
+            b'd' => Ok(ProcMacroDecl::CustomDerive {
+                trait_name: name,
+                attributes: attributes.split_terminator('\0').map(str::to_owned).collect(),
+            }),
+            b'a' => Ok(ProcMacroDecl::Attr { name }),
+            b'b' => Ok(ProcMacroDecl::Bang { name }),
+            _ => Err(malformed()),
+        }
+    }
+}
+
+/// The server's end of the pipes to a proc macro server process.
+///
+/// The pipes are only locked for reading or writing a single message, so that
+/// proc macros can be run by the server while it's handling a request of
+/// another proc macro (see the module documentation).
+pub struct Connection {
+    reader: Mutex<Box<dyn Read + Send>>,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+/// Locks `pipe`, ignoring poisoning: the lock is never held while running
+/// anything else than reads or writes of whole messages.
+fn lock<T: ?Sized>(pipe: &Mutex<T>) -> MutexGuard<'_, T> {
+    pipe.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Connection {
+    pub fn new(reader: impl Read + Send + 'static, writer: impl Write + Send + 'static) -> Self {
+        Connection { reader: Mutex::new(Box::new(reader)), writer: Mutex::new(Box::new(writer)) }
+    }
+
+    fn write(&self, tag: u8, parts: &[&[u8]]) -> io::Result<()> {
+        write_message(&mut *lock(&self.writer), tag, parts)
+    }
+
+    fn read(&self) -> io::Result<(u8, Vec<Vec<u8>>)> {
+        read_message(&mut *lock(&self.reader))?.ok_or_else(exited_unexpectedly)
+    }
+
+    /// Lists the proc macros of the crate at `locator`, in the order of their
+    /// indices. The inner `Err` is the reason the crate couldn't be loaded.
+    pub fn list(&self, locator: &[u8]) -> io::Result<Result<Vec<ProcMacroDecl>, String>> {
+        self.write(LIST, &[locator])?;
+        match self.read()? {
+            (LISTED, parts) if parts.len() == 1 => {
+                Ok(Err(String::from_utf8_lossy(&parts[0]).into_owned()))
+            }
+            (LISTED, parts) if parts.len() % 2 == 0 => {
+                let mut parts = parts.into_iter();
+                let mut decls = vec![];
+                while let (Some(head), Some(attributes)) = (parts.next(), parts.next()) {
+                    decls.push(ProcMacroDecl::decode(head, attributes)?);
+                }
+                Ok(Ok(decls))
+            }
+            _ => Err(invalid_data("unexpected message from proc macro server")),
+        }
+    }
+
+    /// Runs a derive or function-like proc macro in the proc macro server process.
+    /// The outer `Err` means that the connection to the proc macro server failed.
+    pub fn expand1<S: Server>(
+        &self,
+        locator: &[u8],
+        index: u32,
+        server: S,
+        input: S::TokenStream,
+        force_show_panics: bool,
+    ) -> io::Result<Result<S::TokenStream, PanicMessage>> {
+        let strategy = OutOfProcess { connection: self, locator, index, error: RefCell::new(None) };
+        let result = run_server(
+            &strategy,
+            HandleCounters::get(),
+            server,
+            <MarkedTypes<S> as Types>::TokenStream::mark(input),
+            run_remote,
+            (),
+            force_show_panics,
+        );
+        match strategy.error.into_inner() {
+            Some(e) => Err(e),
+            None => Ok(result.map(<MarkedTypes<S> as Types>::TokenStream::unmark)),
+        }
+    }
+
+    /// Runs an attribute proc macro in the proc macro server process.
+    /// The outer `Err` means that the connection to the proc macro server failed.
+    pub fn expand2<S: Server>(
+        &self,
+        locator: &[u8],
+        index: u32,
+        server: S,
+        input: S::TokenStream,
+        input2: S::TokenStream,
+        force_show_panics: bool,
+    ) -> io::Result<Result<S::TokenStream, PanicMessage>> {
+        let strategy = OutOfProcess { connection: self, locator, index, error: RefCell::new(None) };
+        let result = run_server(
+            &strategy,
+            HandleCounters::get(),
+            server,
+            (
+                <MarkedTypes<S> as Types>::TokenStream::mark(input),
+                <MarkedTypes<S> as Types>::TokenStream::mark(input2),
+            ),
+            run_remote,
+            (),
+            force_show_panics,
+        );
+        match strategy.error.into_inner() {
+            Some(e) => Err(e),
+            None => Ok(result.map(<MarkedTypes<S> as Types>::TokenStream::unmark)),
+        }
+    }
+
+    fn run(
+        &self,
+        locator: &[u8],
+        index: u32,
+        input: Buffer<u8>,
+        force_show_panics: bool,
+        dispatcher: &mut impl DispatcherTrait,
+    ) -> io::Result<Buffer<u8>> {
+        self.write(RUN, &[locator, &index.to_le_bytes(), &input, &[force_show_panics as u8]])?;
+        loop {
+            match self.read()? {
+                (REQUEST, mut parts) if parts.len() == 1 => {
+                    let b = dispatcher.dispatch(Buffer::from(parts.pop().unwrap()));
+                    self.write(RESPONSE, &[&b])?;
+                }
+                (DONE, mut parts) if parts.len() == 1 => {
+                    return Ok(Buffer::from(parts.pop().unwrap()));
+                }
+                _ => return Err(invalid_data("unexpected message from proc macro server")),
+            }
+        }
+    }
+}
+
+/// Stand-in for the `run` function of a `client::Client`, which
+/// only exists in the proc macro server process.
+extern "C" fn run_remote(_: Bridge<'_>, _: ()) -> Buffer<u8> {
+    unreachable!("out-of-process proc macros are run by the proc macro server")
+}
+
+struct OutOfProcess<'a> {
+    connection: &'a Connection,
+    locator: &'a [u8],
+    index: u32,
+    /// Set if talking to the proc macro server failed.
+    error: RefCell<Option<io::Error>>,
+}
+
+impl ExecutionStrategy for OutOfProcess<'_> {
+    fn run_bridge_and_client<D: Copy + Send + 'static>(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer<u8>,
+        _run_client: extern "C" fn(Bridge<'_>, D) -> Buffer<u8>,
+        _client_data: D,
+        force_show_panics: bool,
+    ) -> Buffer<u8> {
+        // `run_server` still needs an output to decode, the error itself is
+        // returned by `expand1` and `expand2`.
+        self.connection
+            .run(self.locator, self.index, input, force_show_panics, dispatcher)
+            .unwrap_or_else(|e| {
+                let msg = e.to_string();
+                *self.error.borrow_mut() = Some(e);
+                encode_panic(msg)
+            })
+    }
+}
+
+/// Runs the proc macro server process side of the protocol, reading from
+/// `reader` and writing to `writer`, until the server closes its end of the
+/// pipe.
+///
+/// `find` maps the crate locators sent by the server to the proc macros of
+/// the crate, with an `Err` being reported to the server as the reason the
+/// crate couldn't be loaded, or as a panic of the proc macro it tried to run.
+pub fn serve(
+    reader: impl Read,
+    writer: impl Write,
+    find: impl FnMut(&[u8]) -> Result<&'static [client::ProcMacro], String>,
+) -> io::Result<()> {
+    let mut serving = Serving { reader, writer, find };
+    loop {
+        match read_message(&mut serving.reader)? {
+            Some((LIST, parts)) if parts.len() == 1 => serving.list(&parts[0])?,
+            Some((RUN, parts)) => serving.run(parts)?,
+            Some(_) => return Err(invalid_data("unexpected message from the server")),
+            None => return Ok(()),
+        }
+    }
+}
+
+struct Serving<R, W, F> {
+    reader: R,
+    writer: W,
+    find: F,
+}
+
+impl<R, W, F> Serving<R, W, F>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&[u8]) -> Result<&'static [client::ProcMacro], String>,
+{
+    fn list(&mut self, locator: &[u8]) -> io::Result<()> {
+        match (self.find)(locator) {
+            Ok(proc_macros) => {
+                let parts: Vec<_> = proc_macros
+                    .iter()
+                    .flat_map(|proc_macro| ProcMacroDecl::from_proc_macro(proc_macro).encode())
+                    .collect();
+                let parts: Vec<&[u8]> = parts.iter().map(|part| &part[..]).collect();
+                write_message(&mut self.writer, LISTED, &parts)
+            }
+            Err(msg) => write_message(&mut self.writer, LISTED, &[msg.as_bytes()]),
+        }
+    }
+
+    fn run(&mut self, parts: Vec<Vec<u8>>) -> io::Result<()> {
+        let (locator, index, input, force_show_panics) = match &parts[..] {
+            [locator, index, input, force_show_panics] => {
+                let index: [u8; 4] =
+                    index[..].try_into().map_err(|_| invalid_data("malformed proc macro index"))?;
+                (&locator[..], u32::from_le_bytes(index), input, force_show_panics[..] == [1])
+            }
+            _ => return Err(invalid_data("malformed request to run a proc macro")),
+        };
+
+        let proc_macro = (self.find)(locator).and_then(|proc_macros| {
+            proc_macros.get(index as usize).ok_or_else(|| format!("no proc macro #{}", index))
+        });
+        let output = match proc_macro {
+            Ok(proc_macro) => {
+                let mut error = None;
+                let mut dispatch = |b: Buffer<u8>| {
+                    // The client turns this into a panic, just like
+                    // a panic in the server handling the request.
+                    self.request(b).unwrap_or_else(|e| {
+                        let msg = format!("lost connection to the server: {}", e);
+                        error.get_or_insert(e);
+                        encode_panic(msg)
+                    })
+                };
+                let bridge = Bridge {
+                    cached_buffer: Buffer::from(input.clone()),
+                    dispatch: (&mut dispatch).into(),
+                    force_show_panics,
+                };
+                let output = match proc_macro {
+                    client::ProcMacro::CustomDerive { client, .. }
+                    | client::ProcMacro::Bang { client, .. } => (client.run)(bridge, client.f),
+                    client::ProcMacro::Attr { client, .. } => (client.run)(bridge, client.f),
+                };
+                if let Some(e) = error {
+                    return Err(e);
+                }
+                output
+            }
+            Err(msg) => encode_panic(msg),
+        };
+
+        write_message(&mut self.writer, DONE, &[&output])
+    }
+
+    /// Sends a bridge request to the server and waits for its response,
+    /// running any proc macros the server needs to handle the request.
+    fn request(&mut self, b: Buffer<u8>) -> io::Result<Buffer<u8>> {
+        write_message(&mut self.writer, REQUEST, &[&b])?;
+        loop {
+            match read_message(&mut self.reader)? {
+                Some((RESPONSE, mut parts)) if parts.len() == 1 => {
+                    return Ok(Buffer::from(parts.pop().unwrap()));
+                }
+                Some((RUN, parts)) => self.run(parts)?,
+                Some(_) => return Err(invalid_data("expected a response from the server")),
+                None => return Err(exited_unexpectedly()),
+            }
+        }
+    }
+}
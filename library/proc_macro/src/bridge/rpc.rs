@@ -4,7 +4,7 @@ use std::any::Any;
 use std::char;
 use std::io::Write;
 use std::num::NonZeroU32;
-use std::ops::Bound;
+use std::ops::{Bound, Range};
 use std::str;
 
 pub(super) type Writer = super::buffer::Buffer<u8>;
@@ -104,6 +104,19 @@ macro_rules! rpc_encode_decode {
     }
 }
 
+impl<S, T: Encode<S>> Encode<S> for Range<T> {
+    fn encode(self, w: &mut Writer, s: &mut S) {
+        self.start.encode(w, s);
+        self.end.encode(w, s);
+    }
+}
+
+impl<S, T: for<'s> DecodeMut<'a, 's, S>> DecodeMut<'a, '_, S> for Range<T> {
+    fn decode(r: &mut Reader<'a>, s: &mut S) -> Self {
+        Range { start: T::decode(r, s), end: T::decode(r, s) }
+    }
+}
+
 impl<S> Encode<S> for () {
     fn encode(self, _: &mut Writer, _: &mut S) {}
 }
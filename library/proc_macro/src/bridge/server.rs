@@ -268,7 +268,7 @@ impl ExecutionStrategy for CrossThread2 {
     }
 }
 
-fn run_server<
+pub(super) fn run_server<
     S: Server,
     I: Encode<HandleStore<MarkedTypes<S>>>,
     O: for<'a, 's> DecodeMut<'a, 's, HandleStore<MarkedTypes<S>>>,
@@ -1,7 +1,7 @@
 use crate::Span;
 
 /// An enum representing a diagnostic level.
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 #[derive(Copy, Clone, Debug)]
 #[non_exhaustive]
 pub enum Level {
@@ -16,27 +16,27 @@ pub enum Level {
 }
 
 /// Trait implemented by types that can be converted into a set of `Span`s.
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 pub trait MultiSpan {
     /// Converts `self` into a `Vec<Span>`.
     fn into_spans(self) -> Vec<Span>;
 }
 
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 impl MultiSpan for Span {
     fn into_spans(self) -> Vec<Span> {
         vec![self]
     }
 }
 
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 impl MultiSpan for Vec<Span> {
     fn into_spans(self) -> Vec<Span> {
         self
     }
 }
 
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 impl<'a> MultiSpan for &'a [Span] {
     fn into_spans(self) -> Vec<Span> {
         self.to_vec()
@@ -45,7 +45,7 @@ impl<'a> MultiSpan for &'a [Span] {
 
 /// A structure representing a diagnostic message and associated children
 /// messages.
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 #[derive(Clone, Debug)]
 pub struct Diagnostic {
     level: Level,
@@ -59,7 +59,7 @@ macro_rules! diagnostic_child_methods {
         /// Adds a new child diagnostic message to `self` with the level
         /// identified by this method's name with the given `spans` and
         /// `message`.
-        #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+        #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
         pub fn $spanned<S, T>(mut self, spans: S, message: T) -> Diagnostic
         where
             S: MultiSpan,
@@ -71,7 +71,7 @@ macro_rules! diagnostic_child_methods {
 
         /// Adds a new child diagnostic message to `self` with the level
         /// identified by this method's name with the given `message`.
-        #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+        #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
         pub fn $regular<T: Into<String>>(mut self, message: T) -> Diagnostic {
             self.children.push(Diagnostic::new($level, message));
             self
@@ -81,10 +81,10 @@ macro_rules! diagnostic_child_methods {
 
 /// Iterator over the children diagnostics of a `Diagnostic`.
 #[derive(Debug, Clone)]
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 pub struct Children<'a>(std::slice::Iter<'a, Diagnostic>);
 
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 impl<'a> Iterator for Children<'a> {
     type Item = &'a Diagnostic;
 
@@ -93,17 +93,17 @@ impl<'a> Iterator for Children<'a> {
     }
 }
 
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 impl Diagnostic {
     /// Creates a new diagnostic with the given `level` and `message`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn new<T: Into<String>>(level: Level, message: T) -> Diagnostic {
         Diagnostic { level, message: message.into(), spans: vec![], children: vec![] }
     }
 
     /// Creates a new diagnostic with the given `level` and `message` pointing to
     /// the given set of `spans`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn spanned<S, T>(spans: S, level: Level, message: T) -> Diagnostic
     where
         S: MultiSpan,
@@ -118,49 +118,49 @@ impl Diagnostic {
     diagnostic_child_methods!(span_help, help, Level::Help);
 
     /// Returns the diagnostic `level` for `self`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn level(&self) -> Level {
         self.level
     }
 
     /// Sets the level in `self` to `level`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn set_level(&mut self, level: Level) {
         self.level = level;
     }
 
     /// Returns the message in `self`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn message(&self) -> &str {
         &self.message
     }
 
     /// Sets the message in `self` to `message`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn set_message<T: Into<String>>(&mut self, message: T) {
         self.message = message.into();
     }
 
     /// Returns the `Span`s in `self`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn spans(&self) -> &[Span] {
         &self.spans
     }
 
     /// Sets the `Span`s in `self` to `spans`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn set_spans<S: MultiSpan>(&mut self, spans: S) {
         self.spans = spans.into_spans();
     }
 
     /// Returns an iterator over the children diagnostics of `self`.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn children(&self) -> Children<'_> {
         Children(self.children.iter())
     }
 
     /// Emit the diagnostic.
-    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
     pub fn emit(self) {
         fn to_internal(spans: Vec<Span>) -> crate::bridge::client::MultiSpan {
             let mut multi_span = crate::bridge::client::MultiSpan::new();
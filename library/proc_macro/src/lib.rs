@@ -39,11 +39,11 @@ pub mod bridge;
 
 mod diagnostic;
 
-#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
 pub use diagnostic::{Diagnostic, Level, MultiSpan};
 
 use std::cmp::Ordering;
-use std::ops::{Bound, RangeBounds};
+use std::ops::{Bound, Range, RangeBounds};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::{error, fmt, iter, mem};
@@ -66,6 +66,19 @@ pub fn is_available() -> bool {
     bridge::Bridge::is_available()
 }
 
+/// Declares that the currently running procedural macro is pure, i.e. that its
+/// output only depends on its input tokens, and on the environment variables and
+/// files it accessed through [`tracked_env::var`] and [`tracked_path::path`].
+///
+/// This allows the compiler to reuse the output of the macro from a previous
+/// compilation, instead of running it again, when none of these have changed.
+/// Macros that inspect the locations of spans (e.g. through [`Span::start`]) or
+/// emit diagnostics are never reused, even if they declare themselves pure.
+#[unstable(feature = "proc_macro_pure", issue = "none")]
+pub fn declare_pure() {
+    bridge::client::FreeFunctions::declare_pure();
+}
+
 /// The main type provided by this crate, representing an abstract stream of
 /// tokens, or, more specifically, a sequence of token trees.
 /// The type provide interfaces for iterating over those token trees and, conversely,
@@ -104,6 +117,28 @@ impl !Send for LexError {}
 #[stable(feature = "proc_macro_lib", since = "1.15.0")]
 impl !Sync for LexError {}
 
+/// Error returned from `TokenStream::expand_expr`.
+#[unstable(feature = "proc_macro_expand", issue = "none")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ExpandError;
+
+#[unstable(feature = "proc_macro_expand", issue = "none")]
+impl fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("macro expansion failed")
+    }
+}
+
+#[unstable(feature = "proc_macro_expand", issue = "none")]
+impl error::Error for ExpandError {}
+
+#[unstable(feature = "proc_macro_expand", issue = "none")]
+impl !Send for ExpandError {}
+
+#[unstable(feature = "proc_macro_expand", issue = "none")]
+impl !Sync for ExpandError {}
+
 impl TokenStream {
     /// Returns an empty `TokenStream` containing no token trees.
     #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
@@ -116,6 +151,24 @@ impl TokenStream {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Parses this `TokenStream` as an expression and attempts to expand any
+    /// macros within it. Returns the expanded `TokenStream`.
+    ///
+    /// Currently only expressions expanding to literals will succeed, although
+    /// this may be relaxed in the future.
+    ///
+    /// NOTE: In error conditions, `expand_expr` may leave macros unexpanded,
+    /// report an error, failing compilation, and/or return an `Err(..)`. The
+    /// specific behavior for any error condition, and what conditions are
+    /// considered errors, is unspecified and may change in the future.
+    #[unstable(feature = "proc_macro_expand", issue = "none")]
+    pub fn expand_expr(&self) -> Result<TokenStream, ExpandError> {
+        match bridge::client::TokenStream::expand_expr(&self.0) {
+            Ok(stream) => Ok(TokenStream(stream)),
+            Err(_) => Err(ExpandError),
+        }
+    }
 }
 
 /// Attempts to break the string into tokens and parse those tokens into a token stream.
@@ -287,7 +340,7 @@ macro_rules! diagnostic_method {
     ($name:ident, $level:expr) => {
         /// Creates a new `Diagnostic` with the given `message` at the span
         /// `self`.
-        #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+        #[stable(feature = "proc_macro_diagnostic", since = "1.49.0")]
         pub fn $name<T: Into<String>>(self, message: T) -> Diagnostic {
             Diagnostic::spanned(self, $level, message)
         }
@@ -320,7 +373,7 @@ impl Span {
     }
 
     /// The original source file into which this span points.
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub fn source_file(&self) -> SourceFile {
         SourceFile(self.0.source_file())
     }
@@ -341,17 +394,24 @@ impl Span {
     }
 
     /// Gets the starting line/column in the source file for this span.
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub fn start(&self) -> LineColumn {
         self.0.start()
     }
 
     /// Gets the ending line/column in the source file for this span.
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub fn end(&self) -> LineColumn {
         self.0.end()
     }
 
+    /// Returns the range of bytes that this span covers in its source file,
+    /// as offsets from the start of the file.
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
+    pub fn byte_range(&self) -> Range<usize> {
+        self.0.byte_range()
+    }
+
     /// Creates a new span encompassing `self` and `other`.
     ///
     /// Returns `None` if `self` and `other` are from different files.
@@ -407,31 +467,31 @@ impl fmt::Debug for Span {
 }
 
 /// A line-column pair representing the start or end of a `Span`.
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct LineColumn {
     /// The 1-indexed line in the source file on which the span starts or ends (inclusive).
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub line: usize,
     /// The 0-indexed column (in UTF-8 characters) in the source file on which
     /// the span starts or ends (inclusive).
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub column: usize,
 }
 
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl !Send for LineColumn {}
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl !Sync for LineColumn {}
 
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl Ord for LineColumn {
     fn cmp(&self, other: &Self) -> Ordering {
         self.line.cmp(&other.line).then(self.column.cmp(&other.column))
     }
 }
 
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl PartialOrd for LineColumn {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -439,7 +499,7 @@ impl PartialOrd for LineColumn {
 }
 
 /// The source file of a given `Span`.
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 #[derive(Clone)]
 pub struct SourceFile(bridge::client::SourceFile);
 
@@ -454,14 +514,14 @@ impl SourceFile {
     /// the command line, the path as given may not actually be valid.
     ///
     /// [`is_real`]: Self::is_real
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub fn path(&self) -> PathBuf {
         PathBuf::from(self.0.path())
     }
 
     /// Returns `true` if this source file is a real source file, and not generated by an external
     /// macro's expansion.
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_span_location", since = "1.49.0")]
     pub fn is_real(&self) -> bool {
         // This is a hack until intercrate spans are implemented and we can have real source files
         // for spans generated in external macros.
@@ -470,7 +530,7 @@ impl SourceFile {
     }
 }
 
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl fmt::Debug for SourceFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SourceFile")
@@ -480,14 +540,14 @@ impl fmt::Debug for SourceFile {
     }
 }
 
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl PartialEq for SourceFile {
     fn eq(&self, other: &Self) -> bool {
         self.0.eq(&other.0)
     }
 }
 
-#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[stable(feature = "proc_macro_span_location", since = "1.49.0")]
 impl Eq for SourceFile {}
 
 /// A single token or a delimited sequence of token trees (e.g., `[1, (), ..]`).
@@ -1126,7 +1186,7 @@ impl Literal {
     // called blindly. For example, `to_string()` for the character 'c' returns
     // "'\u{63}'"; there is no way for the user to know whether the source text
     // was 'c' or whether it was '\u{63}'.
-    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    #[stable(feature = "proc_macro_literal_subspan", since = "1.49.0")]
     pub fn subspan<R: RangeBounds<usize>>(&self, range: R) -> Option<Span> {
         // HACK(eddyb) something akin to `Option::cloned`, but for `Bound<&T>`.
         fn cloned_bound<T: Clone>(bound: Bound<&T>) -> Bound<T> {
@@ -1185,3 +1245,20 @@ pub mod tracked_env {
         value
     }
 }
+
+/// Tracked access to additional files.
+#[unstable(feature = "proc_macro_tracked_path", issue = "none")]
+pub mod tracked_path {
+    /// Track a file explicitly.
+    ///
+    /// Commonly used for tracking asset preprocessing, or files that are read to generate
+    /// code, such as schemas. The build system executing the compiler will know that the
+    /// file was accessed during compilation, and will be able to rerun the build when its
+    /// contents change. The path is interpreted relative to the current directory of the
+    /// compiler, like the paths given to `std::fs`.
+    #[unstable(feature = "proc_macro_tracked_path", issue = "none")]
+    pub fn path<P: AsRef<str>>(path: P) {
+        let path: &str = path.as_ref();
+        crate::bridge::client::FreeFunctions::track_path(path);
+    }
+}
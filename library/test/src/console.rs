@@ -1,5 +1,6 @@
 //! Module providing interface for running tests in the console.
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io;
 use std::io::prelude::Write;
@@ -15,7 +16,7 @@ use super::{
     run_tests,
     test_result::TestResult,
     time::TestExecTime,
-    types::{NamePadding, TestDesc, TestDescAndFn},
+    types::{DocTestInfo, NamePadding, TestDesc, TestDescAndFn},
 };
 
 /// Generic wrapper over stdout.
@@ -249,6 +250,16 @@ fn on_test_event(
 /// A simple console test runner.
 /// Runs provided tests reporting process and results to the stdout.
 pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Result<bool> {
+    run_tests_console_with_doctests(opts, tests, HashMap::new())
+}
+
+/// Like `run_tests_console`, with the sources of the documentation tests among `tests`, by test
+/// name, which the JSON output reports with their events.
+pub(crate) fn run_tests_console_with_doctests(
+    opts: &TestOpts,
+    tests: Vec<TestDescAndFn>,
+    doctests: HashMap<String, DocTestInfo>,
+) -> io::Result<bool> {
     let output = match term::stdout() {
         None => OutputLocation::Raw(io::stdout()),
         Some(t) => OutputLocation::Pretty(t),
@@ -273,7 +284,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         OutputFormat::Terse => {
             Box::new(TerseFormatter::new(output, opts.use_color(), max_name_len, is_multithreaded))
         }
-        OutputFormat::Json => Box::new(JsonFormatter::new(output)),
+        OutputFormat::Json => Box::new(JsonFormatter::new(output, doctests)),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
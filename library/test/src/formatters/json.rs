@@ -1,20 +1,26 @@
-use std::{borrow::Cow, io, io::prelude::Write};
+use std::{borrow::Cow, collections::HashMap, io, io::prelude::Write};
 
 use super::OutputFormatter;
 use crate::{
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
     time,
-    types::TestDesc,
+    types::{DocTestInfo, TestDesc},
 };
 
 pub(crate) struct JsonFormatter<T> {
     out: OutputLocation<T>,
+    doctests: HashMap<String, DocTestInfo>,
 }
 
 impl<T: Write> JsonFormatter<T> {
-    pub fn new(out: OutputLocation<T>) -> Self {
-        Self { out }
+    pub fn new(out: OutputLocation<T>, doctests: HashMap<String, DocTestInfo>) -> Self {
+        Self { out, doctests }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
     }
 
     fn writeln_message(&mut self, s: &str) -> io::Result<()> {
@@ -30,6 +36,41 @@ impl<T: Write> JsonFormatter<T> {
         self.out.write_all(s.as_ref())
     }
 
+    /// Writes the source of the documentation test with the given name, if it is one, and the
+    /// diagnostics of its compilation if asked to.
+    fn write_doctest(&mut self, name: &str, diagnostics: bool) -> io::Result<()> {
+        let doctest = match self.doctests.get(name) {
+            Some(doctest) => doctest,
+            None => return Ok(()),
+        };
+        let attributes = doctest
+            .attributes
+            .iter()
+            .map(|attr| format!(r#""{}""#, EscapedString(attr)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut message = format!(
+            ", \"doctest\": {{ \
+             \"file\": \"{}\", \
+             \"start_line\": {}, \
+             \"end_line\": {}, \
+             \"item_path\": \"{}\", \
+             \"attributes\": [{}] }}",
+            EscapedString(&doctest.file),
+            doctest.start_line,
+            doctest.end_line,
+            EscapedString(&doctest.item_path),
+            attributes
+        );
+        let diagnostics =
+            if diagnostics { doctest.diagnostics.lock().unwrap().clone() } else { Vec::new() };
+        if !diagnostics.is_empty() {
+            // The diagnostics are already JSON, so they're written as they are.
+            message.push_str(&format!(r#", "diagnostics": [{}]"#, diagnostics.join(", ")));
+        }
+        self.write_message(&message)
+    }
+
     fn write_event(
         &mut self,
         ty: &str,
@@ -52,6 +93,7 @@ impl<T: Write> JsonFormatter<T> {
         if let Some(extra) = extra {
             self.write_message(&*format!(r#", {}"#, extra))?;
         }
+        self.write_doctest(name, true)?;
         self.writeln_message(" }")
     }
 }
@@ -65,10 +107,12 @@ impl<T: Write> OutputFormatter for JsonFormatter<T> {
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
-        self.writeln_message(&*format!(
-            r#"{{ "type": "test", "event": "started", "name": "{}" }}"#,
+        self.write_message(&*format!(
+            r#"{{ "type": "test", "event": "started", "name": "{}""#,
             desc.name
-        ))
+        ))?;
+        self.write_doctest(desc.name.as_slice(), false)?;
+        self.writeln_message(" }")
     }
 
     fn write_result(
@@ -60,6 +60,7 @@ pub mod test {
 }
 
 use std::{
+    collections::HashMap,
     env, io,
     io::prelude::Write,
     panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo},
@@ -112,13 +113,24 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Opt
     if let Some(options) = options {
         opts.options = options;
     }
+    test_main_with_doctests(&opts, tests, HashMap::new())
+}
+
+/// Like `test_main`, with the options already parsed by `parse_opts`, and the sources of the
+/// documentation tests among `tests`, by test name, which the JSON output reports with their
+/// events.
+pub fn test_main_with_doctests(
+    opts: &TestOpts,
+    tests: Vec<TestDescAndFn>,
+    doctests: HashMap<String, DocTestInfo>,
+) {
     if opts.list {
-        if let Err(e) = console::list_tests_console(&opts, tests) {
+        if let Err(e) = console::list_tests_console(opts, tests) {
             eprintln!("error: io error when listing tests: {:?}", e);
             process::exit(ERROR_EXIT_CODE);
         }
     } else {
-        match console::run_tests_console(&opts, tests) {
+        match console::run_tests_console_with_doctests(opts, tests, doctests) {
             Ok(true) => {}
             Ok(false) => process::exit(ERROR_EXIT_CODE),
             Err(e) => {
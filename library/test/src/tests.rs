@@ -3,7 +3,7 @@ use super::*;
 use crate::{
     bench::Bencher,
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{JsonFormatter, OutputFormatter, PrettyFormatter},
     options::OutputFormat,
     test::{
         filter_tests,
@@ -19,6 +19,7 @@ use crate::{
         TestDesc,
         TestDescAndFn,
         TestOpts,
+        TrFailed,
         TrIgnored,
         TrOk,
         // FIXME (introduced by #65251)
@@ -28,6 +29,7 @@ use crate::{
     time::{TestTimeOptions, TimeThreshold},
 };
 use std::any::TypeId;
+use std::collections::HashMap;
 use std::sync::mpsc::channel;
 use std::time::Duration;
 
@@ -686,3 +688,52 @@ fn should_sort_failures_before_printing_them() {
     let bpos = s.find("b").unwrap();
     assert!(apos < bpos);
 }
+
+#[test]
+fn json_formatter_writes_doctest_info() {
+    let desc = TestDesc {
+        name: StaticTestName("src/lib.rs - foo (line 3)"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        test_type: TestType::DocTest,
+    };
+    let doctest = DocTestInfo {
+        file: "src/lib.rs".to_string(),
+        start_line: 3,
+        end_line: 6,
+        item_path: "foo".to_string(),
+        attributes: vec!["edition2018".to_string()],
+        diagnostics: Default::default(),
+    };
+    doctest.diagnostics.lock().unwrap().push(r#"{"message":"oops"}"#.to_string());
+    let mut doctests = HashMap::new();
+    doctests.insert(desc.name.as_slice().to_string(), doctest);
+
+    let mut out = JsonFormatter::new(OutputLocation::Raw(Vec::new()), doctests);
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    out.write_test_start(&desc).unwrap();
+    out.write_result(&desc, &TrFailed, None, &[], &st).unwrap();
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+
+    let doctest = "\"doctest\": { \"file\": \"src/lib.rs\", \"start_line\": 3, \"end_line\": 6, \
+                   \"item_path\": \"foo\", \"attributes\": [\"edition2018\"] }";
+    let mut lines = s.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        format!(
+            r#"{{ "type": "test", "event": "started", "name": "src/lib.rs - foo (line 3)", {} }}"#,
+            doctest
+        )
+    );
+    assert_eq!(
+        lines.next().unwrap(),
+        format!(
+            r#"{{ "type": "test", "name": "src/lib.rs - foo (line 3)", "event": "failed", {}, "#,
+            doctest
+        ) + r#""diagnostics": [{"message":"oops"}] }"#
+    );
+}
@@ -2,6 +2,7 @@
 
 use std::borrow::Cow;
 use std::fmt;
+use std::sync::{Arc, Mutex};
 
 use super::bench::Bencher;
 use super::options;
@@ -138,6 +139,25 @@ impl TestDesc {
     }
 }
 
+/// Where a documentation test comes from, which the JSON formatter reports with the events of the
+/// test so that tools can show its result next to its source.
+#[derive(Clone, Debug, Default)]
+pub struct DocTestInfo {
+    /// The file containing the documentation.
+    pub file: String,
+    /// The line of the opening fence of the code block.
+    pub start_line: usize,
+    /// The line of the closing fence of the code block.
+    pub end_line: usize,
+    /// The path of the documented item, or the headings above the code block in a Markdown file.
+    pub item_path: String,
+    /// The attributes of the code block, such as `should_panic` or `edition2018`.
+    pub attributes: Vec<String>,
+    /// The diagnostics of rustc in JSON, one per line, filled in by the test if it failed to
+    /// compile.
+    pub diagnostics: Arc<Mutex<Vec<String>>>,
+}
+
 #[derive(Debug)]
 pub struct TestDescAndFn {
     pub desc: TestDesc,
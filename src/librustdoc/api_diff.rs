@@ -0,0 +1,545 @@
+//! Comparing the public API of a crate to a snapshot of it (see `--api-snapshot` and
+//! `--api-diff`), to find out which release its changes call for under semver.
+//!
+//! A snapshot maps every item of the API (including fields, variants, associated items and
+//! trait implementations) to its signature, as it's printed in the documentation but without the
+//! names of function arguments, and to the few properties which affect what can be added to it,
+//! e.g. whether a struct can be built with a struct literal. Changes are classified like so:
+//!
+//! - Removing an item, or changing its signature, is a major change. This is conservative, as
+//!   some signature changes (e.g. relaxing a bound) are compatible.
+//! - Adding an item is a minor change, except for adding an item to a trait which implementors
+//!   have to provide, or a variant or field to an enum, variant or struct which can be matched
+//!   exhaustively (or built) outside of the crate, which are major.
+//! - Making an item `#[non_exhaustive]` (or adding private fields to it) is a major change.
+//! - Deprecating an item is a minor change.
+
+use crate::clean::{self, GetDefId};
+use crate::config::OutputFormat;
+use crate::doctree::StructType;
+use crate::formats::item_type::ItemType;
+use crate::html::format::{print_abi_with_space, print_generic_bounds, PrintWithSpace, WhereClause};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default)]
+crate struct ApiDiffOptions {
+    /// Where to save a snapshot of the crate's API.
+    crate snapshot_output: Option<PathBuf>,
+    /// A snapshot to compare the crate's API to.
+    crate baseline: Option<PathBuf>,
+}
+
+impl ApiDiffOptions {
+    crate fn is_enabled(&self) -> bool {
+        self.snapshot_output.is_some() || self.baseline.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+crate struct ApiItem {
+    crate signature: String,
+    /// The key of the item this one is part of, e.g. the struct of a field.
+    crate parent: Option<String>,
+    /// Whether implementors of the trait this item is in have to provide it.
+    #[serde(default)]
+    crate required: bool,
+    /// Whether the struct, union, enum or variant can't be built, or matched exhaustively,
+    /// outside of the crate.
+    #[serde(default)]
+    crate non_exhaustive: bool,
+    #[serde(default)]
+    crate deprecated: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+crate struct ApiSnapshot {
+    crate crate_name: String,
+    crate crate_version: Option<String>,
+    /// The items of the API, by their kind and path (e.g. `fn krate::module::function`).
+    crate items: BTreeMap<String, ApiItem>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+crate enum Severity {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Patch => "patch",
+            Severity::Minor => "minor",
+            Severity::Major => "major",
+        })
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+crate struct Change {
+    crate severity: Severity,
+    crate item: String,
+    crate description: String,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    crate_name: &'a str,
+    old_version: Option<&'a str>,
+    new_version: Option<&'a str>,
+    /// The release the changes call for.
+    severity: Severity,
+    changes: &'a [Change],
+}
+
+/// Builds an `ApiSnapshot` from the cleaned crate.
+#[derive(Default)]
+struct SnapshotBuilder {
+    items: BTreeMap<String, ApiItem>,
+    /// The keys and paths of the types in the crate, which their impls are recorded under.
+    types: FxHashMap<DefId, (String, String)>,
+    impls: Vec<(String, clean::Item)>,
+}
+
+impl SnapshotBuilder {
+    /// Records `item`, returning its key.
+    fn insert(
+        &mut self,
+        path: &str,
+        item: &clean::Item,
+        signature: String,
+        parent: Option<&str>,
+    ) -> String {
+        let kind = match ItemType::from(item) {
+            // Whether a trait method has a default is recorded as `required`.
+            ItemType::TyMethod => ItemType::Method,
+            kind => kind,
+        };
+        let key = format!("{} {}", kind, path);
+        let required = match item.inner {
+            clean::TyMethodItem(_) => true,
+            clean::AssocConstItem(_, ref default) => default.is_none(),
+            clean::AssocTypeItem(_, ref default) => default.is_none(),
+            _ => false,
+        };
+        let non_exhaustive = item.is_non_exhaustive()
+            || match item.inner {
+                clean::StructItem(clean::Struct { fields_stripped, .. })
+                | clean::UnionItem(clean::Union { fields_stripped, .. })
+                | clean::VariantItem(clean::Variant {
+                    kind: clean::VariantKind::Struct(clean::VariantStruct { fields_stripped, .. }),
+                }) => fields_stripped,
+                _ => false,
+            };
+        self.items.insert(
+            key.clone(),
+            ApiItem {
+                signature,
+                parent: parent.map(str::to_owned),
+                required,
+                non_exhaustive,
+                deprecated: item.deprecation.is_some(),
+            },
+        );
+        key
+    }
+
+    fn visit_items(&mut self, parent_path: &str, items: &[clean::Item], parent: Option<&str>) {
+        for item in items {
+            self.visit_item(parent_path, item, parent);
+        }
+    }
+
+    fn visit_item(&mut self, parent_path: &str, item: &clean::Item, parent: Option<&str>) {
+        match item.inner {
+            clean::ImplItem(_) => {
+                self.impls.push((parent_path.to_owned(), item.clone()));
+                return;
+            }
+            // Re-exports have no name of their own.
+            clean::ImportItem(ref import) => {
+                let name = match import {
+                    clean::Import::Simple(name, _) => name.as_str(),
+                    clean::Import::Glob(_) => "*",
+                };
+                let path = format!("{}::{}", parent_path, name);
+                self.insert(&path, item, signature(name, item), parent);
+                return;
+            }
+            _ => {}
+        }
+        let name = match item.name {
+            Some(ref name) => name,
+            None => return,
+        };
+        let path = format!("{}::{}", parent_path, name);
+        match item.inner {
+            clean::StrippedItem(_) | clean::PrimitiveItem(_) | clean::KeywordItem(_) => return,
+            _ => {}
+        }
+        let key = self.insert(&path, item, signature(name, item), parent);
+        match item.inner {
+            clean::ModuleItem(ref m) => self.visit_items(&path, &m.items, None),
+            clean::StructItem(clean::Struct { ref fields, .. })
+            | clean::UnionItem(clean::Union { ref fields, .. })
+            | clean::VariantItem(clean::Variant {
+                kind: clean::VariantKind::Struct(clean::VariantStruct { ref fields, .. }),
+            }) => self.visit_items(&path, fields, Some(key.as_str())),
+            clean::EnumItem(ref e) => self.visit_items(&path, &e.variants.raw, Some(key.as_str())),
+            clean::TraitItem(ref t) => self.visit_items(&path, &t.items, Some(key.as_str())),
+            _ => {}
+        }
+        match item.inner {
+            clean::StructItem(_)
+            | clean::UnionItem(_)
+            | clean::EnumItem(_)
+            | clean::TypedefItem(_, false)
+            | clean::ForeignTypeItem => {
+                self.types.insert(item.def_id, (key, path));
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the impls, once the paths of the types they're for are known.
+    fn visit_impls(&mut self) {
+        for (module_path, item) in std::mem::take(&mut self.impls) {
+            let impl_ = match item.inner {
+                clean::ImplItem(ref impl_) => impl_,
+                _ => unreachable!(),
+            };
+            // Blanket impls come with the traits they're for, wherever those are.
+            if impl_.blanket_impl.is_some() {
+                continue;
+            }
+            let self_ty = impl_.for_.def_id().and_then(|did| self.types.get(&did)).cloned();
+            match impl_.trait_ {
+                Some(ref trait_) => {
+                    // Types with the same name in different modules have to be told apart.
+                    let module = match (&self_ty, &impl_.for_) {
+                        (Some((_, path)), clean::ResolvedPath { .. }) => {
+                            format!("{}::", &path[..path.rfind("::").unwrap_or(0)])
+                        }
+                        _ => String::new(),
+                    };
+                    let negative = impl_.polarity == Some(clean::ImplPolarity::Negative);
+                    let path = format!(
+                        "{}{:#} for {}{:#}",
+                        if negative { "!" } else { "" },
+                        trait_.print(),
+                        module,
+                        impl_.for_.print()
+                    );
+                    let signature = format!("{:#}", impl_.print()).trim_end().to_owned();
+                    let parent = self_ty.as_ref().map(|(key, _)| key.as_str());
+                    self.insert(&path, &item, signature, parent);
+                }
+                None => {
+                    let (parent, type_path) = match self_ty {
+                        Some((key, path)) => (Some(key), path),
+                        None => (None, format!("{}::{:#}", module_path, impl_.for_.print())),
+                    };
+                    self.visit_items(&type_path, &impl_.items, parent.as_deref());
+                }
+            }
+        }
+    }
+}
+
+/// Prints the types of the arguments and the return type of a function, leaving out the names of
+/// the arguments, since renaming them isn't a change to the API.
+fn print_fn_decl(decl: &clean::FnDecl) -> String {
+    let mut args = decl
+        .inputs
+        .values
+        .iter()
+        .map(|arg| match arg.to_self() {
+            Some(clean::SelfValue) => "self".to_owned(),
+            Some(clean::SelfBorrowed(Some(ref lt), mtbl)) => {
+                format!("&{} {}self", lt.print(), mtbl.print_with_space())
+            }
+            Some(clean::SelfBorrowed(None, mtbl)) => format!("&{}self", mtbl.print_with_space()),
+            Some(clean::SelfExplicit(ref ty)) => format!("self: {:#}", ty.print()),
+            None => format!("{:#}", arg.type_.print()),
+        })
+        .collect::<Vec<_>>();
+    if decl.c_variadic {
+        args.push("...".to_owned());
+    }
+    format!("({}){:#}", args.join(", "), decl.output.print())
+}
+
+/// Prints the signature of `item` as plain text.
+fn signature(name: &str, item: &clean::Item) -> String {
+    let fn_signature =
+        |header: &hir::FnHeader, generics: &clean::Generics, decl: &clean::FnDecl| {
+            format!(
+                "{}{}{}{:#}fn {}{:#}{}{:#}",
+                header.constness.print_with_space(),
+                header.asyncness.print_with_space(),
+                header.unsafety.print_with_space(),
+                print_abi_with_space(header.abi),
+                name,
+                generics.print(),
+                print_fn_decl(decl),
+                WhereClause { gens: generics, indent: 0, end_newline: false },
+            )
+        };
+    let type_signature = |keyword: &str, generics: &clean::Generics, suffix: &str| {
+        format!(
+            "{} {}{:#}{}{:#}",
+            keyword,
+            name,
+            generics.print(),
+            suffix,
+            WhereClause { gens: generics, indent: 0, end_newline: false },
+        )
+    };
+    match item.inner {
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            fn_signature(&f.header, &f.generics, &f.decl)
+        }
+        clean::MethodItem(ref m) => fn_signature(&m.header, &m.generics, &m.decl),
+        clean::TyMethodItem(ref m) => fn_signature(&m.header, &m.generics, &m.decl),
+        clean::StructItem(ref s) => type_signature(
+            "struct",
+            &s.generics,
+            match s.struct_type {
+                StructType::Plain => " { .. }",
+                StructType::Tuple => "( .. )",
+                StructType::Unit => "",
+            },
+        ),
+        clean::UnionItem(ref u) => type_signature("union", &u.generics, ""),
+        clean::EnumItem(ref e) => type_signature("enum", &e.generics, ""),
+        clean::TraitItem(ref t) => {
+            let bounds = if t.bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {:#}", print_generic_bounds(&t.bounds))
+            };
+            let keyword = format!(
+                "{}{}trait",
+                t.unsafety.print_with_space(),
+                if t.is_auto { "auto " } else { "" }
+            );
+            type_signature(&keyword, &t.generics, &bounds)
+        }
+        clean::TraitAliasItem(ref t) => type_signature(
+            "trait",
+            &t.generics,
+            &format!(" = {:#}", print_generic_bounds(&t.bounds)),
+        ),
+        clean::TypedefItem(ref t, _) => {
+            type_signature("type", &t.generics, &format!(" = {:#}", t.type_.print()))
+        }
+        clean::OpaqueTyItem(ref t) => type_signature(
+            "type",
+            &t.generics,
+            &format!(" = impl {:#}", print_generic_bounds(&t.bounds)),
+        ),
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => {
+            format!("static {}{}: {:#}", s.mutability.print_with_space(), name, s.type_.print())
+        }
+        clean::ConstantItem(ref c) => format!("const {}: {:#}", name, c.type_.print()),
+        clean::AssocConstItem(ref ty, _) => format!("const {}: {:#}", name, ty.print()),
+        clean::AssocTypeItem(ref bounds, _) if bounds.is_empty() => format!("type {}", name),
+        clean::AssocTypeItem(ref bounds, _) => {
+            format!("type {}: {:#}", name, print_generic_bounds(bounds))
+        }
+        clean::StructFieldItem(ref ty) => format!("{}: {:#}", name, ty.print()),
+        clean::VariantItem(ref v) => match v.kind {
+            clean::VariantKind::CLike => name.to_owned(),
+            clean::VariantKind::Tuple(ref tys) => format!(
+                "{}({})",
+                name,
+                tys.iter().map(|ty| format!("{:#}", ty.print())).collect::<Vec<_>>().join(", ")
+            ),
+            clean::VariantKind::Struct(_) => format!("{} {{ .. }}", name),
+        },
+        clean::ForeignTypeItem => format!("type {}", name),
+        clean::ExternCrateItem(ref krate, ref orig) => match orig {
+            Some(orig) => format!("extern crate {} as {}", orig, krate),
+            None => format!("extern crate {}", krate),
+        },
+        clean::ImportItem(ref import) => match import {
+            clean::Import::Simple(_, source) => format!("use {:#}", source.path.print()),
+            clean::Import::Glob(source) => format!("use {:#}::*", source.path.print()),
+        },
+        clean::ProcMacroItem(ref m) if !m.helpers.is_empty() => {
+            format!("attributes({})", m.helpers.join(", "))
+        }
+        // Macros are only compared by name, since their definitions say
+        // little about which inputs they accept.
+        _ => String::new(),
+    }
+}
+
+impl ApiSnapshot {
+    crate fn new(krate: &clean::Crate) -> ApiSnapshot {
+        let mut builder = SnapshotBuilder::default();
+        if let Some(ref module) = krate.module {
+            if let clean::ModuleItem(ref m) = module.inner {
+                builder.visit_items(&krate.name, &m.items, None);
+            }
+        }
+        builder.visit_impls();
+        ApiSnapshot {
+            crate_name: krate.name.clone(),
+            crate_version: krate.version.clone(),
+            items: builder.items,
+        }
+    }
+}
+
+/// Classifies the changes from the `old` API to the `new` one.
+crate fn compare(old: &ApiSnapshot, new: &ApiSnapshot) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut change = |severity, item: &str, description: String| {
+        changes.push(Change { severity, item: item.to_owned(), description })
+    };
+
+    for (key, old_item) in &old.items {
+        let new_item = match new.items.get(key) {
+            Some(new_item) => new_item,
+            None => {
+                change(Severity::Major, key, "removed".to_owned());
+                continue;
+            }
+        };
+        if old_item.signature != new_item.signature {
+            change(
+                Severity::Major,
+                key,
+                format!("changed from `{}` to `{}`", old_item.signature, new_item.signature),
+            );
+        }
+        match (old_item.non_exhaustive, new_item.non_exhaustive) {
+            (false, true) => change(
+                Severity::Major,
+                key,
+                "can no longer be built or matched exhaustively outside of the crate".to_owned(),
+            ),
+            (true, false) => change(
+                Severity::Minor,
+                key,
+                "can now be built or matched exhaustively outside of the crate".to_owned(),
+            ),
+            _ => {}
+        }
+        match (old_item.required, new_item.required) {
+            (false, true) => change(
+                Severity::Major,
+                key,
+                "no longer has a default, so implementors have to provide it".to_owned(),
+            ),
+            (true, false) => change(Severity::Minor, key, "now has a default".to_owned()),
+            _ => {}
+        }
+        if !old_item.deprecated && new_item.deprecated {
+            change(Severity::Minor, key, "deprecated".to_owned());
+        }
+    }
+
+    for (key, new_item) in &new.items {
+        if old.items.contains_key(key) {
+            continue;
+        }
+        let old_parent = new_item.parent.as_ref().and_then(|parent| old.items.get(parent));
+        let is_trait_impl = key.starts_with("impl ");
+        match old_parent {
+            Some(_) if new_item.required => change(
+                Severity::Major,
+                key,
+                "added to a trait, which implementors have to provide".to_owned(),
+            ),
+            Some(parent)
+                if !parent.non_exhaustive && !is_trait_impl && is_field_or_variant(key) =>
+            {
+                change(
+                    Severity::Major,
+                    key,
+                    "added to a type which could be built or matched exhaustively \
+                     outside of the crate"
+                        .to_owned(),
+                )
+            }
+            _ => change(Severity::Minor, key, "added".to_owned()),
+        }
+    }
+
+    changes.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.item.cmp(&b.item)));
+    changes
+}
+
+fn is_field_or_variant(key: &str) -> bool {
+    key.starts_with(&format!("{} ", ItemType::StructField))
+        || key.starts_with(&format!("{} ", ItemType::Variant))
+}
+
+fn print_report(
+    old: &ApiSnapshot,
+    new: &ApiSnapshot,
+    changes: &[Change],
+    output_format: Option<OutputFormat>,
+) -> Result<(), String> {
+    let severity = changes.iter().map(|change| change.severity).max().unwrap_or(Severity::Patch);
+    if output_format.map_or(false, |f| f.is_json()) {
+        let report = Report {
+            crate_name: &new.crate_name,
+            old_version: old.crate_version.as_deref(),
+            new_version: new.crate_version.as_deref(),
+            severity,
+            changes,
+        };
+        println!("{}", serde_json::to_string(&report).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
+
+    let versions = match (&old.crate_version, &new.crate_version) {
+        (Some(old), Some(new)) => format!(" ({} -> {})", old, new),
+        _ => String::new(),
+    };
+    println!("API changes in `{}`{}: a {} release is required", new.crate_name, versions, severity);
+    for change in changes {
+        println!("{}: {}: {}", change.severity, change.item, change.description);
+    }
+    Ok(())
+}
+
+/// Saves a snapshot of the API of `krate`, and/or compares it to a previous one.
+crate fn run(
+    krate: &clean::Crate,
+    options: ApiDiffOptions,
+    output_format: Option<OutputFormat>,
+) -> Result<(), String> {
+    let snapshot = ApiSnapshot::new(krate);
+    if let Some(path) = options.snapshot_output {
+        let json = serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string())?;
+        fs::write(&path, json)
+            .map_err(|e| format!("failed to write API snapshot to `{}`: {}", path.display(), e))?;
+    }
+    if let Some(path) = options.baseline {
+        let baseline = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<ApiSnapshot>(&json).map_err(|e| e.to_string()))
+            .map_err(|e| format!("failed to read API snapshot `{}`: {}", path.display(), e))?;
+        let changes = compare(&baseline, &snapshot);
+        print_report(&baseline, &snapshot, &changes, output_format)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,139 @@
+use super::{compare, ApiItem, ApiSnapshot, Severity};
+
+fn item(signature: &str, parent: Option<&str>) -> ApiItem {
+    ApiItem {
+        signature: signature.to_owned(),
+        parent: parent.map(str::to_owned),
+        required: false,
+        non_exhaustive: false,
+        deprecated: false,
+    }
+}
+
+fn snapshot(items: Vec<(&str, ApiItem)>) -> ApiSnapshot {
+    ApiSnapshot {
+        crate_name: "krate".to_owned(),
+        crate_version: None,
+        items: items.into_iter().map(|(key, item)| (key.to_owned(), item)).collect(),
+    }
+}
+
+fn severities(old: &ApiSnapshot, new: &ApiSnapshot) -> Vec<(Severity, String)> {
+    compare(old, new).into_iter().map(|change| (change.severity, change.item)).collect()
+}
+
+#[test]
+fn test_unchanged() {
+    let api = snapshot(vec![("fn krate::f", item("fn f()", None))]);
+    assert!(compare(&api, &api).is_empty());
+}
+
+#[test]
+fn test_removed_and_added() {
+    let old = snapshot(vec![("fn krate::f", item("fn f()", None))]);
+    let new = snapshot(vec![("fn krate::g", item("fn g()", None))]);
+    assert_eq!(
+        severities(&old, &new),
+        vec![
+            (Severity::Major, "fn krate::f".to_owned()),
+            (Severity::Minor, "fn krate::g".to_owned())
+        ]
+    );
+}
+
+#[test]
+fn test_signature_changed() {
+    let old = snapshot(vec![("fn krate::f", item("fn f(u32)", None))]);
+    let new = snapshot(vec![("fn krate::f", item("fn f(u64)", None))]);
+    let changes = compare(&old, &new);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].severity, Severity::Major);
+    assert_eq!(changes[0].description, "changed from `fn f(u32)` to `fn f(u64)`");
+}
+
+#[test]
+fn test_trait_items() {
+    let trait_ = ("trait krate::T", item("trait T", None));
+    let old = snapshot(vec![trait_.clone()]);
+    let provided = item("fn provided(&self)", Some("trait krate::T"));
+    let required = ApiItem { required: true, ..item("fn required(&self)", Some("trait krate::T")) };
+    let new = snapshot(vec![
+        trait_,
+        ("method krate::T::provided", provided),
+        ("method krate::T::required", required),
+    ]);
+    assert_eq!(
+        severities(&old, &new),
+        vec![
+            (Severity::Major, "method krate::T::required".to_owned()),
+            (Severity::Minor, "method krate::T::provided".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_default_removed() {
+    let provided = item("fn f(&self)", Some("trait krate::T"));
+    let old = snapshot(vec![("method krate::T::f", provided.clone())]);
+    let new = snapshot(vec![("method krate::T::f", ApiItem { required: true, ..provided })]);
+    assert_eq!(severities(&old, &new), vec![(Severity::Major, "method krate::T::f".to_owned())]);
+}
+
+#[test]
+fn test_variants() {
+    let exhaustive = item("enum E", None);
+    let non_exhaustive = ApiItem { non_exhaustive: true, ..item("enum E", None) };
+    let variant = ("variant krate::E::B", item("B", Some("enum krate::E")));
+
+    let old = snapshot(vec![("enum krate::E", exhaustive.clone())]);
+    let new = snapshot(vec![("enum krate::E", exhaustive), variant.clone()]);
+    assert_eq!(severities(&old, &new), vec![(Severity::Major, "variant krate::E::B".to_owned())]);
+
+    let old = snapshot(vec![("enum krate::E", non_exhaustive.clone())]);
+    let new = snapshot(vec![("enum krate::E", non_exhaustive), variant]);
+    assert_eq!(severities(&old, &new), vec![(Severity::Minor, "variant krate::E::B".to_owned())]);
+}
+
+#[test]
+fn test_fields_of_new_type() {
+    // The fields of a type which is new themselves are only part of the addition of the type.
+    let old = snapshot(vec![]);
+    let new = snapshot(vec![
+        ("struct krate::S", item("struct S { .. }", None)),
+        ("structfield krate::S::a", item("a: u32", Some("struct krate::S"))),
+    ]);
+    assert!(severities(&old, &new).iter().all(|(severity, _)| *severity == Severity::Minor));
+}
+
+#[test]
+fn test_non_exhaustive() {
+    let exhaustive = item("struct S { .. }", None);
+    let non_exhaustive = ApiItem { non_exhaustive: true, ..exhaustive.clone() };
+    let old = snapshot(vec![("struct krate::S", exhaustive)]);
+    let new = snapshot(vec![("struct krate::S", non_exhaustive)]);
+    assert_eq!(severities(&old, &new), vec![(Severity::Major, "struct krate::S".to_owned())]);
+    assert_eq!(severities(&new, &old), vec![(Severity::Minor, "struct krate::S".to_owned())]);
+}
+
+#[test]
+fn test_deprecated() {
+    let f = item("fn f()", None);
+    let old = snapshot(vec![("fn krate::f", f.clone())]);
+    let new = snapshot(vec![("fn krate::f", ApiItem { deprecated: true, ..f })]);
+    assert_eq!(severities(&old, &new), vec![(Severity::Minor, "fn krate::f".to_owned())]);
+}
+
+#[test]
+fn test_trait_impl_added() {
+    // Implementing a trait for an exhaustive struct isn't the same as adding a field to it.
+    let s = ("struct krate::S", item("struct S { .. }", None));
+    let old = snapshot(vec![s.clone()]);
+    let new = snapshot(vec![
+        s,
+        ("impl Clone for krate::S", item("impl Clone for S", Some("struct krate::S"))),
+    ]);
+    assert_eq!(
+        severities(&old, &new),
+        vec![(Severity::Minor, "impl Clone for krate::S".to_owned())]
+    );
+}
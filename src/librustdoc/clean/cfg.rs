@@ -8,6 +8,7 @@ use std::mem;
 use std::ops;
 
 use rustc_ast::{LitKind, MetaItem, MetaItemKind, NestedMetaItem};
+use rustc_data_structures::fx::FxHashSet;
 use rustc_feature::Features;
 use rustc_session::parse::ParseSess;
 use rustc_span::symbol::{sym, Symbol};
@@ -201,6 +202,147 @@ impl Cfg {
             _ => false,
         }
     }
+
+    /// Removes the configuration options in `hidden`, e.g. `doc` from `any(unix, doc)`, so that
+    /// they aren't displayed. Returns `None` if nothing is left.
+    pub(crate) fn strip_hidden(&self, hidden: &FxHashSet<Cfg>) -> Option<Cfg> {
+        match *self {
+            Cfg::False | Cfg::True => Some(self.clone()),
+            Cfg::Cfg(..) => {
+                if hidden.contains(self) {
+                    None
+                } else {
+                    Some(self.clone())
+                }
+            }
+            Cfg::Not(ref child) => child.strip_hidden(hidden).map(|child| !child),
+            Cfg::All(ref sub_cfgs) => sub_cfgs
+                .iter()
+                .filter_map(|sub_cfg| sub_cfg.strip_hidden(hidden))
+                .fold(None, |cfg, sub_cfg| match cfg {
+                    Some(cfg) => Some(cfg & sub_cfg),
+                    None => Some(sub_cfg),
+                }),
+            Cfg::Any(ref sub_cfgs) => sub_cfgs
+                .iter()
+                .filter_map(|sub_cfg| sub_cfg.strip_hidden(hidden))
+                .fold(None, |cfg, sub_cfg| match cfg {
+                    Some(cfg) => Some(cfg | sub_cfg),
+                    None => Some(sub_cfg),
+                }),
+        }
+    }
+
+    /// Whether the configuration and `other` can't both hold, e.g. `unix` and `not(unix)`, or
+    /// `target_os = "linux"` and `target_os = "macos"`.
+    ///
+    /// The options are taken to be independent, except for those which a target has only one
+    /// value of. Configurations with too many options are never found contradictory.
+    pub(crate) fn contradicts(&self, other: &Cfg) -> bool {
+        let mut options = Vec::new();
+        self.collect_options(&mut options);
+        other.collect_options(&mut options);
+        if options.len() > MAX_CHECKED_OPTIONS {
+            return false;
+        }
+
+        // Looks for a set of enabled options under which both configurations hold.
+        !(0u32..1 << options.len()).any(|set| {
+            let enabled: Vec<Cfg> = options
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| set & (1 << i) != 0)
+                .map(|(_, option)| option.clone())
+                .collect();
+            let possible = enabled.iter().enumerate().all(|(i, a)| {
+                enabled[i + 1..].iter().all(|b| match (a, b) {
+                    (Cfg::Cfg(a_name, Some(_)), Cfg::Cfg(b_name, Some(_))) => {
+                        a_name != b_name || !SINGLE_VALUED_OPTIONS.contains(a_name)
+                    }
+                    _ => true,
+                })
+            });
+            possible && self.holds(&enabled) && other.holds(&enabled)
+        })
+    }
+
+    /// Adds the options the configuration depends on to `options`.
+    fn collect_options(&self, options: &mut Vec<Cfg>) {
+        match *self {
+            Cfg::False | Cfg::True => {}
+            Cfg::Cfg(..) => {
+                let option = self.normalize_option();
+                if !options.contains(&option) {
+                    options.push(option);
+                }
+            }
+            Cfg::Not(ref child) => child.collect_options(options),
+            Cfg::All(ref sub_cfgs) | Cfg::Any(ref sub_cfgs) => {
+                for sub_cfg in sub_cfgs {
+                    sub_cfg.collect_options(options);
+                }
+            }
+        }
+    }
+
+    /// Spells `unix` and `windows` as the `target_family` they stand for.
+    fn normalize_option(&self) -> Cfg {
+        match *self {
+            Cfg::Cfg(name, None) if name == sym::unix || name == sym::windows => {
+                Cfg::Cfg(sym::target_family, Some(name))
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Whether the configuration holds when exactly the options in `enabled` are enabled.
+    fn holds(&self, enabled: &[Cfg]) -> bool {
+        match *self {
+            Cfg::False => false,
+            Cfg::True => true,
+            Cfg::Cfg(..) => enabled.contains(&self.normalize_option()),
+            Cfg::Not(ref child) => !child.holds(enabled),
+            Cfg::All(ref sub_cfgs) => sub_cfgs.iter().all(|sub_cfg| sub_cfg.holds(enabled)),
+            Cfg::Any(ref sub_cfgs) => sub_cfgs.iter().any(|sub_cfg| sub_cfg.holds(enabled)),
+        }
+    }
+}
+
+/// The most options `Cfg::contradicts` looks at, since it tries every combination of them.
+const MAX_CHECKED_OPTIONS: usize = 12;
+
+/// The options which a target has at most one value of.
+const SINGLE_VALUED_OPTIONS: &[Symbol] = &[
+    sym::target_arch,
+    sym::target_endian,
+    sym::target_env,
+    sym::target_family,
+    sym::target_os,
+    sym::target_pointer_width,
+    sym::target_vendor,
+];
+
+/// Writes the configuration the way it's written in a `#[cfg]` attribute.
+impl fmt::Display for Cfg {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Cfg::True => fmt.write_str("all()"),
+            Cfg::False => fmt.write_str("any()"),
+            Cfg::Cfg(name, None) => write!(fmt, "{}", name),
+            Cfg::Cfg(name, Some(value)) => write!(fmt, "{} = \"{}\"", name, value),
+            Cfg::Not(ref child) => write!(fmt, "not({})", child),
+            Cfg::All(ref sub_cfgs) | Cfg::Any(ref sub_cfgs) => {
+                fmt.write_str(if let Cfg::All(..) = *self { "all(" } else { "any(" })?;
+                for (i, sub_cfg) in sub_cfgs.iter().enumerate() {
+                    if i != 0 {
+                        fmt.write_str(", ")?;
+                    }
+                    write!(fmt, "{}", sub_cfg)?;
+                }
+                fmt.write_char(')')
+            }
+        }
+    }
 }
 
 impl ops::Not for Cfg {
@@ -2,6 +2,7 @@ use super::*;
 
 use rustc_ast::attr;
 use rustc_ast::Path;
+use rustc_data_structures::fx::FxHashSet;
 use rustc_span::symbol::{Ident, Symbol};
 use rustc_span::with_default_session_globals;
 use rustc_span::DUMMY_SP;
@@ -433,3 +434,68 @@ fn test_render_long_html() {
         );
     })
 }
+
+#[test]
+fn test_strip_hidden() {
+    with_default_session_globals(|| {
+        let mut hidden = FxHashSet::default();
+        hidden.insert(word_cfg("doc"));
+        hidden.insert(name_value_cfg("feature", "std"));
+
+        assert_eq!(word_cfg("unix").strip_hidden(&hidden), Some(word_cfg("unix")));
+        assert_eq!(word_cfg("doc").strip_hidden(&hidden), None);
+        assert_eq!(
+            (word_cfg("unix") | word_cfg("doc")).strip_hidden(&hidden),
+            Some(word_cfg("unix"))
+        );
+        assert_eq!(
+            (name_value_cfg("feature", "std") & !word_cfg("windows")).strip_hidden(&hidden),
+            Some(!word_cfg("windows"))
+        );
+        assert_eq!(
+            (name_value_cfg("feature", "alloc") & !name_value_cfg("feature", "std"))
+                .strip_hidden(&hidden),
+            Some(name_value_cfg("feature", "alloc"))
+        );
+        assert_eq!(
+            (!word_cfg("doc") | name_value_cfg("feature", "std")).strip_hidden(&hidden),
+            None
+        );
+    })
+}
+
+#[test]
+fn test_contradicts() {
+    with_default_session_globals(|| {
+        assert!(word_cfg("unix").contradicts(&!word_cfg("unix")));
+        assert!(word_cfg("unix").contradicts(&word_cfg("windows")));
+        assert!(word_cfg("unix").contradicts(&name_value_cfg("target_family", "windows")));
+        assert!(
+            name_value_cfg("target_os", "linux").contradicts(&name_value_cfg("target_os", "macos"))
+        );
+        assert!(
+            (word_cfg("unix") & name_value_cfg("feature", "std"))
+                .contradicts(&!name_value_cfg("feature", "std"))
+        );
+        assert!(Cfg::False.contradicts(&Cfg::True));
+
+        assert!(!word_cfg("unix").contradicts(&word_cfg("unix")));
+        assert!(!word_cfg("unix").contradicts(&name_value_cfg("target_os", "linux")));
+        assert!(!(word_cfg("unix") | word_cfg("doc")).contradicts(&word_cfg("windows")));
+        assert!(!name_value_cfg("feature", "std").contradicts(&name_value_cfg("feature", "alloc")));
+        assert!(!word_cfg("unix").contradicts(&Cfg::True));
+    })
+}
+
+#[test]
+fn test_display() {
+    with_default_session_globals(|| {
+        assert_eq!(word_cfg("unix").to_string(), "unix");
+        assert_eq!(name_value_cfg("target_os", "linux").to_string(), r#"target_os = "linux""#);
+        assert_eq!(
+            (word_cfg("unix") & !name_value_cfg("feature", "std")).to_string(),
+            r#"all(unix, not(feature = "std"))"#
+        );
+        assert_eq!((word_cfg("unix") | word_cfg("windows")).to_string(), "any(unix, windows)");
+    })
+}
@@ -890,8 +890,7 @@ impl<'a> Clean<Method>
     fn clean(&self, cx: &DocContext<'_>) -> Method {
         let (generics, decl) =
             enter_impl_trait(cx, || (self.1.clean(cx), (&*self.0.decl, self.2).clean(cx)));
-        let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
-        Method { decl, generics, header: self.0.header, defaultness: self.3, all_types, ret_types }
+        Method { decl, generics, header: self.0.header, defaultness: self.3 }
     }
 }
 
@@ -908,7 +907,6 @@ impl Clean<Item> for doctree::Function<'_> {
         } else {
             hir::Constness::NotConst
         };
-        let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
         Item {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
@@ -921,8 +919,6 @@ impl Clean<Item> for doctree::Function<'_> {
                 decl,
                 generics,
                 header: hir::FnHeader { constness, ..self.header },
-                all_types,
-                ret_types,
             }),
         }
     }
@@ -1121,8 +1117,7 @@ impl Clean<Item> for hir::TraitItem<'_> {
                 let (generics, decl) = enter_impl_trait(cx, || {
                     (self.generics.clean(cx), (&*sig.decl, &names[..]).clean(cx))
                 });
-                let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
-                let mut t = TyMethod { header: sig.header, decl, generics, all_types, ret_types };
+                let mut t = TyMethod { header: sig.header, decl, generics };
                 if t.header.constness == hir::Constness::Const
                     && is_unstable_const_fn(cx.tcx, local_did.to_def_id()).is_some()
                 {
@@ -1225,7 +1220,6 @@ impl Clean<Item> for ty::AssocItem {
                     ty::ImplContainer(_) => true,
                     ty::TraitContainer(_) => self.defaultness.has_value(),
                 };
-                let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
                 if provided {
                     let constness = if is_min_const_fn(cx.tcx, self.def_id) {
                         hir::Constness::Const
@@ -1247,8 +1241,6 @@ impl Clean<Item> for ty::AssocItem {
                             asyncness,
                         },
                         defaultness,
-                        all_types,
-                        ret_types,
                     })
                 } else {
                     TyMethodItem(TyMethod {
@@ -1260,8 +1252,6 @@ impl Clean<Item> for ty::AssocItem {
                             constness: hir::Constness::NotConst,
                             asyncness: hir::IsAsync::NotAsync,
                         },
-                        all_types,
-                        ret_types,
                     })
                 }
             }
@@ -2297,7 +2287,6 @@ impl Clean<Item> for doctree::ForeignItem<'_> {
                 let abi = cx.tcx.hir().get_foreign_abi(self.id);
                 let (generics, decl) =
                     enter_impl_trait(cx, || (generics.clean(cx), (&**decl, &names[..]).clean(cx)));
-                let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
                 ForeignFunctionItem(Function {
                     decl,
                     generics,
@@ -2307,8 +2296,6 @@ impl Clean<Item> for doctree::ForeignItem<'_> {
                         constness: hir::Constness::NotConst,
                         asyncness: hir::IsAsync::NotAsync,
                     },
-                    all_types,
-                    ret_types,
                 })
             }
             hir::ForeignItemKind::Static(ref ty, mutbl) => ForeignStaticItem(Static {
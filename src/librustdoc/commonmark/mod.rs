@@ -0,0 +1,719 @@
+//! Rendering the documentation as CommonMark (`--output-format markdown`), for static site
+//! generators and wikis which only take Markdown.
+//!
+//! Each module and item is written to the path of its HTML page, with a `.md` extension instead
+//! of `.html`, and the intra-doc links to the items of the crate are rewritten as relative links
+//! between those files. The declarations are written as Rust code blocks, and the documentation
+//! is kept as it was written, except for its headings, which are moved down under the ones of the
+//! page.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use rustc_hir as hir;
+use rustc_span::edition::Edition;
+use rustc_span::hygiene::MacroKind;
+
+use crate::clean::{self, RenderedLink};
+use crate::config::{RenderInfo, RenderOptions};
+use crate::doctree;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::FormatRenderer;
+use crate::html::format::{print_abi_with_space, print_generic_bounds, PrintWithSpace, WhereClause};
+use crate::html::markdown::{replace_markdown_links, shift_markdown_headings};
+use crate::html::render::{item_ty_to_strs, plain_text_summary, CURRENT_DEPTH};
+
+/// The order of the sections of a module's page, after its re-exports. It's the same as in the
+/// HTML output.
+const MODULE_SECTIONS: &[ItemType] = &[
+    ItemType::Primitive,
+    ItemType::Module,
+    ItemType::Macro,
+    ItemType::Struct,
+    ItemType::Enum,
+    ItemType::Constant,
+    ItemType::Static,
+    ItemType::Trait,
+    ItemType::Function,
+    ItemType::Typedef,
+    ItemType::Union,
+    ItemType::ForeignType,
+    ItemType::Keyword,
+    ItemType::OpaqueTy,
+    ItemType::ProcAttribute,
+    ItemType::ProcDerive,
+    ItemType::TraitAlias,
+];
+
+#[derive(Clone)]
+crate struct CommonMarkRenderer {
+    /// The directory of the current module.
+    dst: PathBuf,
+    /// The path of the current module.
+    current: Vec<String>,
+    /// Whether the current module is stripped, in which case none of its items get a page.
+    in_stripped_mod: bool,
+}
+
+impl CommonMarkRenderer {
+    fn write(&self, file_name: &str, contents: String) -> Result<(), Error> {
+        let path = self.dst.join(file_name);
+        fs::write(&path, contents).map_err(|e| Error::new(e, &path))
+    }
+
+    /// Appends the documentation of `item` to `page`, in a section with a heading of the given
+    /// `level`. Its headings are moved down under that one, and its intra-doc links to the items
+    /// rendered along with it point to their Markdown files.
+    fn push_docs(&self, page: &mut String, item: &clean::Item, level: u32, cache: &Cache) {
+        let dox = match item.collapsed_doc_value() {
+            Some(dox) => dox,
+            None => return,
+        };
+        let links = item
+            .links()
+            .into_iter()
+            .map(|link| {
+                // The other crates are documented separately, most likely as HTML.
+                let rendered_here = item.attrs.links.iter().any(|item_link| {
+                    item_link.link == link.original_text
+                        && item_link.did.map_or(false, |did| cache.paths.contains_key(&did))
+                });
+                if rendered_here {
+                    RenderedLink { href: markdown_href(link.href), ..link }
+                } else {
+                    link
+                }
+            })
+            .collect::<Vec<_>>();
+        let docs = shift_markdown_headings(&replace_markdown_links(&dox, &links), level);
+        if !docs.trim().is_empty() {
+            page.push_str(docs.trim_end());
+            page.push_str("\n\n");
+        }
+    }
+
+    fn render_item(&self, item: &clean::Item, cache: &Cache) -> String {
+        // The intra-doc links are relative to the page they're rendered in.
+        CURRENT_DEPTH.with(|slot| slot.set(self.current.len()));
+
+        let name = item.name.as_deref().unwrap_or_default();
+        let path = match item.inner {
+            clean::ModuleItem(_) => self.current.join("::"),
+            clean::PrimitiveItem(_) | clean::KeywordItem(_) => name.to_owned(),
+            _ => format!("{}::{}", self.current.join("::"), name),
+        };
+        let mut page = String::new();
+        writeln!(page, "# {} `{}`\n", page_kind(item).unwrap_or_default(), path).unwrap();
+
+        if let Some(decl) = declaration(item) {
+            writeln!(page, "```rust\n{}\n```\n", decl).unwrap();
+        }
+        if let Some(ref depr) = item.deprecation {
+            page.push_str("> **Deprecated");
+            if let Some(ref since) = depr.since {
+                write!(page, " since {}", since).unwrap();
+            }
+            page.push_str("**");
+            if let Some(ref note) = depr.note {
+                write!(page, ": {}", note).unwrap();
+            }
+            page.push_str("\n\n");
+        }
+        self.push_docs(&mut page, item, 1, cache);
+
+        match item.inner {
+            clean::ModuleItem(ref m) => self.module_items(&mut page, &m.items),
+            clean::StructItem(ref s) => self.fields(&mut page, &s.fields, cache),
+            clean::UnionItem(ref u) => self.fields(&mut page, &u.fields, cache),
+            clean::EnumItem(ref e) => self.variants(&mut page, &e.variants.raw, cache),
+            clean::TraitItem(ref t) => self.trait_items(&mut page, item, t, cache),
+            _ => {}
+        }
+        if !item.is_mod() {
+            self.impls(&mut page, item, cache);
+        }
+
+        page.truncate(page.trim_end().len());
+        page.push('\n');
+        page
+    }
+
+    fn module_items(&self, page: &mut String, items: &[clean::Item]) {
+        let items = items.iter().filter(|item| !item.is_stripped()).collect::<Vec<_>>();
+
+        let reexports = items
+            .iter()
+            .filter_map(|item| match item.inner {
+                clean::ImportItem(ref import) => Some(format!(
+                    "{:#}{}",
+                    item.visibility.print_with_space(),
+                    print_import(import)
+                )),
+                clean::ExternCrateItem(ref name, ref src) => {
+                    let krate = match *src {
+                        Some(ref src) => format!("{} as {}", src, name),
+                        None => name.clone(),
+                    };
+                    Some(format!("{:#}extern crate {};", item.visibility.print_with_space(), krate))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !reexports.is_empty() {
+            writeln!(page, "## Re-exports\n\n```rust\n{}\n```\n", reexports.join("\n")).unwrap();
+        }
+
+        for ty in MODULE_SECTIONS {
+            let mut section = items.iter().filter(|item| item.type_() == *ty).peekable();
+            if section.peek().is_none() {
+                continue;
+            }
+            writeln!(page, "## {}\n", item_ty_to_strs(ty).1).unwrap();
+            for item in section {
+                let name = item.name.as_deref().unwrap_or_default();
+                let file = if item.is_mod() {
+                    format!("{}/index.md", name)
+                } else {
+                    format!("{}.{}.md", item.type_(), name)
+                };
+                write!(page, "- [`{}`]({})", name, file).unwrap();
+                let summary = plain_text_summary(item.doc_value());
+                if !summary.is_empty() {
+                    write!(page, ": {}", summary).unwrap();
+                }
+                page.push('\n');
+            }
+            page.push('\n');
+        }
+    }
+
+    fn fields(&self, page: &mut String, fields: &[clean::Item], cache: &Cache) {
+        let mut fields = fields
+            .iter()
+            .filter_map(|field| match field.inner {
+                clean::StructFieldItem(ref ty) => Some((field, ty)),
+                _ => None,
+            })
+            .peekable();
+        if fields.peek().is_none() {
+            return;
+        }
+        page.push_str("## Fields\n\n");
+        for (field, ty) in fields {
+            let name = field.name.as_deref().unwrap_or_default();
+            heading(
+                page,
+                3,
+                &format!("structfield.{}", name),
+                &format!("{}: {:#}", name, ty.print()),
+            );
+            self.push_docs(page, field, 3, cache);
+        }
+    }
+
+    fn variants(&self, page: &mut String, variants: &[clean::Item], cache: &Cache) {
+        let mut variants = variants.iter().filter(|variant| !variant.is_stripped()).peekable();
+        if variants.peek().is_none() {
+            return;
+        }
+        page.push_str("## Variants\n\n");
+        for variant in variants {
+            let name = variant.name.as_deref().unwrap_or_default();
+            let kind = match variant.inner {
+                clean::VariantItem(ref v) => &v.kind,
+                _ => continue,
+            };
+            heading(page, 3, &format!("variant.{}", name), &print_variant(name, kind));
+            self.push_docs(page, variant, 3, cache);
+            if let clean::VariantKind::Struct(ref s) = *kind {
+                for field in &s.fields {
+                    if let clean::StructFieldItem(ref ty) = field.inner {
+                        let name = field.name.as_deref().unwrap_or_default();
+                        write!(page, "- `{}: {:#}`", name, ty.print()).unwrap();
+                        let summary = plain_text_summary(field.doc_value());
+                        if !summary.is_empty() {
+                            write!(page, ": {}", summary).unwrap();
+                        }
+                        page.push('\n');
+                    }
+                }
+                page.push('\n');
+            }
+        }
+    }
+
+    fn trait_items(&self, page: &mut String, item: &clean::Item, t: &clean::Trait, cache: &Cache) {
+        let sections = [
+            (ItemType::AssocType, "Associated Types"),
+            (ItemType::AssocConst, "Associated Constants"),
+            (ItemType::TyMethod, "Required Methods"),
+            (ItemType::Method, "Provided Methods"),
+        ];
+        for &(ty, title) in &sections {
+            let mut items = t.items.iter().filter(|item| item.type_() == ty).peekable();
+            if items.peek().is_none() {
+                continue;
+            }
+            writeln!(page, "## {}\n", title).unwrap();
+            for assoc in items {
+                if let Some(sig) = assoc_signature(assoc) {
+                    let name = assoc.name.as_deref().unwrap_or_default();
+                    heading(page, 3, &format!("{}.{}", ty, name), &sig);
+                    self.push_docs(page, assoc, 3, cache);
+                }
+            }
+        }
+
+        if let Some(implementors) = cache.implementors.get(&item.def_id) {
+            let mut implementors = implementors
+                .iter()
+                .map(|implementor| print_impl(implementor.inner_impl()))
+                .collect::<Vec<_>>();
+            implementors.sort();
+            page.push_str("## Implementors\n\n");
+            for implementor in implementors {
+                writeln!(page, "- `{}`", implementor).unwrap();
+            }
+            page.push('\n');
+        }
+    }
+
+    fn impls(&self, page: &mut String, item: &clean::Item, cache: &Cache) {
+        let impls = match cache.impls.get(&item.def_id) {
+            Some(impls) => impls,
+            None => return,
+        };
+        let (inherent, traits): (Vec<_>, Vec<_>) =
+            impls.iter().partition(|i| i.inner_impl().trait_.is_none());
+
+        if !inherent.is_empty() {
+            page.push_str("## Implementations\n\n");
+            for i in inherent {
+                writeln!(page, "### `{}`\n", print_impl(i.inner_impl())).unwrap();
+                self.push_docs(page, &i.impl_item, 3, cache);
+                for assoc in &i.inner_impl().items {
+                    if assoc.is_stripped() {
+                        continue;
+                    }
+                    if let Some(sig) = assoc_signature(assoc) {
+                        let name = assoc.name.as_deref().unwrap_or_default();
+                        heading(page, 4, &format!("{}.{}", assoc.type_(), name), &sig);
+                        self.push_docs(page, assoc, 4, cache);
+                    }
+                }
+            }
+        }
+
+        // Like in the HTML output, the auto trait and blanket implementations are left out of
+        // the list of trait implementations.
+        let mut traits = traits
+            .iter()
+            .filter(|i| !i.inner_impl().synthetic && i.inner_impl().blanket_impl.is_none())
+            .map(|i| print_impl(i.inner_impl()))
+            .collect::<Vec<_>>();
+        if !traits.is_empty() {
+            traits.sort();
+            page.push_str("## Trait Implementations\n\n");
+            for i in traits {
+                writeln!(page, "- `{}`", i).unwrap();
+            }
+            page.push('\n');
+        }
+    }
+}
+
+impl FormatRenderer for CommonMarkRenderer {
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        _render_info: RenderInfo,
+        _edition: Edition,
+        _cache: &mut Cache,
+    ) -> Result<(Self, clean::Crate), Error> {
+        let dst = options.output;
+        fs::create_dir_all(&dst).map_err(|e| Error::new(e, &dst))?;
+        Ok((CommonMarkRenderer { dst, current: Vec::new(), in_stripped_mod: false }, krate))
+    }
+
+    fn item(&mut self, item: clean::Item, cache: &Cache) -> Result<(), Error> {
+        // Unlike the HTML output, there are no redirect pages for the items of stripped modules.
+        if self.in_stripped_mod || item.is_stripped() || page_kind(&item).is_none() {
+            return Ok(());
+        }
+        let file_name = format!("{}.{}.md", item.type_(), item.name.as_deref().unwrap_or_default());
+        let page = self.render_item(&item, cache);
+        self.write(&file_name, page)
+    }
+
+    fn mod_item_in(
+        &mut self,
+        item: &clean::Item,
+        item_name: &str,
+        cache: &Cache,
+    ) -> Result<(), Error> {
+        if !self.in_stripped_mod {
+            self.in_stripped_mod = item.is_stripped();
+        }
+        self.dst.push(item_name);
+        self.current.push(item_name.to_owned());
+        if self.in_stripped_mod {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.dst).map_err(|e| Error::new(e, &self.dst))?;
+        let page = self.render_item(item, cache);
+        self.write("index.md", page)
+    }
+
+    fn mod_item_out(&mut self, _item_name: &str) -> Result<(), Error> {
+        self.dst.pop();
+        self.current.pop();
+        Ok(())
+    }
+
+    fn after_krate(&mut self, _krate: &clean::Crate, _cache: &Cache) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn after_run(&mut self, _diag: &rustc_errors::Handler) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// The kind of item a page documents, as written in its title, or `None` if the item doesn't
+/// get a page of its own.
+fn page_kind(item: &clean::Item) -> Option<&'static str> {
+    Some(match item.inner {
+        clean::ModuleItem(ref m) if m.is_crate => "Crate",
+        clean::ModuleItem(_) => "Module",
+        clean::FunctionItem(_) | clean::ForeignFunctionItem(_) => "Function",
+        clean::TraitItem(_) => "Trait",
+        clean::StructItem(_) => "Struct",
+        clean::UnionItem(_) => "Union",
+        clean::EnumItem(_) => "Enum",
+        clean::TypedefItem(_, false) => "Type Definition",
+        clean::MacroItem(_) => "Macro",
+        clean::ProcMacroItem(ref mac) => match mac.kind {
+            MacroKind::Bang => "Macro",
+            MacroKind::Attr => "Attribute Macro",
+            MacroKind::Derive => "Derive Macro",
+        },
+        clean::PrimitiveItem(_) => "Primitive Type",
+        clean::StaticItem(_) | clean::ForeignStaticItem(_) => "Static",
+        clean::ConstantItem(_) => "Constant",
+        clean::ForeignTypeItem => "Foreign Type",
+        clean::KeywordItem(_) => "Keyword",
+        clean::OpaqueTyItem(_) => "Opaque Type",
+        clean::TraitAliasItem(_) => "Trait Alias",
+        _ => return None,
+    })
+}
+
+/// Makes a link to the HTML page of an item point to its Markdown file instead.
+fn markdown_href(href: String) -> String {
+    let (path, fragment) = href.split_at(href.find('#').unwrap_or(href.len()));
+    if let Some(path) = path.strip_suffix(".html") {
+        return format!("{}.md{}", path, fragment);
+    }
+    href
+}
+
+/// Writes a heading with an anchor, so the links to `#id` work like they do in the HTML pages.
+fn heading(page: &mut String, level: usize, id: &str, code: &str) {
+    writeln!(page, "<a id=\"{}\"></a>\n\n{} `{}`\n", id, "#".repeat(level), code).unwrap();
+}
+
+/// The declaration of an item, as written in the code block at the top of its page.
+fn declaration(item: &clean::Item) -> Option<String> {
+    let name = item.name.as_deref().unwrap_or_default();
+    let decl = match item.inner {
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            print_fn(item, &f.header, &f.generics, &f.decl)
+        }
+        clean::StructItem(ref s) => {
+            print_struct(item, "struct", &s.generics, s.struct_type, &s.fields, s.fields_stripped)
+        }
+        clean::UnionItem(ref u) => {
+            print_struct(item, "union", &u.generics, doctree::Plain, &u.fields, u.fields_stripped)
+        }
+        clean::EnumItem(ref e) => {
+            let mut decl = format!(
+                "{:#}enum {}{:#}{:#}",
+                item.visibility.print_with_space(),
+                name,
+                e.generics.print(),
+                where_clause(&e.generics),
+            );
+            if e.variants.is_empty() && !e.variants_stripped {
+                decl.push_str(" {}");
+            } else {
+                decl.push_str(" {\n");
+                for variant in e.variants.iter() {
+                    if let clean::VariantItem(ref v) = variant.inner {
+                        let variant_name = variant.name.as_deref().unwrap_or_default();
+                        writeln!(decl, "    {},", print_variant(variant_name, &v.kind)).unwrap();
+                    }
+                }
+                if e.variants_stripped {
+                    decl.push_str("    // some variants omitted\n");
+                }
+                decl.push('}');
+            }
+            decl
+        }
+        clean::TraitItem(ref t) => {
+            let mut decl = format!(
+                "{:#}{}{}trait {}{:#}",
+                item.visibility.print_with_space(),
+                t.unsafety.print_with_space(),
+                if t.is_auto { "auto " } else { "" },
+                name,
+                t.generics.print(),
+            );
+            if !t.bounds.is_empty() {
+                write!(decl, ": {:#}", print_generic_bounds(&t.bounds)).unwrap();
+            }
+            write!(decl, "{:#}", where_clause(&t.generics)).unwrap();
+            let items = t
+                .items
+                .iter()
+                .filter_map(|assoc| {
+                    let sig = assoc_signature(assoc)?;
+                    Some(match assoc.inner {
+                        clean::MethodItem(_) => format!("    {} {{ ... }}\n", sig),
+                        _ => format!("    {};\n", sig),
+                    })
+                })
+                .collect::<String>();
+            if items.is_empty() {
+                decl.push_str(" {}");
+            } else {
+                write!(decl, " {{\n{}}}", items).unwrap();
+            }
+            decl
+        }
+        clean::TypedefItem(ref t, false) => format!(
+            "{:#}type {}{:#}{:#} = {:#};",
+            item.visibility.print_with_space(),
+            name,
+            t.generics.print(),
+            where_clause(&t.generics),
+            t.type_.print(),
+        ),
+        clean::OpaqueTyItem(ref t) => format!(
+            "{:#}type {}{:#}{:#} = impl {:#};",
+            item.visibility.print_with_space(),
+            name,
+            t.generics.print(),
+            where_clause(&t.generics),
+            print_generic_bounds(&t.bounds),
+        ),
+        clean::TraitAliasItem(ref t) => format!(
+            "trait {}{:#}{:#} = {:#};",
+            name,
+            t.generics.print(),
+            where_clause(&t.generics),
+            print_generic_bounds(&t.bounds),
+        ),
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => format!(
+            "{:#}static {}{}: {:#};",
+            item.visibility.print_with_space(),
+            s.mutability.print_with_space(),
+            name,
+            s.type_.print(),
+        ),
+        clean::ConstantItem(ref c) => format!(
+            "{:#}const {}: {:#}{};",
+            item.visibility.print_with_space(),
+            name,
+            c.type_.print(),
+            if c.is_literal { format!(" = {}", c.expr) } else { String::new() },
+        ),
+        clean::MacroItem(ref m) => m.source.clone(),
+        clean::ProcMacroItem(ref m) => match m.kind {
+            MacroKind::Bang => format!("{}!() {{ /* proc-macro */ }}", name),
+            MacroKind::Attr => format!("#[{}]", name),
+            MacroKind::Derive => format!("#[derive({})]", name),
+        },
+        clean::ForeignTypeItem => {
+            format!("extern {{\n    {:#}type {};\n}}", item.visibility.print_with_space(), name)
+        }
+        _ => return None,
+    };
+    Some(decl)
+}
+
+/// The signature of an associated item, as written in the heading of its section.
+fn assoc_signature(item: &clean::Item) -> Option<String> {
+    let name = item.name.as_deref()?;
+    Some(match item.inner {
+        clean::TyMethodItem(ref m) => print_fn(item, &m.header, &m.generics, &m.decl),
+        clean::MethodItem(ref m) => print_fn(item, &m.header, &m.generics, &m.decl),
+        clean::AssocConstItem(ref ty, _) => {
+            format!("{:#}const {}: {:#}", item.visibility.print_with_space(), name, ty.print())
+        }
+        clean::AssocTypeItem(ref bounds, ref default) => {
+            let mut sig = format!("type {}", name);
+            if !bounds.is_empty() {
+                write!(sig, ": {:#}", print_generic_bounds(bounds)).unwrap();
+            }
+            if let Some(ref default) = *default {
+                write!(sig, " = {:#}", default.print()).unwrap();
+            }
+            sig
+        }
+        clean::TypedefItem(ref t, true) => format!("type {} = {:#}", name, t.type_.print()),
+        _ => return None,
+    })
+}
+
+fn where_clause(gens: &clean::Generics) -> WhereClause<'_> {
+    WhereClause { gens, indent: 0, end_newline: false }
+}
+
+fn print_fn(
+    item: &clean::Item,
+    header: &hir::FnHeader,
+    generics: &clean::Generics,
+    decl: &clean::FnDecl,
+) -> String {
+    format!(
+        "{:#}{}{}{}{:#}fn {}{:#}{}{:#}",
+        item.visibility.print_with_space(),
+        header.constness.print_with_space(),
+        header.asyncness.print_with_space(),
+        header.unsafety.print_with_space(),
+        print_abi_with_space(header.abi),
+        item.name.as_deref().unwrap_or_default(),
+        generics.print(),
+        print_fn_decl(decl),
+        where_clause(generics),
+    )
+}
+
+/// Prints the arguments and the return type of a function, with its receiver written the way
+/// it is in the code (`FnDecl::print` writes `self: &Self` for `&self`).
+fn print_fn_decl(decl: &clean::FnDecl) -> String {
+    let mut args = decl
+        .inputs
+        .values
+        .iter()
+        .map(|arg| match arg.to_self() {
+            Some(clean::SelfValue) => "self".to_owned(),
+            Some(clean::SelfBorrowed(Some(ref lt), mtbl)) => {
+                format!("&{} {}self", lt.print(), mtbl.print_with_space())
+            }
+            Some(clean::SelfBorrowed(None, mtbl)) => format!("&{}self", mtbl.print_with_space()),
+            Some(clean::SelfExplicit(ref ty)) => format!("self: {:#}", ty.print()),
+            None if arg.name.is_empty() => format!("{:#}", arg.type_.print()),
+            None => format!("{}: {:#}", arg.name, arg.type_.print()),
+        })
+        .collect::<Vec<_>>();
+    if decl.c_variadic {
+        args.push("...".to_owned());
+    }
+    format!("({}){:#}", args.join(", "), decl.output.print())
+}
+
+fn print_struct(
+    item: &clean::Item,
+    keyword: &str,
+    generics: &clean::Generics,
+    struct_type: doctree::StructType,
+    fields: &[clean::Item],
+    fields_stripped: bool,
+) -> String {
+    let mut decl = format!(
+        "{:#}{} {}{:#}",
+        item.visibility.print_with_space(),
+        keyword,
+        item.name.as_deref().unwrap_or_default(),
+        generics.print(),
+    );
+    match struct_type {
+        doctree::Plain => {
+            writeln!(decl, "{:#} {{", where_clause(generics)).unwrap();
+            for field in fields {
+                if let clean::StructFieldItem(ref ty) = field.inner {
+                    writeln!(
+                        decl,
+                        "    {:#}{}: {:#},",
+                        field.visibility.print_with_space(),
+                        field.name.as_deref().unwrap_or_default(),
+                        ty.print(),
+                    )
+                    .unwrap();
+                }
+            }
+            if fields_stripped {
+                decl.push_str("    // some fields omitted\n");
+            }
+            decl.push('}');
+        }
+        doctree::Tuple => {
+            let fields = fields
+                .iter()
+                .map(|field| match field.inner {
+                    clean::StructFieldItem(ref ty) => {
+                        format!("{:#}{:#}", field.visibility.print_with_space(), ty.print())
+                    }
+                    _ => "_".to_owned(),
+                })
+                .collect::<Vec<_>>();
+            write!(decl, "({}){:#};", fields.join(", "), where_clause(generics)).unwrap();
+        }
+        doctree::Unit => write!(decl, "{:#};", where_clause(generics)).unwrap(),
+    }
+    decl
+}
+
+fn print_variant(name: &str, kind: &clean::VariantKind) -> String {
+    match *kind {
+        clean::VariantKind::CLike => name.to_owned(),
+        clean::VariantKind::Tuple(ref tys) => {
+            let tys = tys.iter().map(|ty| format!("{:#}", ty.print())).collect::<Vec<_>>();
+            format!("{}({})", name, tys.join(", "))
+        }
+        clean::VariantKind::Struct(ref s) => {
+            let mut fields = s
+                .fields
+                .iter()
+                .filter_map(|field| match field.inner {
+                    clean::StructFieldItem(ref ty) => Some(format!(
+                        "{}: {:#}",
+                        field.name.as_deref().unwrap_or_default(),
+                        ty.print()
+                    )),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if s.fields_stripped {
+                fields.push("..".to_owned());
+            }
+            format!("{} {{ {} }}", name, fields.join(", "))
+        }
+    }
+}
+
+/// Prints an import; `Import::print` doesn't print plain text.
+fn print_import(import: &clean::Import) -> String {
+    let path = |src: &clean::ImportSource| {
+        src.path.segments.iter().map(|seg| &*seg.name).collect::<Vec<_>>().join("::")
+    };
+    match *import {
+        clean::Import::Simple(ref name, ref src) if name.as_str() != src.path.last_name() => {
+            format!("use {} as {};", path(src), name)
+        }
+        clean::Import::Simple(_, ref src) => format!("use {};", path(src)),
+        clean::Import::Glob(ref src) if src.path.segments.is_empty() => "use *;".to_owned(),
+        clean::Import::Glob(ref src) => format!("use {}::*;", path(src)),
+    }
+}
+
+/// Prints an implementation on one line, without the trailing comma of its where-clause.
+fn print_impl(i: &clean::Impl) -> String {
+    format!("{:#}", i.print()).trim_end().trim_end_matches(',').to_owned()
+}
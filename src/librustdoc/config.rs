@@ -19,20 +19,24 @@ use rustc_session::search_paths::SearchPath;
 use rustc_span::edition::{Edition, DEFAULT_EDITION};
 use rustc_target::spec::TargetTriple;
 
+use crate::api_diff::ApiDiffOptions;
 use crate::core::new_handler;
 use crate::externalfiles::ExternalHtml;
 use crate::html;
 use crate::html::markdown::IdMap;
+use crate::html::render::bundle::BundleOptions;
 use crate::html::render::StylePath;
 use crate::html::static_files;
 use crate::opts;
 use crate::passes::{self, Condition, DefaultPassOption};
+use crate::scrape_examples::{self, AllCallLocations, FnCallLocations, ScrapeExamplesOptions};
 use crate::theme;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum OutputFormat {
     Json,
     Html,
+    Markdown,
 }
 
 impl OutputFormat {
@@ -51,6 +55,7 @@ impl TryFrom<&str> for OutputFormat {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "markdown" => Ok(OutputFormat::Markdown),
             _ => Err(format!("unknown output format `{}`", value)),
         }
     }
@@ -117,6 +122,8 @@ pub struct Options {
     /// For example, using ignore-foo to ignore running the doctest on any target that
     /// contains "foo" as a substring
     pub enable_per_target_ignores: bool,
+    /// Whether to compile the doctests of each edition which can be into one binary.
+    pub batch_doctests: bool,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from $sysroot/bin/rustc.
@@ -145,6 +152,12 @@ pub struct Options {
     pub render_options: RenderOptions,
     /// Output format rendering (used only for "show-coverage" option for the moment)
     pub output_format: Option<OutputFormat>,
+    /// If present, the crate isn't documented, but the calls it makes to the target crates are
+    /// written out to be shown as examples in their documentation.
+    crate scrape_examples_options: Option<ScrapeExamplesOptions>,
+    /// If enabled, the crate isn't documented, but a snapshot of its API is saved and/or
+    /// compared to a previous one.
+    crate api_diff_options: ApiDiffOptions,
 }
 
 impl fmt::Debug for Options {
@@ -185,6 +198,9 @@ impl fmt::Debug for Options {
             .field("runtool", &self.runtool)
             .field("runtool_args", &self.runtool_args)
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
+            .field("batch_doctests", &self.batch_doctests)
+            .field("scrape_examples_options", &self.scrape_examples_options)
+            .field("api_diff_options", &self.api_diff_options)
             .finish()
     }
 }
@@ -246,10 +262,17 @@ pub struct RenderOptions {
     /// If false, the `select` element to have search filtering by crates on rendered docs
     /// won't be generated.
     pub generate_search_filter: bool,
+    /// If true, an index of the words of the documentation is generated for the search.
+    pub full_text_search: bool,
     /// Document items that have lower than `pub` visibility.
     pub document_private: bool,
     /// Document items that have `doc(hidden)`.
     pub document_hidden: bool,
+    /// The calls scraped from other crates (see `--with-examples`), to show as examples.
+    crate call_locations: AllCallLocations,
+    /// How the crate is combined with the other crates documented into the same output
+    /// directory (see `--merge`).
+    crate bundle: BundleOptions,
 }
 
 /// Temporary storage for data obtained during `RustdocVisitor::clean()`.
@@ -264,6 +287,8 @@ pub struct RenderInfo {
     pub deref_mut_trait_did: Option<DefId>,
     pub owned_box_did: Option<DefId>,
     pub output_format: Option<OutputFormat>,
+    /// The calls to the local crate's functions and methods found in the scraped examples.
+    crate call_locations: FxHashMap<DefId, FnCallLocations>,
 }
 
 impl Options {
@@ -510,10 +535,15 @@ impl Options {
                         diag.struct_err("json output format isn't supported for doc generation")
                             .emit();
                         return Err(1);
+                    } else if o == OutputFormat::Markdown && !nightly_options::is_nightly_build() {
+                        diag.struct_err("markdown output format is only supported on nightly")
+                            .emit();
+                        return Err(1);
                     } else if !o.is_json() && show_coverage {
-                        diag.struct_err(
-                            "html output format isn't supported for the --show-coverage option",
-                        )
+                        diag.struct_err(&format!(
+                            "{} output format isn't supported for the --show-coverage option",
+                            s
+                        ))
                         .emit();
                         return Err(1);
                     }
@@ -541,6 +571,7 @@ impl Options {
         let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
         let static_root_path = matches.opt_str("static-root-path");
         let generate_search_filter = !matches.opt_present("disable-per-crate-search");
+        let full_text_search = matches.opt_present("full-text-search");
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
@@ -550,8 +581,17 @@ impl Options {
         let runtool = matches.opt_str("runtool");
         let runtool_args = matches.opt_strs("runtool-arg");
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
+        let batch_doctests = matches.opt_present("batch-doctests");
         let document_private = matches.opt_present("document-private-items");
         let document_hidden = matches.opt_present("document-hidden-items");
+        let scrape_examples_options = ScrapeExamplesOptions::new(&matches, &diag)?;
+        let call_locations =
+            scrape_examples::load_call_locations(matches.opt_strs("with-examples"), &diag)?;
+        let bundle = BundleOptions::new(&matches, &diag)?;
+        let api_diff_options = ApiDiffOptions {
+            snapshot_output: matches.opt_str("api-snapshot").map(PathBuf::from),
+            baseline: matches.opt_str("api-diff").map(PathBuf::from),
+        };
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -586,6 +626,7 @@ impl Options {
             runtool,
             runtool_args,
             enable_per_target_ignores,
+            batch_doctests,
             test_builder,
             render_options: RenderOptions {
                 output,
@@ -605,10 +646,15 @@ impl Options {
                 markdown_css,
                 markdown_playground_url,
                 generate_search_filter,
+                full_text_search,
                 document_private,
                 document_hidden,
+                call_locations,
+                bundle,
             },
             output_format,
+            scrape_examples_options,
+            api_diff_options,
         })
     }
 
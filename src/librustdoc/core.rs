@@ -35,6 +35,7 @@ use crate::clean::{AttributesExt, MAX_DEF_ID};
 use crate::config::{Options as RustdocOptions, RenderOptions};
 use crate::config::{OutputFormat, RenderInfo};
 use crate::passes::{self, Condition::*, ConditionalPass};
+use crate::scrape_examples;
 
 pub use rustc_session::config::{CodegenOptions, DebuggingOptions, Input, Options};
 pub use rustc_session::search_paths::SearchPath;
@@ -280,9 +281,10 @@ where
     (lint_opts, lint_caps)
 }
 
+/// Returns `None` if the crate was only scraped for examples, and so shouldn't be rendered.
 pub fn run_core(
     options: RustdocOptions,
-) -> (clean::Crate, RenderInfo, RenderOptions, Lrc<Session>) {
+) -> Option<(clean::Crate, RenderInfo, RenderOptions, Lrc<Session>)> {
     // Parse, resolve, and typecheck the given crate.
 
     let RustdocOptions {
@@ -306,6 +308,7 @@ pub fn run_core(
         display_warnings,
         render_options,
         output_format,
+        scrape_examples_options,
         ..
     } = options;
 
@@ -326,6 +329,9 @@ pub fn run_core(
     let missing_docs = rustc_lint::builtin::MISSING_DOCS.name;
     let missing_doc_example = rustc_lint::builtin::MISSING_DOC_CODE_EXAMPLES.name;
     let private_doc_tests = rustc_lint::builtin::PRIVATE_DOC_TESTS.name;
+    let missing_doc_sections = rustc_lint::builtin::MISSING_DOC_SECTIONS.name;
+    let unused_doc_sections = rustc_lint::builtin::UNUSED_DOC_SECTIONS.name;
+    let contradictory_doc_cfg = rustc_lint::builtin::CONTRADICTORY_DOC_CFG.name;
     let no_crate_level_docs = rustc_lint::builtin::MISSING_CRATE_LEVEL_DOCS.name;
     let invalid_codeblock_attributes_name = rustc_lint::builtin::INVALID_CODEBLOCK_ATTRIBUTES.name;
     let renamed_and_removed_lints = rustc_lint::builtin::RENAMED_AND_REMOVED_LINTS.name;
@@ -338,6 +344,9 @@ pub fn run_core(
         missing_docs.to_owned(),
         missing_doc_example.to_owned(),
         private_doc_tests.to_owned(),
+        missing_doc_sections.to_owned(),
+        unused_doc_sections.to_owned(),
+        contradictory_doc_cfg.to_owned(),
         no_crate_level_docs.to_owned(),
         invalid_codeblock_attributes_name.to_owned(),
         renamed_and_removed_lints.to_owned(),
@@ -465,6 +474,13 @@ pub fn run_core(
 
             let mut global_ctxt = abort_on_err(queries.global_ctxt(), sess).take();
 
+            if let Some(options) = scrape_examples_options {
+                sess.time("scrape_examples", || {
+                    global_ctxt.enter(|tcx| scrape_examples::run(tcx, options))
+                });
+                return None;
+            }
+
             let (krate, render_info, opts) = sess.time("run_global_ctxt", || {
                 global_ctxt.enter(|tcx| {
                     run_global_ctxt(
@@ -477,7 +493,7 @@ pub fn run_core(
                     )
                 })
             });
-            (krate, render_info, opts, Lrc::clone(sess))
+            Some((krate, render_info, opts, Lrc::clone(sess)))
         })
     })
 }
@@ -532,6 +548,8 @@ fn run_global_ctxt(
     let mut renderinfo = RenderInfo::default();
     renderinfo.access_levels = access_levels;
     renderinfo.output_format = output_format;
+    renderinfo.call_locations =
+        scrape_examples::local_call_locations(tcx, &render_options.call_locations);
 
     let mut ctxt = DocContext {
         tcx,
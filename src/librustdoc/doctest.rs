@@ -20,10 +20,12 @@ use tempfile::Builder as TempFileBuilder;
 use std::collections::HashMap;
 use std::env;
 use std::io::{self, Write};
+use std::iter;
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
+use std::sync::{Arc, Mutex};
 
 use crate::clean::Attributes;
 use crate::config::Options;
@@ -99,8 +101,11 @@ pub fn run(options: Options) -> Result<(), ErrorReported> {
         registry: rustc_driver::diagnostics_registry(),
     };
 
-    let mut test_args = options.test_args.clone();
-    let display_warnings = options.display_warnings;
+    let test_opts = match parse_test_args(&options.test_args, options.display_warnings) {
+        Some(test_opts) => test_opts,
+        None => return Ok(()),
+    };
+    let json_output = test_opts.format == testing::OutputFormat::Json;
 
     let tests = interface::run_compiler(config, |compiler| {
         compiler.enter(|queries| {
@@ -117,6 +122,7 @@ pub fn run(options: Options) -> Result<(), ErrorReported> {
                 Some(compiler.session().parse_sess.clone_source_map()),
                 None,
                 enable_per_target_ignores,
+                json_output,
             );
 
             let mut global_ctxt = queries.global_ctxt()?.take();
@@ -145,22 +151,16 @@ pub fn run(options: Options) -> Result<(), ErrorReported> {
             });
             compiler.session().abort_if_errors();
 
-            let ret: Result<_, ErrorReported> = Ok(collector.tests);
+            let ret: Result<_, ErrorReported> = Ok((collector.tests, collector.doctests));
             ret
         })
     });
-    let tests = match tests {
+    let (tests, doctests) = match tests {
         Ok(tests) => tests,
         Err(ErrorReported) => return Err(ErrorReported),
     };
 
-    test_args.insert(0, "rustdoctest".to_string());
-
-    testing::test_main(
-        &test_args,
-        tests,
-        Some(testing::Options::new().display_output(display_warnings)),
-    );
+    testing::test_main_with_doctests(&test_opts, tests, doctests.unwrap_or_default());
 
     Ok(())
 }
@@ -198,6 +198,27 @@ fn scrape_test_config(krate: &::rustc_hir::Crate<'_>) -> TestOptions {
     opts
 }
 
+/// Parses the arguments of libtest like `test::test_main` does, exiting like it does if they're
+/// invalid. Returns `None` if they only ask for the help, which has then been shown.
+crate fn parse_test_args(
+    test_args: &[String],
+    display_warnings: bool,
+) -> Option<testing::TestOpts> {
+    // The first argument is the name of the program.
+    let args =
+        iter::once("rustdoctest".to_string()).chain(test_args.iter().cloned()).collect::<Vec<_>>();
+    let mut opts = match testing::test::parse_opts(&args) {
+        Some(Ok(opts)) => opts,
+        Some(Err(msg)) => {
+            eprintln!("error: {}", msg);
+            process::exit(101);
+        }
+        None => return None,
+    };
+    opts.options = testing::Options::new().display_output(display_warnings);
+    Some(opts)
+}
+
 /// Documentation test failure modes.
 enum TestFailure {
     /// The test failed to compile.
@@ -231,6 +252,195 @@ impl DirState {
     }
 }
 
+/// Doctests compiled into one binary (see `--batch-doctests`), to spare loading the standard
+/// library and linking for each of them.
+///
+/// Each test is put in a module of the binary, which runs the test whose index it's given as
+/// argument. The tests still run in processes of their own, and the ones failing are compiled and
+/// run again separately, so they fail like the tests compiled separately. If the batch doesn't
+/// compile, its tests are compiled separately.
+struct Batch {
+    edition: Edition,
+    tests: Vec<String>,
+    /// Whether a test mentions the documented crate, which is then injected like it is in the
+    /// tests compiled separately.
+    uses_crate: bool,
+    state: BatchState,
+}
+
+enum BatchState {
+    /// The batch hasn't been compiled yet.
+    Pending,
+    /// The binary of the batch is in the directory.
+    Compiled(DirState),
+    /// The batch failed to compile.
+    Failed,
+}
+
+impl Batch {
+    fn new(edition: Edition) -> Batch {
+        Batch {
+            edition,
+            tests: Vec::new(),
+            uses_crate: false,
+            state: BatchState::Pending,
+        }
+    }
+
+    /// Adds a test to the batch, and returns its index, or `None` if the test has to be compiled
+    /// separately, i.e. if it has crate attributes or `extern crate` items. Those would have to be
+    /// at the root of the binary, where the other tests could use them.
+    fn add(&mut self, test: &str, cratename: &str) -> Option<usize> {
+        let (crate_attrs, everything_else, crates) = partition_source(test);
+        if crate_attrs.lines().any(|line| line.trim_start().starts_with("#!["))
+            || !crates.is_empty()
+        {
+            return None;
+        }
+        self.uses_crate |= test.contains(cratename);
+        self.tests.push(everything_else);
+        Some(self.tests.len() - 1)
+    }
+
+    /// Returns the code of the binary of the batch.
+    fn source(&self, cratename: &str, opts: &TestOptions) -> String {
+        let mut prog = String::new();
+        if opts.attrs.is_empty() {
+            prog.push_str("#![allow(unused)]\n");
+        }
+        for attr in &opts.attrs {
+            prog.push_str(&format!("#![{}]\n", attr));
+        }
+        if self.uses_crate && !opts.no_crate_inject && cratename != "std" {
+            prog.push_str(&format!("extern crate {};\n", cratename));
+        }
+
+        // What the `main` functions of the tests return is reported like `Termination` does.
+        prog.push_str(
+            "trait __DoctestResult {
+    fn report(self) -> i32;
+}
+impl __DoctestResult for () {
+    fn report(self) -> i32 {
+        0
+    }
+}
+impl<E: std::fmt::Debug> __DoctestResult for Result<(), E> {
+    fn report(self) -> i32 {
+        match self {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!(\"Error: {:?}\", e);
+                1
+            }
+        }
+    }
+}
+",
+        );
+
+        // The tests are wrapped into `main` functions like when they're compiled separately, but
+        // without the attributes, which are at the root.
+        let test_opts =
+            TestOptions { no_crate_inject: true, display_warnings: true, attrs: vec![] };
+        for (i, test) in self.tests.iter().enumerate() {
+            let (test, _) = make_test(test, None, false, &test_opts, self.edition);
+            prog.push_str(&format!(
+                "mod __doctest_{} {{
+{}
+#[allow(unused_imports)]
+use super::*;
+pub(crate) fn __run() -> i32 {{
+    super::__DoctestResult::report(main())
+}}
+}}
+",
+                i, test
+            ));
+        }
+
+        prog.push_str(
+            "fn main() {
+    let code = match std::env::args().nth(1).and_then(|test| test.parse::<usize>().ok()) {
+",
+        );
+        for i in 0..self.tests.len() {
+            prog.push_str(&format!("        Some({0}) => __doctest_{0}::__run(),\n", i));
+        }
+        prog.push_str(
+            "        _ => panic!(\"no such doctest\"),
+    };
+    std::process::exit(code);
+}
+",
+        );
+        prog
+    }
+
+    /// Returns the path to the binary of the batch, which is compiled by the first test of the
+    /// batch to run, or `None` if the batch doesn't compile.
+    fn binary(
+        &mut self,
+        cratename: &str,
+        options: &Options,
+        opts: &TestOptions,
+        target: &TargetTriple,
+    ) -> Option<PathBuf> {
+        if let BatchState::Pending = self.state {
+            self.state = self.compile(cratename, options, opts, target);
+        }
+        match self.state {
+            BatchState::Compiled(ref outdir) => Some(outdir.path().join("rust_out")),
+            BatchState::Pending | BatchState::Failed => None,
+        }
+    }
+
+    /// Compiles the batch. Failing to run rustc fails the batch rather than the test compiling it,
+    /// as its tests are then compiled separately, which reports the error.
+    fn compile(
+        &self,
+        cratename: &str,
+        options: &Options,
+        opts: &TestOptions,
+        target: &TargetTriple,
+    ) -> BatchState {
+        let outdir = if let Some(ref path) = options.persist_doctests {
+            let path = path.join(format!("batch_{}", self.edition));
+            if std::fs::create_dir_all(&path).is_err() {
+                return BatchState::Failed;
+            }
+            DirState::Perm(path)
+        } else {
+            match TempFileBuilder::new().prefix("rustdoctest").tempdir() {
+                Ok(dir) => DirState::Temp(dir),
+                Err(_) => return BatchState::Failed,
+            }
+        };
+
+        let mut compiler = rustc_command(options, self.edition, target);
+        compiler.arg("-o").arg(outdir.path().join("rust_out"));
+        compiler.arg("-");
+        compiler.stdin(Stdio::piped());
+        // The errors are reported when the tests are compiled separately.
+        compiler.stderr(Stdio::null());
+
+        let mut child = match compiler.spawn() {
+            Ok(child) => child,
+            Err(_) => return BatchState::Failed,
+        };
+        let source = self.source(cratename, opts);
+        // Taking `stdin` closes it once the source is written.
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(source.as_bytes()).is_ok(),
+            None => false,
+        };
+        match child.wait() {
+            Ok(status) if written && status.success() => BatchState::Compiled(outdir),
+            _ => BatchState::Failed,
+        }
+    }
+}
+
 fn run_test(
     test: &str,
     cratename: &str,
@@ -248,52 +458,25 @@ fn run_test(
     edition: Edition,
     outdir: DirState,
     path: PathBuf,
+    diagnostics: Option<Arc<Mutex<Vec<String>>>>,
 ) -> Result<(), TestFailure> {
     let (test, line_offset) = make_test(test, Some(cratename), as_test_harness, opts, edition);
 
     let output_file = outdir.path().join("rust_out");
 
-    let rustc_binary = options
-        .test_builder
-        .as_deref()
-        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
-    let mut compiler = Command::new(&rustc_binary);
-    compiler.arg("--crate-type").arg("bin");
-    for cfg in &options.cfgs {
-        compiler.arg("--cfg").arg(&cfg);
-    }
-    if let Some(sysroot) = options.maybe_sysroot {
-        compiler.arg("--sysroot").arg(sysroot);
-    }
-    compiler.arg("--edition").arg(&edition.to_string());
+    let mut compiler = rustc_command(&options, edition, &target);
     compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
     compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
     compiler.arg("-o").arg(&output_file);
     if as_test_harness {
         compiler.arg("--test");
     }
-    for lib_str in &options.lib_strs {
-        compiler.arg("-L").arg(&lib_str);
-    }
-    for extern_str in &options.extern_strs {
-        compiler.arg("--extern").arg(&extern_str);
-    }
-    compiler.arg("-Ccodegen-units=1");
-    for codegen_options_str in &options.codegen_options_strs {
-        compiler.arg("-C").arg(&codegen_options_str);
-    }
-    for debugging_option_str in &options.debugging_opts_strs {
-        compiler.arg("-Z").arg(&debugging_option_str);
-    }
     if no_run && !compile_fail {
         compiler.arg("--emit=metadata");
     }
-    compiler.arg("--target").arg(match target {
-        TargetTriple::TargetTriple(s) => s,
-        TargetTriple::TargetPath(path) => {
-            path.to_str().expect("target path must be valid unicode").to_string()
-        }
-    });
+    if diagnostics.is_some() {
+        compiler.arg("--error-format=json");
+    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
@@ -313,7 +496,14 @@ fn run_test(
         }
     }
     let out = str::from_utf8(&output.stderr).unwrap();
-    let _bomb = Bomb(&out);
+    // The JSON diagnostics are reported with the result of the test instead of being printed.
+    let _bomb = Bomb(if diagnostics.is_some() { "" } else { out });
+    let report_diagnostics = || {
+        if let Some(diagnostics) = &diagnostics {
+            let lines = out.lines().filter(|line| line.starts_with('{')).map(str::to_owned);
+            diagnostics.lock().unwrap().extend(lines);
+        }
+    };
     match (output.status.success(), compile_fail) {
         (true, true) => {
             return Err(TestFailure::UnexpectedCompilePass);
@@ -324,11 +514,13 @@ fn run_test(
                 error_codes.retain(|err| !out.contains(&format!("error[{}]: ", err)));
 
                 if !error_codes.is_empty() {
+                    report_diagnostics();
                     return Err(TestFailure::MissingErrorCodes(error_codes));
                 }
             }
         }
         (false, false) => {
+            report_diagnostics();
             return Err(TestFailure::CompileError);
         }
     }
@@ -338,6 +530,55 @@ fn run_test(
     }
 
     // Run the code!
+    run_binary(&output_file, None, runtool, runtool_args, should_panic)
+}
+
+/// Makes the command compiling a doctest into a binary, with the options given to rustdoc.
+fn rustc_command(options: &Options, edition: Edition, target: &TargetTriple) -> Command {
+    let rustc_binary = options
+        .test_builder
+        .as_deref()
+        .unwrap_or_else(|| rustc_interface::util::rustc_path().expect("found rustc"));
+    let mut compiler = Command::new(&rustc_binary);
+    compiler.arg("--crate-type").arg("bin");
+    for cfg in &options.cfgs {
+        compiler.arg("--cfg").arg(&cfg);
+    }
+    if let Some(ref sysroot) = options.maybe_sysroot {
+        compiler.arg("--sysroot").arg(sysroot);
+    }
+    compiler.arg("--edition").arg(&edition.to_string());
+    for lib_str in &options.lib_strs {
+        compiler.arg("-L").arg(&lib_str);
+    }
+    for extern_str in &options.extern_strs {
+        compiler.arg("--extern").arg(&extern_str);
+    }
+    compiler.arg("-Ccodegen-units=1");
+    for codegen_options_str in &options.codegen_options_strs {
+        compiler.arg("-C").arg(&codegen_options_str);
+    }
+    for debugging_option_str in &options.debugging_opts_strs {
+        compiler.arg("-Z").arg(&debugging_option_str);
+    }
+    compiler.arg("--target").arg(match *target {
+        TargetTriple::TargetTriple(ref s) => s.as_str(),
+        TargetTriple::TargetPath(ref path) => {
+            path.to_str().expect("target path must be valid unicode")
+        }
+    });
+    compiler
+}
+
+/// Runs the binary of a doctest, with the runtool if there's one. `test` is the index of the
+/// test if the binary is the one of a batch.
+fn run_binary(
+    output_file: &Path,
+    test: Option<usize>,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    should_panic: bool,
+) -> Result<(), TestFailure> {
     let mut cmd;
 
     if let Some(tool) = runtool {
@@ -347,6 +588,9 @@ fn run_test(
     } else {
         cmd = Command::new(output_file);
     }
+    if let Some(test) = test {
+        cmd.arg(test.to_string());
+    }
 
     match cmd.output() {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
@@ -648,6 +892,12 @@ pub struct Collector {
     source_map: Option<Lrc<SourceMap>>,
     filename: Option<PathBuf>,
     visited_tests: HashMap<(String, usize), usize>,
+    /// The batches of tests of each edition, with `--batch-doctests`.
+    batches: HashMap<Edition, Arc<Mutex<Batch>>>,
+    /// The sources of the tests by name, when libtest writes its events in JSON, in which case
+    /// they're reported with the events of the tests, along with the errors of the tests which
+    /// don't compile.
+    pub doctests: Option<HashMap<String, testing::DocTestInfo>>,
 }
 
 impl Collector {
@@ -659,7 +909,9 @@ impl Collector {
         source_map: Option<Lrc<SourceMap>>,
         filename: Option<PathBuf>,
         enable_per_target_ignores: bool,
+        json_output: bool,
     ) -> Collector {
+        let doctests = if json_output { Some(HashMap::new()) } else { None };
         Collector {
             tests: Vec::new(),
             names: Vec::new(),
@@ -672,6 +924,8 @@ impl Collector {
             source_map,
             filename,
             visited_tests: HashMap::new(),
+            batches: HashMap::new(),
+            doctests,
         }
     }
 
@@ -710,6 +964,21 @@ impl Tester for Collector {
     fn add_test(&mut self, test: String, config: LangString, line: usize) {
         let filename = self.get_filename();
         let name = self.generate_name(line, &filename);
+        let item_path = self.names.join("::");
+        let diagnostics = self.doctests.as_mut().map(|doctests| {
+            let doctest = testing::DocTestInfo {
+                file: filename.to_string(),
+                start_line: line,
+                // The code is between the lines of the fences.
+                end_line: line + test.lines().count() + 1,
+                item_path,
+                attributes: config.attributes(),
+                diagnostics: Default::default(),
+            };
+            let diagnostics = doctest.diagnostics.clone();
+            doctests.insert(name.clone(), doctest);
+            diagnostics
+        });
         let cratename = self.cratename.to_string();
         let opts = self.opts.clone();
         let edition = config.edition.unwrap_or(self.options.edition);
@@ -718,6 +987,30 @@ impl Tester for Collector {
         let runtool_args = self.options.runtool_args.clone();
         let target = self.options.target.clone();
         let target_str = target.to_string();
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        };
+
+        // The warnings of the tests can't be told apart in a batch, so they're only batched when
+        // the warnings aren't shown.
+        let batch = if self.options.batch_doctests
+            && !ignore
+            && !opts.display_warnings
+            && !config.compile_fail
+            && !config.no_run
+            && !config.test_harness
+        {
+            let batch = self
+                .batches
+                .entry(edition)
+                .or_insert_with(|| Arc::new(Mutex::new(Batch::new(edition))));
+            let index = batch.lock().unwrap().add(&test, &cratename);
+            index.map(|index| (batch.clone(), index))
+        } else {
+            None
+        };
 
         // FIXME(#44940): if doctests ever support path remapping, then this filename
         // needs to be the result of `SourceMap::span_to_unmapped_path`.
@@ -765,35 +1058,52 @@ impl Tester for Collector {
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-                },
+                ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
                 test_type: testing::TestType::DocTest,
             },
             testfn: testing::DynTestFn(box move || {
-                let res = run_test(
-                    &test,
-                    &cratename,
-                    line,
-                    options,
-                    config.should_panic,
-                    config.no_run,
-                    config.test_harness,
-                    runtool,
-                    runtool_args,
-                    target,
-                    config.compile_fail,
-                    config.error_codes,
-                    &opts,
-                    edition,
-                    outdir,
-                    path,
-                );
+                // A test failing in the batch is compiled and run again on its own, since the
+                // batch doesn't know where its tests are in the documentation, which shows in the
+                // locations of panics, `file!()` and `line!()`.
+                let passed_in_batch = batch.map_or(false, |(batch, index)| {
+                    let binary = batch.lock().unwrap().binary(&cratename, &options, &opts, &target);
+                    binary.map_or(false, |binary| {
+                        run_binary(
+                            &binary,
+                            Some(index),
+                            runtool.clone(),
+                            runtool_args.clone(),
+                            config.should_panic,
+                        )
+                        .is_ok()
+                    })
+                });
+                let res = if passed_in_batch {
+                    Ok(())
+                } else {
+                    run_test(
+                        &test,
+                        &cratename,
+                        line,
+                        options,
+                        config.should_panic,
+                        config.no_run,
+                        config.test_harness,
+                        runtool,
+                        runtool_args,
+                        target,
+                        config.compile_fail,
+                        config.error_codes,
+                        &opts,
+                        edition,
+                        outdir,
+                        path,
+                        diagnostics,
+                    )
+                };
 
                 if let Err(err) = res {
                     match err {
@@ -1,4 +1,4 @@
-use super::{make_test, TestOptions};
+use super::{make_test, Batch, TestOptions};
 use rustc_span::edition::DEFAULT_EDITION;
 
 #[test]
@@ -277,3 +277,46 @@ test_wrapper! {
     let output = make_test(input, Some("my_crate"), false, &opts, DEFAULT_EDITION);
     assert_eq!(output, (expected, 1));
 }
+
+#[test]
+fn batch_add() {
+    // Tests with crate attributes or `#[macro_use] extern crate` items aren't batched, and the
+    // `extern crate` items of the others are moved to the root of the batch, once.
+    let mut batch = Batch::new(DEFAULT_EDITION);
+    assert_eq!(batch.add("#![feature(never_type)]\nlet x: ! = panic!();", "asdf"), None);
+    assert_eq!(batch.add("#[macro_use] extern crate qwop;\nqwop!();", "asdf"), None);
+    assert_eq!(batch.add("extern crate qwop;\nqwop::foo();", "asdf"), Some(0));
+    assert_eq!(batch.add("// a comment\nextern crate qwop;\nassert!(true);", "asdf"), Some(1));
+    assert_eq!(batch.crates, vec!["extern crate qwop;".to_owned()]);
+    assert_eq!(batch.tests, vec!["qwop::foo();\n".to_owned(), "assert!(true);\n".to_owned()]);
+    assert!(!batch.uses_crate);
+}
+
+#[test]
+fn batch_source() {
+    let opts = TestOptions::default();
+    let mut batch = Batch::new(DEFAULT_EDITION);
+    batch.add("use asdf::qwop;\nassert_eq!(2+2, 4);", "asdf");
+    batch.add("fn main() -> Result<(), String> {\n    Ok(())\n}", "asdf");
+    let source = batch.source("asdf", &opts);
+
+    assert!(source.starts_with("#![allow(unused)]\nextern crate asdf;\n"));
+    assert!(source.contains(
+        "mod __doctest_0 {
+fn main() {
+use asdf::qwop;
+assert_eq!(2+2, 4);
+}
+#[allow(unused_imports)]
+use super::*;
+"
+    ));
+    assert!(source.contains(
+        "mod __doctest_1 {
+fn main() -> Result<(), String> {
+    Ok(())
+}
+"
+    ));
+    assert!(source.contains("        Some(1) => __doctest_1::__run(),\n"));
+}
@@ -14,9 +14,13 @@ use crate::config::RenderInfo;
 use crate::fold::DocFolder;
 use crate::formats::item_type::ItemType;
 use crate::formats::Impl;
-use crate::html::render::cache::{extern_location, get_index_search_type, ExternalLocation};
+use crate::html::render::bundle::BundleOptions;
+use crate::html::render::cache::{
+    extern_location, full_text_terms, get_index_search_type, ExternalLocation,
+};
 use crate::html::render::IndexItem;
 use crate::html::render::{plain_text_summary, shorten};
+use crate::scrape_examples::FnCallLocations;
 
 thread_local!(crate static CACHE_KEY: RefCell<Arc<Cache>> = Default::default());
 
@@ -91,6 +95,10 @@ pub struct Cache {
     /// This is stored in `Cache` so it doesn't need to be passed through all rustdoc functions.
     pub document_private: bool,
 
+    /// Whether to build the full-text search index, from the words of the documentation of
+    /// each item in the search index.
+    pub full_text_search: bool,
+
     // Private fields only used when initially crawling a crate to build a cache
     stack: Vec<String>,
     parent_stack: Vec<DefId>,
@@ -122,13 +130,19 @@ pub struct Cache {
     /// Aliases added through `#[doc(alias = "...")]`. Since a few items can have the same alias,
     /// we need the alias element to have an array of items.
     pub aliases: BTreeMap<String, Vec<usize>>,
+
+    /// The calls to local functions and methods found in the scraped examples
+    /// (see `--with-examples`), shown on their pages.
+    crate call_locations: FxHashMap<DefId, FnCallLocations>,
 }
 
 impl Cache {
     pub fn from_krate(
         render_info: RenderInfo,
         document_private: bool,
+        full_text_search: bool,
         extern_html_root_urls: &BTreeMap<String, String>,
+        bundle: &BundleOptions,
         dst: &Path,
         mut krate: clean::Crate,
     ) -> (clean::Crate, Cache) {
@@ -141,6 +155,7 @@ impl Cache {
             deref_trait_did,
             deref_mut_trait_did,
             owned_box_did,
+            call_locations,
             ..
         } = render_info;
 
@@ -155,11 +170,13 @@ impl Cache {
             access_levels,
             crate_version: krate.version.take(),
             document_private,
+            full_text_search,
             traits: krate.external_traits.replace(Default::default()),
             deref_trait_did,
             deref_mut_trait_did,
             owned_box_did,
             masked_crates: mem::take(&mut krate.masked_crates),
+            call_locations,
             ..Cache::default()
         };
 
@@ -174,9 +191,8 @@ impl Cache {
                 _ => PathBuf::new(),
             };
             let extern_url = extern_html_root_urls.get(&e.name).map(|u| &**u);
-            cache
-                .extern_locations
-                .insert(n, (e.name.clone(), src_root, extern_location(e, extern_url, &dst)));
+            let location = extern_location(e, extern_url, &dst, bundle);
+            cache.extern_locations.insert(n, (e.name.clone(), src_root, location));
 
             let did = DefId { krate: n, index: CRATE_DEF_INDEX };
             cache.external_paths.insert(did, (vec![e.name.to_string()], ItemType::Module));
@@ -317,6 +333,11 @@ impl DocFolder for Cache {
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
+                            full_text_terms: if self.full_text_search {
+                                full_text_terms(item.doc_value())
+                            } else {
+                                Vec::new()
+                            },
                         });
 
                         for alias in item.attrs.get_doc_aliases() {
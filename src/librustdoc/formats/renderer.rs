@@ -53,7 +53,9 @@ pub fn run_format<T: FormatRenderer>(
     let (krate, mut cache) = Cache::from_krate(
         render_info.clone(),
         options.document_private,
+        options.full_text_search,
         &options.extern_html_root_urls,
+        &options.bundle,
         &options.output,
         krate,
     );
@@ -18,6 +18,7 @@ use crate::clean::{self, PrimitiveType};
 use crate::formats::cache::cache;
 use crate::formats::item_type::ItemType;
 use crate::html::escape::Escape;
+use crate::html::render::bundle;
 use crate::html::render::cache::ExternalLocation;
 use crate::html::render::CURRENT_DEPTH;
 
@@ -496,29 +497,40 @@ pub fn href(did: DefId) -> Option<(String, ItemType, Vec<String>)> {
                 shortty,
                 match cache.extern_locations[&did.krate] {
                     (.., ExternalLocation::Remote(ref s)) => s.to_string(),
-                    (.., ExternalLocation::Local) => "../".repeat(depth),
+                    (.., ExternalLocation::Local) => {
+                        bundle::record_link(item_page_path(fqp, shortty));
+                        "../".repeat(depth)
+                    }
                     (.., ExternalLocation::Unknown) => return None,
                 },
             )
         }
     };
+    url.push_str(&item_page_path(fqp, shortty));
+    Some((url, shortty, fqp.to_vec()))
+}
+
+/// Returns the path of the page of the item with the given path and type, relative to the root of
+/// the documentation.
+crate fn item_page_path(fqp: &[String], shortty: ItemType) -> String {
+    let mut path = String::new();
     for component in &fqp[..fqp.len() - 1] {
-        url.push_str(component);
-        url.push_str("/");
+        path.push_str(component);
+        path.push_str("/");
     }
     match shortty {
         ItemType::Module => {
-            url.push_str(fqp.last().unwrap());
-            url.push_str("/index.html");
+            path.push_str(fqp.last().unwrap());
+            path.push_str("/index.html");
         }
         _ => {
-            url.push_str(shortty.as_str());
-            url.push_str(".");
-            url.push_str(fqp.last().unwrap());
-            url.push_str(".html");
+            path.push_str(shortty.as_str());
+            path.push_str(".");
+            path.push_str(fqp.last().unwrap());
+            path.push_str(".html");
         }
     }
-    Some((url, shortty, fqp.to_vec()))
+    path
 }
 
 /// Used when rendering a `ResolvedPath` structure. This invokes the `path`
@@ -578,6 +590,11 @@ fn primitive_link(
                 let loc = match m.extern_locations[&def_id.krate] {
                     (ref cname, _, ExternalLocation::Remote(ref s)) => Some((cname, s.to_string())),
                     (ref cname, _, ExternalLocation::Local) => {
+                        bundle::record_link(format!(
+                            "{}/primitive.{}.html",
+                            cname,
+                            prim.to_url_str()
+                        ));
                         let len = CURRENT_DEPTH.with(|s| s.get());
                         Some((cname, "../".repeat(len)))
                     }
@@ -40,6 +40,20 @@ pub fn render_with_highlighting(
     out
 }
 
+/// Highlights `src` like `render_with_highlighting`, additionally wrapping the code in each
+/// of the `marked` byte ranges (e.g. the calls in a scraped example) in a `highlight` span.
+pub fn render_with_marked_ranges(
+    src: &str,
+    class: Option<&str>,
+    marked: &[(usize, usize)],
+) -> String {
+    let mut out = String::with_capacity(src.len());
+    write_header(&mut out, class);
+    write_marked_code(&mut out, src, marked);
+    write_footer(&mut out, None);
+    out
+}
+
 fn write_header(out: &mut String, class: Option<&str>) {
     write!(out, "<div class=\"example-wrap\"><pre class=\"rust {}\">\n", class.unwrap_or_default())
         .unwrap()
@@ -55,6 +69,43 @@ fn write_code(out: &mut String, src: &str) {
     });
 }
 
+fn write_marked_code(out: &mut String, src: &str, marked: &[(usize, usize)]) {
+    // Marked ranges may overlap (e.g. nested calls), so merge them first.
+    let mut ranges: Vec<(usize, usize)> = Vec::with_capacity(marked.len());
+    let mut sorted = marked.to_vec();
+    sorted.sort();
+    for (lo, hi) in sorted {
+        match ranges.last_mut() {
+            Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+            _ => ranges.push((lo, hi)),
+        }
+    }
+
+    let mut ranges = ranges.into_iter().filter(|&(lo, hi)| lo < hi).peekable();
+    let mut pos = 0;
+    let mut in_range = false;
+    Classifier::new(src).highlight(&mut |highlight| match highlight {
+        Highlight::Token { text, class } => {
+            if !in_range && ranges.peek().map_or(false, |&(lo, _)| pos >= lo) {
+                out.push_str("<span class=\"highlight\">");
+                in_range = true;
+            }
+            string(out, Escape(text), class);
+            pos += text.len();
+            if in_range && ranges.peek().map_or(false, |&(_, hi)| pos >= hi) {
+                exit_span(out);
+                in_range = false;
+                ranges.next();
+            }
+        }
+        Highlight::EnterSpan { class } => enter_span(out, class),
+        Highlight::ExitSpan => exit_span(out),
+    });
+    if in_range {
+        exit_span(out);
+    }
+}
+
 fn write_footer(out: &mut String, playground_button: Option<&str>) {
     write!(out, "</pre>{}</div>\n", playground_button.unwrap_or_default()).unwrap()
 }
@@ -1,4 +1,4 @@
-use super::write_code;
+use super::{write_code, write_marked_code};
 use expect_test::expect_file;
 
 #[test]
@@ -12,6 +12,21 @@ fn test_html_highlighting() {
     expect_file!["fixtures/sample.html"].assert_eq(&html);
 }
 
+#[test]
+fn test_marked_ranges() {
+    let src = "fn main() { foo(foo(1)); bar(); }";
+    let mut out = String::new();
+    // The ranges of both calls to `foo`, which overlap.
+    write_marked_code(&mut out, src, &[(16, 22), (12, 23)]);
+    assert_eq!(
+        out,
+        "<span class=\"kw\">fn</span> <span class=\"ident\">main</span>() { \
+         <span class=\"highlight\"><span class=\"ident\">foo</span>(\
+         <span class=\"ident\">foo</span>(<span class=\"number\">1</span>))</span>; \
+         <span class=\"ident\">bar</span>(); }"
+    );
+}
+
 const STYLE: &str = r#"
 <style>
 .kw { color: #8959A8; }
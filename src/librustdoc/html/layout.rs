@@ -17,6 +17,8 @@ pub struct Layout {
     /// If false, the `select` element to have search filtering by crates on rendered docs
     /// won't be generated.
     pub generate_search_filter: bool,
+    /// If true, the full-text search index is loaded along with the search index.
+    pub full_text_search: bool,
 }
 
 pub struct Page<'a> {
@@ -116,6 +118,7 @@ pub fn render<T: Print, S: Print>(
     {static_extra_scripts}\
     {extra_scripts}\
     <script defer src=\"{root_path}search-index{suffix}.js\"></script>\
+    {full_text_script}\
 </body>\
 </html>",
         css_extension = if layout.css_file_extension.is_some() {
@@ -210,6 +213,15 @@ pub fn render<T: Print, S: Print>(
                 )
             })
             .collect::<String>(),
+        full_text_script = if layout.full_text_search {
+            format!(
+                "<script defer src=\"{root_path}search-fulltext{suffix}.js\"></script>",
+                root_path = page.root_path,
+                suffix = page.resource_suffix
+            )
+        } else {
+            String::new()
+        },
         filter_crates = if layout.generate_search_filter {
             "<select id=\"crate-search\">\
                  <option value=\"All crates\">All crates</option>\
@@ -27,6 +27,7 @@ use rustc_session::lint;
 use rustc_span::edition::Edition;
 use rustc_span::Span;
 use std::borrow::Cow;
+use std::cmp;
 use std::collections::VecDeque;
 use std::default::Default;
 use std::fmt::Write;
@@ -766,6 +767,14 @@ pub struct LangString {
     pub edition: Option<Edition>,
 }
 
+/// Splits the attributes of a code block into tokens.
+fn tokens(string: &str) -> impl Iterator<Item = &str> {
+    string
+        .split(|c: char| !(c == '_' || c == '-' || c.is_alphanumeric()))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum Ignore {
     All,
@@ -789,6 +798,11 @@ impl LangString {
         }
     }
 
+    /// The attributes written after the opening fence of the code block.
+    crate fn attributes(&self) -> Vec<String> {
+        tokens(&self.original).map(str::to_owned).collect()
+    }
+
     fn parse_without_check(
         string: &str,
         allow_error_code_check: ErrorCodes,
@@ -810,11 +824,9 @@ impl LangString {
         let mut ignores = vec![];
 
         data.original = string.to_owned();
-        let tokens = string.split(|c: char| !(c == '_' || c == '-' || c.is_alphanumeric()));
 
-        for token in tokens {
-            match token.trim() {
-                "" => {}
+        for token in tokens(string) {
+            match token {
                 "should_panic" => {
                     data.should_panic = true;
                     seen_rust_tags = !seen_other_tags;
@@ -1064,6 +1076,29 @@ pub fn plain_text_summary(md: &str) -> String {
     s
 }
 
+/// Renders all of the provided markdown as plain text, for the full-text search index.
+///
+/// - Headings, links, and formatting are stripped.
+/// - Inline code is rendered as-is.
+/// - HTML and code blocks are ignored.
+crate fn plain_text(md: &str) -> String {
+    let mut s = String::with_capacity(md.len());
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(md, opts()) {
+        match &event {
+            Event::Text(text) if !in_code_block => s.push_str(text),
+            Event::Code(code) => s.push_str(code),
+            Event::Start(Tag::CodeBlock(..)) => in_code_block = true,
+            Event::End(Tag::CodeBlock(..)) => in_code_block = false,
+            Event::HardBreak | Event::SoftBreak | Event::End(_) => s.push(' '),
+            _ => (),
+        }
+    }
+
+    s
+}
+
 pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     if md.is_empty() {
         return vec![];
@@ -1116,6 +1151,110 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     links
 }
 
+/// Returns the text of each heading in the markdown, along with the range of bytes it occupies.
+crate fn markdown_headings(md: &str) -> Vec<(String, Range<usize>)> {
+    let mut headings = vec![];
+    let mut heading: Option<(String, Range<usize>)> = None;
+
+    for (event, range) in Parser::new_ext(md, opts()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(_)) => heading = Some((String::new(), range)),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((ref mut s, _)) = heading {
+                    s.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(_)) => {
+                if let Some((s, range)) = heading.take() {
+                    let end = range.start + md[range.clone()].trim_end().len();
+                    headings.push((s.trim().to_owned(), range.start..end));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// Rewrites the links of `md` which are in `links` (i.e. the intra-doc links) as inline links to
+/// their `href`, keeping the rest of the Markdown as it is.
+crate fn replace_markdown_links<'a>(md: &'a str, links: &'a [RenderedLink]) -> String {
+    if links.is_empty() {
+        return md.to_owned();
+    }
+
+    // The destination of the reference links without a definition is their original text, so all
+    // the links can be matched the same way.
+    let mut replacer = |broken_link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|link| *link.original_text == *broken_link.reference)
+            .map(|link| (link.original_text.as_str().into(), "".into()))
+    };
+    let p = Parser::new_with_broken_link_callback(md, opts(), Some(&mut replacer));
+
+    let mut s = String::with_capacity(md.len());
+    let mut written = 0;
+    // The range and new destination of the link being replaced, and the range of its text.
+    let mut current: Option<(Range<usize>, &str, Option<Range<usize>>)> = None;
+    for (event, range) in p.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link(_, dest, _)) => {
+                current = links
+                    .iter()
+                    .find(|link| *link.original_text == *dest)
+                    .map(|link| (range, link.href.as_str(), None));
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some((range, href, Some(text))) = current.take() {
+                    s.push_str(&md[written..range.start]);
+                    write!(s, "[{}]({})", &md[text], href).unwrap();
+                    written = range.end;
+                }
+            }
+            _ => {
+                if let Some((_, _, ref mut text)) = current {
+                    *text = Some(match text.take() {
+                        Some(text) => text.start..range.end,
+                        None => range,
+                    });
+                }
+            }
+        }
+    }
+    s.push_str(&md[written..]);
+    s
+}
+
+/// Moves the headings of `md` down by `levels` levels, so that they come under the heading of the
+/// section the Markdown is put in. Headings don't go further down than level 6, and setext headings
+/// are rewritten as ATX headings.
+crate fn shift_markdown_headings(md: &str, levels: u32) -> String {
+    let mut s = String::with_capacity(md.len());
+    let mut written = 0;
+    for (event, range) in Parser::new_ext(md, opts()).into_offset_iter() {
+        if let Event::Start(Tag::Heading(level)) = event {
+            let hashes = "#".repeat(cmp::min(level + levels, 6) as usize);
+            let heading = md[range.clone()].trim_end();
+            s.push_str(&md[written..range.start]);
+            if let Some(text) = heading.trim_start().strip_prefix('#') {
+                s.push_str(&heading[..heading.len() - heading.trim_start().len()]);
+                s.push_str(&hashes);
+                s.push_str(text.trim_start_matches('#'));
+            } else {
+                // The text of a setext heading is on the lines above its underline.
+                let mut lines = heading.lines().map(str::trim).collect::<Vec<_>>();
+                lines.pop();
+                write!(s, "{} {}", hashes, lines.join(" ")).unwrap();
+            }
+            written = range.start + heading.len();
+        }
+    }
+    s.push_str(&md[written..]);
+    s
+}
+
 #[derive(Debug)]
 crate struct RustCodeBlock {
     /// The range in the markdown that the code block occupies. Note that this includes the fences
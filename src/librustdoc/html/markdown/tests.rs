@@ -1,5 +1,9 @@
-use super::plain_text_summary;
+use super::{
+    markdown_headings, plain_text, plain_text_summary, replace_markdown_links,
+    shift_markdown_headings,
+};
 use super::{ErrorCodes, IdMap, Ignore, LangString, Markdown, MarkdownHtml};
+use crate::clean::RenderedLink;
 use rustc_span::edition::{Edition, DEFAULT_EDITION};
 use std::cell::RefCell;
 
@@ -129,6 +133,18 @@ fn test_lang_string_parse() {
     );
 }
 
+#[test]
+fn test_lang_string_attributes() {
+    fn t(s: &str, expect: &[&str]) {
+        assert_eq!(LangString::parse(s, ErrorCodes::Yes, true, None).attributes(), expect);
+    }
+    t("", &[]);
+    t("rust", &["rust"]);
+    t("should_panic,edition2018", &["should_panic", "edition2018"]);
+    t("{.no_run .example}", &["no_run", "example"]);
+    t("compile_fail E0450", &["compile_fail", "E0450"]);
+}
+
 #[test]
 fn test_header() {
     fn t(input: &str, expect: &str) {
@@ -226,6 +242,26 @@ fn test_plain_text_summary() {
     t("<div>hello</div>", "");
 }
 
+#[test]
+fn test_plain_text() {
+    fn t(input: &str, expect: &str) {
+        let output = plain_text(input);
+        assert_eq!(
+            output.split_whitespace().collect::<Vec<_>>().join(" "),
+            expect,
+            "original: {}",
+            input
+        );
+    }
+
+    t("hello [Rust](https://www.rust-lang.org) :)", "hello Rust :)");
+    t("first paragraph\n\nsecond paragraph", "first paragraph second paragraph");
+    t("# header\ntext", "header text");
+    t("code `let x = i32;` ...", "code let x = i32; ...");
+    t("before\n\n```\nfn main() {}\n```\n\nafter", "before after");
+    t("<div>hello</div>", "");
+}
+
 #[test]
 fn test_markdown_html_escape() {
     fn t(input: &str, expect: &str) {
@@ -239,3 +275,57 @@ fn test_markdown_html_escape() {
     t("Struct<'a, T>", "<p>Struct&lt;'a, T&gt;</p>\n");
     t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
 }
+
+#[test]
+fn test_markdown_headings() {
+    fn t(input: &str, expect: &[&str]) {
+        let output: Vec<_> = markdown_headings(input).into_iter().map(|(s, _)| s).collect();
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("", &[]);
+    t("no headings", &[]);
+    t("# Safety\n\nText\n\n## Panics  ", &["Safety", "Panics"]);
+    t("Errors\n======", &["Errors"]);
+    t("# The `Result`", &["The Result"]);
+    t("```\n# not a heading\n```", &[]);
+}
+
+#[test]
+fn test_replace_markdown_links() {
+    fn t(input: &str, expect: &str) {
+        let links = ["Foo", "`Bar`", "crate::baz"]
+            .iter()
+            .map(|&original_text| RenderedLink {
+                original_text: original_text.to_owned(),
+                new_text: original_text.trim_matches('`').to_owned(),
+                href: format!("{}.md", original_text.trim_matches('`')),
+            })
+            .collect::<Vec<_>>();
+        let output = replace_markdown_links(input, &links);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("no links", "no links");
+    t("see [Foo] and [`Bar`].", "see [Foo](Foo.md) and [`Bar`](Bar.md).");
+    t("the [*baz*](crate::baz) function", "the [*baz*](crate::baz.md) function");
+    t("[text][Foo]", "[text](Foo.md)");
+    t("[Foo]\n\n[Foo]: crate::baz", "[Foo](crate::baz.md)\n\n[Foo]: crate::baz");
+    t("[web](https://www.rust-lang.org) and [Qux]", "[web](https://www.rust-lang.org) and [Qux]");
+    t("`[Foo]`", "`[Foo]`");
+}
+
+#[test]
+fn test_shift_markdown_headings() {
+    fn t(input: &str, levels: u32, expect: &str) {
+        let output = shift_markdown_headings(input, levels);
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("no headings", 1, "no headings");
+    t("# Examples\n\ntext", 1, "## Examples\n\ntext");
+    t("text\n\n## Panics #\n", 3, "text\n\n##### Panics #\n");
+    t("### Deep\n", 4, "###### Deep\n");
+    t("Setext\nheading\n===\n\ntext", 1, "## Setext heading\n\ntext");
+    t("```\n# not a heading\n```", 1, "```\n# not a heading\n```");
+}
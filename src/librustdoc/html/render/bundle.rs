@@ -0,0 +1,174 @@
+//! Combining the documentation of crates documented separately into one site.
+//!
+//! Besides the directory of each crate, the documentation written to an output directory has
+//! files shared by all its crates: the search index, the index of the source files, the
+//! implementors of each trait and the list of crates. By default (`--merge=shared`), each run of
+//! rustdoc updates those files with the crate it documents.
+//!
+//! When the crates are documented separately (e.g. on different machines), each run can instead
+//! use `--merge=none`, which doesn't touch the shared files but writes the crate's part of them,
+//! a `CrateInfo`, to `--parts-out-dir`. Once the crates' output directories are copied into one,
+//! a last run with `--merge=finalize` and an `--include-parts-dir` for each of the other crates
+//! writes the shared files of the whole bundle, and checks that the links between its crates
+//! point to pages which exist.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::mem;
+use std::path::PathBuf;
+
+use rustc_session::getopts;
+use serde::{Deserialize, Serialize};
+
+/// How the shared files are written (see `--merge`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+crate enum MergeMode {
+    /// The shared files aren't written; the crate's part of them is written to `--parts-out-dir`.
+    None,
+    /// The crate is added to the shared files of the output directory.
+    Shared,
+    /// The shared files are written from scratch, for the crate and the included parts.
+    Finalize,
+}
+
+/// The name of the file a crate's part of the shared files is written to in `--parts-out-dir`.
+crate const CRATE_INFO_FILE: &str = "crate-info.json";
+
+/// A crate's part of the files shared by all the crates of the output directory.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+crate struct CrateInfo {
+    crate name: String,
+    /// The crate's entry in `search-index.js`.
+    crate search_index: String,
+    /// The crate's entry in `search-fulltext.js`, if it has one.
+    crate full_text_index: Option<String>,
+    /// The crate's entry in `source-files.js`, if its source files are documented.
+    crate sources: Option<String>,
+    /// The crate's implementations of traits from other crates, by the path of the trait's file
+    /// in the `implementors` directory.
+    crate implementors: BTreeMap<String, String>,
+    /// The pages of the crate, relative to the output directory.
+    crate pages: BTreeSet<String>,
+    /// The pages of other crates of the bundle which the crate's pages link to, relative to the
+    /// output directory.
+    crate links: BTreeSet<String>,
+}
+
+#[derive(Clone, Debug)]
+crate struct BundleOptions {
+    crate merge: MergeMode,
+    crate parts_out_dir: Option<PathBuf>,
+    /// The parts of the other crates of the bundle, read from `--include-parts-dir`.
+    crate included: Vec<CrateInfo>,
+}
+
+impl BundleOptions {
+    crate fn new(
+        matches: &getopts::Matches,
+        diag: &rustc_errors::Handler,
+    ) -> Result<BundleOptions, i32> {
+        let merge = match matches.opt_str("merge").as_deref() {
+            None | Some("shared") => MergeMode::Shared,
+            Some("none") => MergeMode::None,
+            Some("finalize") => MergeMode::Finalize,
+            Some(s) => {
+                diag.struct_err(&format!("unknown value for `--merge`: `{}`", s))
+                    .note("the possible values are `none`, `shared` and `finalize`")
+                    .emit();
+                return Err(1);
+            }
+        };
+        let parts_out_dir = matches.opt_str("parts-out-dir").map(PathBuf::from);
+        if parts_out_dir.is_some() && merge != MergeMode::None {
+            diag.struct_err("`--parts-out-dir` can only be used with `--merge=none`").emit();
+            return Err(1);
+        }
+        let include_parts_dirs = matches.opt_strs("include-parts-dir");
+        if !include_parts_dirs.is_empty() && merge == MergeMode::Shared {
+            diag.struct_err("`--include-parts-dir` can't be used with `--merge=shared`").emit();
+            return Err(1);
+        }
+
+        let mut included = Vec::with_capacity(include_parts_dirs.len());
+        for dir in include_parts_dirs {
+            let path = PathBuf::from(&dir).join(CRATE_INFO_FILE);
+            let info = fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|contents| {
+                serde_json::from_str::<CrateInfo>(&contents).map_err(|e| e.to_string())
+            });
+            match info {
+                Ok(info) => included.push(info),
+                Err(e) => {
+                    diag.struct_err(&format!(
+                        "failed to read the documentation parts in `{}`: {}",
+                        dir, e
+                    ))
+                    .emit();
+                    return Err(1);
+                }
+            }
+        }
+        Ok(BundleOptions { merge, parts_out_dir, included })
+    }
+
+    /// Whether the documentation of `krate` is part of the bundle, so links to it are relative
+    /// even if it hasn't been documented into the output directory (yet).
+    crate fn includes(&self, krate: &str) -> bool {
+        self.included.iter().any(|info| info.name == krate)
+    }
+}
+
+thread_local!(
+    /// The pages of other crates documented into the output directory which the pages rendered so
+    /// far link to.
+    static LINKS: RefCell<BTreeSet<String>> = Default::default()
+);
+
+/// Records a link to a page of another crate documented into the output directory, given the
+/// page's path relative to the output directory.
+crate fn record_link(page: String) {
+    LINKS.with(|links| links.borrow_mut().insert(page));
+}
+
+/// Returns the links recorded since the last call.
+crate fn take_links() -> BTreeSet<String> {
+    LINKS.with(|links| mem::take(&mut *links.borrow_mut()))
+}
+
+/// Checks that the links between the crates of a bundle point to pages of the bundle.
+crate fn check_links(crates: &[&CrateInfo], diag: &rustc_errors::Handler) {
+    let names: BTreeSet<&str> = crates.iter().map(|info| &*info.name).collect();
+    let pages: BTreeSet<&str> =
+        crates.iter().flat_map(|info| info.pages.iter().map(|page| &**page)).collect();
+
+    for info in crates {
+        // The missing pages, by the crate they should be in.
+        let mut missing: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for link in &info.links {
+            if !pages.contains(&**link) {
+                let krate = link.split('/').next().unwrap_or_default();
+                missing.entry(krate).or_default().push(link);
+            }
+        }
+        for (krate, links) in missing {
+            if !names.contains(krate) {
+                diag.struct_warn(&format!(
+                    "`{}` links to `{}`, which isn't part of the documentation bundle",
+                    info.name, krate
+                ))
+                .help(&format!(
+                    "add the parts of `{}` to the bundle with `--include-parts-dir`",
+                    krate
+                ))
+                .emit();
+                continue;
+            }
+            let mut warn =
+                diag.struct_warn(&format!("`{}` links to missing pages of `{}`", info.name, krate));
+            for link in links {
+                warn.note(&format!("no page at `{}`", link));
+            }
+            warn.emit();
+        }
+    }
+}
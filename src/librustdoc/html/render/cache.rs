@@ -1,16 +1,20 @@
 use std::collections::BTreeMap;
+use std::iter;
 use std::path::Path;
 
 use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
 use rustc_span::symbol::sym;
 use serde::Serialize;
 
-use crate::clean::types::GetDefId;
-use crate::clean::{self, AttributesExt};
+use crate::clean::{self, AttributesExt, PrimitiveType};
 use crate::formats::cache::Cache;
 use crate::formats::item_type::ItemType;
+use crate::html::markdown;
+use crate::html::render::bundle::BundleOptions;
 use crate::html::render::{plain_text_summary, shorten};
-use crate::html::render::{Generic, IndexItem, IndexItemFunctionType, RenderType, TypeWithKind};
+use crate::html::render::{IndexItem, IndexItemFunctionType, RenderType};
 
 /// Indicates where an external crate can be found.
 pub enum ExternalLocation {
@@ -28,11 +32,13 @@ pub fn extern_location(
     e: &clean::ExternalCrate,
     extern_url: Option<&str>,
     dst: &Path,
+    bundle: &BundleOptions,
 ) -> ExternalLocation {
     use ExternalLocation::*;
-    // See if there's documentation generated into the local directory
+    // See if there's documentation generated into the local directory, or which will be once the
+    // documentation bundle is merged
     let local_location = dst.join(&e.name);
-    if local_location.is_dir() {
+    if local_location.is_dir() || bundle.includes(&e.name) {
         return Local;
     }
 
@@ -67,8 +73,15 @@ pub fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     let mut crate_items = Vec::with_capacity(cache.search_index.len());
     let mut crate_paths = vec![];
 
-    let Cache { ref mut search_index, ref orphan_impl_items, ref paths, ref mut aliases, .. } =
-        *cache;
+    let Cache {
+        ref mut search_index,
+        ref orphan_impl_items,
+        ref paths,
+        ref external_paths,
+        ref mut aliases,
+        full_text_search,
+        ..
+    } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
@@ -82,6 +95,11 @@ pub fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
+                full_text_terms: if full_text_search {
+                    full_text_terms(item.doc_value())
+                } else {
+                    Vec::new()
+                },
             });
             for alias in item.attrs.get_doc_aliases() {
                 aliases
@@ -115,6 +133,12 @@ pub fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
             }
         });
 
+        if let Some(search_type) = &mut item.search_type {
+            for ty in search_type.inputs.iter_mut().chain(&mut search_type.output) {
+                set_type_kinds(ty, paths, external_paths);
+            }
+        }
+
         // Omit the parent path if it is same to that of the prior item.
         if lastpath == item.path {
             item.path.clear();
@@ -164,70 +188,245 @@ pub fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     )
 }
 
+/// Words too common in documentation to be worth putting in the full-text search index. The
+/// search in `main.js` ignores them too.
+const STOP_WORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "be", "by", "for", "from", "if", "in", "into", "is", "it",
+    "its", "of", "on", "or", "that", "the", "this", "to", "with",
+];
+
+/// Words longer than this are unlikely to be searched for, so they aren't indexed.
+const MAX_TERM_LEN: usize = 32;
+
+/// Returns the words of the documentation `doc`, lowercased and deduplicated, for the full-text
+/// search index.
+crate fn full_text_terms(doc: Option<&str>) -> Vec<String> {
+    let text = match doc {
+        Some(doc) => markdown::plain_text(doc),
+        None => return Vec::new(),
+    };
+    let mut terms = text
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| {
+            word.chars().nth(1).is_some()
+                && word.len() <= MAX_TERM_LEN
+                && !word.chars().all(|c| c.is_ascii_digit())
+        })
+        .map(|word| word.to_lowercase())
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect::<Vec<_>>();
+    terms.sort_unstable();
+    terms.dedup();
+    terms
+}
+
+/// Builds the full-text search index, which maps each word of the documentation to the items
+/// whose documentation contains it. Items are numbered as in the search index built by
+/// `build_index`, after the crate itself.
+crate fn build_full_text_index(krate: &clean::Crate, cache: &Cache) -> String {
+    let crate_terms = full_text_terms(krate.module.as_ref().and_then(|module| module.doc_value()));
+    let all_terms =
+        iter::once(&crate_terms).chain(cache.search_index.iter().map(|item| &item.full_text_terms));
+
+    let mut postings = BTreeMap::<&str, Vec<usize>>::new();
+    for (idx, terms) in all_terms.enumerate() {
+        for term in terms {
+            postings.entry(term.as_str()).or_default().push(idx);
+        }
+    }
+
+    #[derive(Serialize)]
+    struct CrateData<'a> {
+        #[serde(rename = "t")]
+        terms: Vec<&'a str>,
+        // The items of each term, as the differences between consecutive indexes (which are
+        // smaller than the indexes themselves), starting from 0.
+        #[serde(rename = "i")]
+        items: Vec<Vec<usize>>,
+    }
+
+    let mut data = CrateData { terms: Vec::new(), items: Vec::new() };
+    for (term, idxs) in postings {
+        let mut prev = 0;
+        data.terms.push(term);
+        data.items.push(
+            idxs.into_iter()
+                .map(|idx| {
+                    let delta = idx - prev;
+                    prev = idx;
+                    delta
+                })
+                .collect(),
+        );
+    }
+
+    // Collect the index into a string
+    format!(
+        r#""{}":{}"#,
+        krate.name,
+        serde_json::to_string(&data)
+            .expect("failed serde conversion")
+            // Escaped like the search index, as it also goes through a JS string.
+            .replace(r"\", r"\\")
+            .replace("'", r"\'")
+            .replace("\\\"", "\\\\\"")
+    )
+}
+
 crate fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
-    let (all_types, ret_types) = match item.inner {
-        clean::FunctionItem(ref f) => (&f.all_types, &f.ret_types),
-        clean::MethodItem(ref m) => (&m.all_types, &m.ret_types),
-        clean::TyMethodItem(ref m) => (&m.all_types, &m.ret_types),
+    let (decl, generics) = match item.inner {
+        clean::FunctionItem(ref f) => (&f.decl, &f.generics),
+        clean::MethodItem(ref m) => (&m.decl, &m.generics),
+        clean::TyMethodItem(ref m) => (&m.decl, &m.generics),
         _ => return None,
     };
 
-    let inputs = all_types
+    // The receiver isn't searched for, the methods of a type being found by their path.
+    let inputs = decl
+        .inputs
+        .values
         .iter()
-        .map(|(ty, kind)| TypeWithKind::from((get_index_type(&ty), *kind)))
-        .filter(|a| a.ty.name.is_some())
+        .filter(|arg| arg.name != "self")
+        .filter_map(|arg| get_index_type(&arg.type_, Some(generics)))
         .collect();
-    let output = ret_types
-        .iter()
-        .map(|(ty, kind)| TypeWithKind::from((get_index_type(&ty), *kind)))
-        .filter(|a| a.ty.name.is_some())
-        .collect::<Vec<_>>();
-    let output = if output.is_empty() { None } else { Some(output) };
+    let output = match decl.output {
+        clean::FnRetTy::Return(ref return_type) => get_index_type(return_type, Some(generics)),
+        clean::FnRetTy::DefaultReturn => None,
+    };
 
     Some(IndexItemFunctionType { inputs, output })
 }
 
-fn get_index_type(clean_type: &clean::Type) -> RenderType {
-    RenderType {
-        ty: clean_type.def_id(),
-        idx: None,
-        name: get_index_type_name(clean_type, true).map(|s| s.to_ascii_lowercase()),
-        generics: get_generics(clean_type),
-    }
+/// Converts a type of a function's signature for the search index, keeping its generic arguments.
+///
+/// Generic parameters get the bounds they have in `generics`, which is `None` for the types
+/// within those bounds. References, slices, arrays, tuples and raw pointers are primitive types
+/// with the types they contain as generic arguments. `Self` is a generic parameter, and
+/// `<T as Trait>::Name` is an associated type with `T` as its generic argument. Returns `None`
+/// for `_`, which can't be part of a signature.
+fn get_index_type(
+    clean_type: &clean::Type,
+    generics: Option<&clean::Generics>,
+) -> Option<RenderType> {
+    let primitive = |prim: PrimitiveType, args: Vec<&clean::Type>| RenderType {
+        ty: None,
+        kind: Some(ItemType::Primitive),
+        name: prim.as_str().to_string(),
+        generics: args.into_iter().filter_map(|arg| get_index_type(arg, generics)).collect(),
+        bounds: None,
+    };
+    let param = |name: &str, bounds: Vec<RenderType>| RenderType {
+        ty: None,
+        kind: None,
+        name: name.to_ascii_lowercase(),
+        generics: Vec::new(),
+        bounds: Some(bounds),
+    };
+
+    Some(match *clean_type {
+        clean::ResolvedPath { ref path, did, .. } => {
+            let segment = path.segments.last()?;
+            let args = match segment.args {
+                clean::GenericArgs::AngleBracketed { ref args, ref bindings } => args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        clean::GenericArg::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    // `Iterator<Item = T>` is searched for like `Iterator<T>`.
+                    .chain(bindings.iter().filter_map(|binding| match binding.kind {
+                        clean::TypeBindingKind::Equality { ref ty } => Some(ty),
+                        clean::TypeBindingKind::Constraint { .. } => None,
+                    }))
+                    .collect::<Vec<_>>(),
+                clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                    inputs.iter().chain(output).collect()
+                }
+            };
+            RenderType {
+                ty: Some(did),
+                kind: None,
+                name: segment.name.to_ascii_lowercase(),
+                generics: args
+                    .into_iter()
+                    .filter_map(|arg| get_index_type(arg, generics))
+                    .collect(),
+                bounds: None,
+            }
+        }
+        clean::Generic(ref name) => {
+            param(name, generics.map(|generics| get_bounds(name, generics)).unwrap_or_default())
+        }
+        // `impl Trait` arguments are anonymous generic parameters.
+        clean::ImplTrait(ref bounds) => {
+            param("", bounds.iter().filter_map(get_bound_type).collect())
+        }
+        clean::Primitive(prim) => primitive(prim, Vec::new()),
+        clean::BorrowedRef { ref type_, .. } => primitive(PrimitiveType::Reference, vec![&**type_]),
+        clean::Slice(ref ty) => primitive(PrimitiveType::Slice, vec![&**ty]),
+        clean::Array(ref ty, _) => primitive(PrimitiveType::Array, vec![&**ty]),
+        clean::RawPointer(_, ref ty) => primitive(PrimitiveType::RawPointer, vec![&**ty]),
+        clean::Tuple(ref tys) if tys.is_empty() => primitive(PrimitiveType::Unit, Vec::new()),
+        clean::Tuple(ref tys) => primitive(PrimitiveType::Tuple, tys.iter().collect()),
+        clean::BareFunction(..) => primitive(PrimitiveType::Fn, Vec::new()),
+        clean::Never => primitive(PrimitiveType::Never, Vec::new()),
+        clean::QPath { ref name, ref self_type, .. } => RenderType {
+            ty: None,
+            kind: Some(ItemType::AssocType),
+            name: name.to_ascii_lowercase(),
+            generics: get_index_type(self_type, generics).into_iter().collect(),
+            bounds: None,
+        },
+        clean::Infer => return None,
+    })
 }
 
-fn get_index_type_name(clean_type: &clean::Type, accept_generic: bool) -> Option<String> {
-    match *clean_type {
-        clean::ResolvedPath { ref path, .. } => {
-            let segments = &path.segments;
-            let path_segment = segments.iter().last().unwrap_or_else(|| {
-                panic!(
-                "get_index_type_name(clean_type: {:?}, accept_generic: {:?}) had length zero path",
-                clean_type, accept_generic
-            )
-            });
-            Some(path_segment.name.clone())
+/// Returns the traits which the generic parameter `name` is bound by in `generics`.
+fn get_bounds(name: &str, generics: &clean::Generics) -> Vec<RenderType> {
+    let param_bounds = generics
+        .params
+        .iter()
+        .filter(|param| param.name == name)
+        .filter_map(|param| param.get_bounds())
+        .flatten();
+    let where_bounds = generics
+        .where_predicates
+        .iter()
+        .filter_map(|pred| match pred {
+            clean::WherePredicate::BoundPredicate { ty: clean::Generic(ty), bounds }
+                if ty == name =>
+            {
+                Some(bounds)
+            }
+            _ => None,
+        })
+        .flatten();
+    param_bounds.chain(where_bounds).filter_map(get_bound_type).collect()
+}
+
+/// Returns the trait of `bound`, unless it's a lifetime or a `?Trait` bound.
+fn get_bound_type(bound: &clean::GenericBound) -> Option<RenderType> {
+    match *bound {
+        clean::GenericBound::TraitBound(ref poly_trait, modifier)
+            if modifier != hir::TraitBoundModifier::Maybe =>
+        {
+            get_index_type(&poly_trait.trait_, None)
         }
-        clean::Generic(ref s) if accept_generic => Some(s.clone()),
-        clean::Primitive(ref p) => Some(format!("{:?}", p)),
-        clean::BorrowedRef { ref type_, .. } => get_index_type_name(type_, accept_generic),
-        // FIXME: add all from clean::Type.
         _ => None,
     }
 }
 
-fn get_generics(clean_type: &clean::Type) -> Option<Vec<Generic>> {
-    clean_type.generics().and_then(|types| {
-        let r = types
-            .iter()
-            .filter_map(|t| {
-                get_index_type_name(t, false).map(|name| Generic {
-                    name: name.to_ascii_lowercase(),
-                    defid: t.def_id(),
-                    idx: None,
-                })
-            })
-            .collect::<Vec<_>>();
-        if r.is_empty() { None } else { Some(r) }
-    })
+/// Sets the kind of `ty` and of the types within it, now that the paths of all the types which
+/// are documented are known.
+fn set_type_kinds(
+    ty: &mut RenderType,
+    paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+    external_paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+) {
+    if let Some(did) = ty.ty {
+        ty.kind = paths.get(&did).or_else(|| external_paths.get(&did)).map(|&(_, kind)| kind);
+    }
+    for inner in ty.generics.iter_mut().chain(ty.bounds.iter_mut().flatten()) {
+        set_type_kinds(inner, paths, external_paths);
+    }
 }
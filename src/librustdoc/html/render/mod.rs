@@ -25,6 +25,7 @@
 //! These threads are not parallelized (they haven't been a bottleneck yet), and
 //! both occur before the crate is rendered.
 
+crate mod bundle;
 pub mod cache;
 
 #[cfg(test)]
@@ -63,7 +64,7 @@ use rustc_span::symbol::{sym, Symbol};
 use serde::ser::SerializeSeq;
 use serde::{Serialize, Serializer};
 
-use crate::clean::{self, AttributesExt, Deprecation, GetDefId, RenderedLink, SelfTy, TypeKind};
+use crate::clean::{self, AttributesExt, Deprecation, GetDefId, RenderedLink, SelfTy};
 use crate::config::{RenderInfo, RenderOptions};
 use crate::docfs::{DocFS, PathError};
 use crate::doctree;
@@ -79,7 +80,9 @@ use crate::html::format::{print_abi_with_space, Buffer, PrintWithSpace};
 use crate::html::markdown::{self, ErrorCodes, IdMap, Markdown, MarkdownHtml, MarkdownSummaryLine};
 use crate::html::sources;
 use crate::html::{highlight, layout, static_files};
-use cache::{build_index, ExternalLocation};
+use crate::scrape_examples::CallData;
+use bundle::{BundleOptions, CrateInfo, MergeMode};
+use cache::{build_full_text_index, build_index, ExternalLocation};
 
 /// A pair of name and its optional document.
 pub type NameDoc = (String, Option<String>);
@@ -116,6 +119,8 @@ crate struct Context {
     /// Storage for the errors produced while generating documentation so they
     /// can be printed together at the end.
     pub errors: Rc<Receiver<String>>,
+    /// The crate's part of the files shared with the other crates of the output directory.
+    crate_info: Rc<RefCell<CrateInfo>>,
 }
 
 crate struct SharedContext {
@@ -156,6 +161,8 @@ crate struct SharedContext {
     pub edition: Edition,
     pub codes: ErrorCodes,
     playground: Option<markdown::Playground>,
+    /// How the files shared with the other crates of the output directory are written.
+    bundle: BundleOptions,
 }
 
 impl Context {
@@ -171,6 +178,12 @@ impl Context {
         let filename = format!("{}{}.{}", base, self.shared.resource_suffix, ext,);
         self.dst.join(&filename)
     }
+
+    /// Records a page of the crate, given its file name in the current module.
+    fn add_page(&self, file_name: &str) {
+        let page = format!("{}/{}", self.current.join("/"), file_name);
+        self.crate_info.borrow_mut().pages.insert(page);
+    }
 }
 
 impl SharedContext {
@@ -209,6 +222,8 @@ pub struct IndexItem {
     pub parent: Option<DefId>,
     pub parent_idx: Option<usize>,
     pub search_type: Option<IndexItemFunctionType>,
+    /// The words of the item's documentation, if the full-text search index is built.
+    pub full_text_terms: Vec<String>,
 }
 
 impl Serialize for IndexItem {
@@ -232,109 +247,50 @@ impl Serialize for IndexItem {
 #[derive(Debug)]
 crate struct RenderType {
     ty: Option<DefId>,
-    idx: Option<usize>,
-    name: Option<String>,
-    generics: Option<Vec<Generic>>,
-}
-
-impl Serialize for RenderType {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        if let Some(name) = &self.name {
-            let mut seq = serializer.serialize_seq(None)?;
-            if let Some(id) = self.idx {
-                seq.serialize_element(&id)?;
-            } else {
-                seq.serialize_element(&name)?;
-            }
-            if let Some(generics) = &self.generics {
-                seq.serialize_element(&generics)?;
-            }
-            seq.end()
-        } else {
-            serializer.serialize_none()
-        }
-    }
-}
-
-/// A type used for the search index.
-#[derive(Debug)]
-crate struct Generic {
+    /// The kind of the type, which is only known once all the paths have been collected.
+    kind: Option<ItemType>,
     name: String,
-    defid: Option<DefId>,
-    idx: Option<usize>,
+    generics: Vec<RenderType>,
+    /// If the type is a generic parameter, the traits it's bound by.
+    bounds: Option<Vec<RenderType>>,
 }
 
-impl Serialize for Generic {
+impl Serialize for RenderType {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        if let Some(id) = self.idx {
-            serializer.serialize_some(&id)
-        } else {
-            serializer.serialize_some(&self.name)
+        let mut seq = serializer.serialize_seq(None)?;
+        seq.serialize_element(&self.name)?;
+        seq.serialize_element(&self.kind)?;
+        // Generic parameters are the only types with bounds, even if they're empty.
+        if let Some(bounds) = &self.bounds {
+            seq.serialize_element(&self.generics)?;
+            seq.serialize_element(bounds)?;
+        } else if !self.generics.is_empty() {
+            seq.serialize_element(&self.generics)?;
         }
+        seq.end()
     }
 }
 
 /// Full type of functions/methods in the search index.
 #[derive(Debug)]
 pub struct IndexItemFunctionType {
-    inputs: Vec<TypeWithKind>,
-    output: Option<Vec<TypeWithKind>>,
+    inputs: Vec<RenderType>,
+    output: Option<RenderType>,
 }
 
 impl Serialize for IndexItemFunctionType {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        // If we couldn't figure out a type, just write `null`.
-        let mut iter = self.inputs.iter();
-        if match self.output {
-            Some(ref output) => iter.chain(output.iter()).any(|ref i| i.ty.name.is_none()),
-            None => iter.any(|ref i| i.ty.name.is_none()),
-        } {
-            serializer.serialize_none()
-        } else {
-            let mut seq = serializer.serialize_seq(None)?;
-            seq.serialize_element(&self.inputs)?;
-            if let Some(output) = &self.output {
-                if output.len() > 1 {
-                    seq.serialize_element(&output)?;
-                } else {
-                    seq.serialize_element(&output[0])?;
-                }
-            }
-            seq.end()
-        }
-    }
-}
-
-#[derive(Debug)]
-crate struct TypeWithKind {
-    ty: RenderType,
-    kind: TypeKind,
-}
-
-impl From<(RenderType, TypeKind)> for TypeWithKind {
-    fn from(x: (RenderType, TypeKind)) -> TypeWithKind {
-        TypeWithKind { ty: x.0, kind: x.1 }
-    }
-}
-
-impl Serialize for TypeWithKind {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         let mut seq = serializer.serialize_seq(None)?;
-        seq.serialize_element(&self.ty.name)?;
-        let x: ItemType = self.kind.into();
-        seq.serialize_element(&x)?;
+        seq.serialize_element(&self.inputs)?;
+        if let Some(output) = &self.output {
+            seq.serialize_element(output)?;
+        }
         seq.end()
     }
 }
@@ -395,6 +351,8 @@ impl FormatRenderer for Context {
             resource_suffix,
             static_root_path,
             generate_search_filter,
+            full_text_search,
+            bundle,
             ..
         } = options;
 
@@ -417,6 +375,7 @@ impl FormatRenderer for Context {
             krate: krate.name.clone(),
             css_file_extension: extension_css,
             generate_search_filter,
+            full_text_search,
         };
         let mut issue_tracker_base_url = None;
         let mut include_sources = true;
@@ -465,6 +424,7 @@ impl FormatRenderer for Context {
             edition,
             codes: ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build()),
             playground,
+            bundle,
         };
 
         // Add the default themes to the `Vec` of stylepaths
@@ -486,8 +446,10 @@ impl FormatRenderer for Context {
 
         // Build our search index
         let index = build_index(&krate, cache);
+        let full_text_index =
+            if full_text_search { Some(build_full_text_index(&krate, cache)) } else { None };
+        let crate_info = crate_info(&scx, &krate, index, full_text_index, cache);
 
-        let cache = Arc::new(cache);
         let mut cx = Context {
             current: Vec::new(),
             dst,
@@ -496,13 +458,14 @@ impl FormatRenderer for Context {
             shared: Arc::new(scx),
             all: Rc::new(RefCell::new(AllTypes::new())),
             errors: Rc::new(receiver),
+            crate_info: Rc::new(RefCell::new(crate_info)),
         };
 
         CURRENT_DEPTH.with(|s| s.set(0));
 
         // Write shared runs within a flock; disable thread dispatching of IO temporarily.
         Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(true);
-        write_shared(&cx, &krate, index, &md_opts, &cache)?;
+        write_shared(&cx, &krate, &md_opts)?;
         Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(false);
         Ok((cx, krate))
     }
@@ -510,6 +473,13 @@ impl FormatRenderer for Context {
     fn after_run(&mut self, diag: &rustc_errors::Handler) -> Result<(), Error> {
         Arc::get_mut(&mut self.shared).unwrap().fs.close();
         let nb_errors = self.errors.iter().map(|err| diag.struct_err(&err).emit()).count();
+        // The links can only be checked once all the pages of the crate have been rendered.
+        if self.shared.bundle.merge == MergeMode::Finalize {
+            let crate_info = self.crate_info.borrow();
+            let mut crates = vec![&*crate_info];
+            crates.extend(&self.shared.bundle.included);
+            bundle::check_links(&crates, diag);
+        }
         if nb_errors > 0 {
             Err(Error::new(io::Error::new(io::ErrorKind::Other, "I/O error"), ""))
         } else {
@@ -579,6 +549,14 @@ impl FormatRenderer for Context {
             &style_files,
         );
         self.shared.fs.write(&settings_file, v.as_bytes())?;
+
+        // All the pages of the crate have been rendered.
+        self.crate_info.borrow_mut().links = bundle::take_links();
+        if let Some(ref dir) = self.shared.bundle.parts_out_dir {
+            self.shared.ensure_dir(dir)?;
+            let crate_info = serde_json::to_string(&*self.crate_info.borrow()).unwrap();
+            self.shared.fs.write(&dir.join(bundle::CRATE_INFO_FILE), crate_info.as_bytes())?;
+        }
         Ok(())
     }
 
@@ -610,6 +588,7 @@ impl FormatRenderer for Context {
             self.shared.ensure_dir(&self.dst)?;
             let joint_dst = self.dst.join("index.html");
             scx.fs.write(&joint_dst, buf.as_bytes())?;
+            self.add_page("index.html");
         }
 
         // Render sidebar-items.js used throughout this module.
@@ -656,6 +635,7 @@ impl FormatRenderer for Context {
             self.shared.ensure_dir(&self.dst)?;
             let joint_dst = self.dst.join(file_name);
             self.shared.fs.write(&joint_dst, buf.as_bytes())?;
+            self.add_page(file_name);
 
             if !self.render_redirect_pages {
                 self.all.borrow_mut().append(full_path(self, &item), &item_type);
@@ -664,22 +644,17 @@ impl FormatRenderer for Context {
             // to the new one (without).
             if item_type == ItemType::Macro {
                 let redir_name = format!("{}.{}!.html", item_type, name);
-                let redir_dst = self.dst.join(redir_name);
+                let redir_dst = self.dst.join(&redir_name);
                 let v = layout::redirect(file_name);
                 self.shared.fs.write(&redir_dst, v.as_bytes())?;
+                self.add_page(&redir_name);
             }
         }
         Ok(())
     }
 }
 
-fn write_shared(
-    cx: &Context,
-    krate: &clean::Crate,
-    search_index: String,
-    options: &RenderOptions,
-    cache: &Cache,
-) -> Result<(), Error> {
+fn write_shared(cx: &Context, krate: &clean::Crate, options: &RenderOptions) -> Result<(), Error> {
     // Write out the shared files. Note that these are shared among all rustdoc
     // docs placed in the output directory, so this needs to be a synchronized
     // operation with respect to all other rustdocs running around.
@@ -937,80 +912,28 @@ themePicker.onblur = handleThemeButtonsBlur;
         Ok((ret, krates))
     }
 
-    use std::ffi::OsString;
-
-    #[derive(Debug)]
-    struct Hierarchy {
-        elem: OsString,
-        children: FxHashMap<OsString, Hierarchy>,
-        elems: FxHashSet<OsString>,
+    // With `--merge=shared`, the entries of the other crates are kept from the existing files.
+    // With `--merge=finalize`, they're those of the included parts, and the files are replaced.
+    let bundle = &cx.shared.bundle;
+    let crate_info = cx.crate_info.borrow();
+    let mut crates = vec![&*crate_info];
+    match bundle.merge {
+        MergeMode::None => return Ok(()),
+        MergeMode::Shared => {}
+        MergeMode::Finalize => crates.extend(&bundle.included),
     }
+    let keep_existing = bundle.merge == MergeMode::Shared;
 
-    impl Hierarchy {
-        fn new(elem: OsString) -> Hierarchy {
-            Hierarchy { elem, children: FxHashMap::default(), elems: FxHashSet::default() }
-        }
-
-        fn to_json_string(&self) -> String {
-            let mut subs: Vec<&Hierarchy> = self.children.values().collect();
-            subs.sort_unstable_by(|a, b| a.elem.cmp(&b.elem));
-            let mut files = self
-                .elems
-                .iter()
-                .map(|s| format!("\"{}\"", s.to_str().expect("invalid osstring conversion")))
-                .collect::<Vec<_>>();
-            files.sort_unstable_by(|a, b| a.cmp(b));
-            let subs = subs.iter().map(|s| s.to_json_string()).collect::<Vec<_>>().join(",");
-            let dirs =
-                if subs.is_empty() { String::new() } else { format!(",\"dirs\":[{}]", subs) };
-            let files = files.join(",");
-            let files =
-                if files.is_empty() { String::new() } else { format!(",\"files\":[{}]", files) };
-            format!(
-                "{{\"name\":\"{name}\"{dirs}{files}}}",
-                name = self.elem.to_str().expect("invalid osstring conversion"),
-                dirs = dirs,
-                files = files
-            )
-        }
-    }
-
-    if cx.shared.include_sources {
-        let mut hierarchy = Hierarchy::new(OsString::new());
-        for source in cx
-            .shared
-            .local_sources
-            .iter()
-            .filter_map(|p| p.0.strip_prefix(&cx.shared.src_root).ok())
-        {
-            let mut h = &mut hierarchy;
-            let mut elems = source
-                .components()
-                .filter_map(|s| match s {
-                    Component::Normal(s) => Some(s.to_owned()),
-                    _ => None,
-                })
-                .peekable();
-            loop {
-                let cur_elem = elems.next().expect("empty file path");
-                if elems.peek().is_none() {
-                    h.elems.insert(cur_elem);
-                    break;
-                } else {
-                    let e = cur_elem.clone();
-                    h.children.entry(cur_elem.clone()).or_insert_with(|| Hierarchy::new(e));
-                    h = h.children.get_mut(&cur_elem).expect("not found child");
-                }
-            }
-        }
-
+    if crates.iter().any(|info| info.sources.is_some()) {
         let dst = cx.dst.join(&format!("source-files{}.js", cx.shared.resource_suffix));
-        let (mut all_sources, _krates) = try_err!(collect(&dst, &krate.name, "sourcesIndex"), &dst);
-        all_sources.push(format!(
-            "sourcesIndex[\"{}\"] = {};",
-            &krate.name,
-            hierarchy.to_json_string()
-        ));
+        let mut all_sources = if keep_existing {
+            try_err!(collect(&dst, &krate.name, "sourcesIndex"), &dst).0
+        } else {
+            Vec::new()
+        };
+        all_sources.extend(crates.iter().filter_map(|info| {
+            Some(format!("sourcesIndex[\"{}\"] = {};", info.name, info.sources.as_ref()?))
+        }));
         all_sources.sort();
         let v = format!(
             "var N = null;var sourcesIndex = {{}};\n{}\ncreateSourceSidebar();\n",
@@ -1021,8 +944,15 @@ themePicker.onblur = handleThemeButtonsBlur;
 
     // Update the search index
     let dst = cx.dst.join(&format!("search-index{}.js", cx.shared.resource_suffix));
-    let (mut all_indexes, mut krates) = try_err!(collect_json(&dst, &krate.name), &dst);
-    all_indexes.push(search_index);
+    let (mut all_indexes, mut krates) = if keep_existing {
+        try_err!(collect_json(&dst, &krate.name), &dst)
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    for info in &crates {
+        all_indexes.push(info.search_index.clone());
+        krates.push(info.name.clone());
+    }
 
     // Sort the indexes by crate so the file will be generated identically even
     // with rustdoc running in parallel.
@@ -1035,6 +965,27 @@ themePicker.onblur = handleThemeButtonsBlur;
         v.push_str("\\\n}');\naddSearchOptions(searchIndex);initSearch(searchIndex);");
         cx.shared.fs.write(&dst, &v)?;
     }
+
+    // Update the full-text search index, in its own file so that the pages of crates without one
+    // don't have to load it. A crate documented without one still loses its previous entry, and
+    // the file is removed once no crate has an entry in it.
+    let dst = cx.dst.join(&format!("search-fulltext{}.js", cx.shared.resource_suffix));
+    let mut all_indexes = if keep_existing {
+        try_err!(collect_json(&dst, &krate.name), &dst).0
+    } else {
+        Vec::new()
+    };
+    all_indexes.extend(crates.iter().filter_map(|info| info.full_text_index.clone()));
+    all_indexes.sort();
+    if !all_indexes.is_empty() {
+        let mut v = String::from("var fullTextIndex = JSON.parse('{\\\n");
+        v.push_str(&all_indexes.join(",\\\n"));
+        // The search index is loaded first, so the search is already initialized.
+        v.push_str("\\\n}');\ninitFullTextSearch(fullTextIndex);");
+        cx.shared.fs.write(&dst, &v)?;
+    } else if dst.exists() {
+        try_err!(fs::remove_file(&dst), &dst);
+    }
     if options.enable_index_page {
         if let Some(index_page) = options.index_page.clone() {
             let mut md_opts = options.clone();
@@ -1056,7 +1007,6 @@ themePicker.onblur = handleThemeButtonsBlur;
                 extra_scripts: &[],
                 static_extra_scripts: &[],
             };
-            krates.push(krate.name.clone());
             krates.sort();
             krates.dedup();
 
@@ -1082,7 +1032,124 @@ themePicker.onblur = handleThemeButtonsBlur;
     }
 
     // Update the list of all implementors for traits
+    let mut implementors: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for info in &crates {
+        for (path, imps) in &info.implementors {
+            implementors
+                .entry(path.as_str())
+                .or_default()
+                .push(format!(r#"implementors["{}"] = {};"#, info.name, imps));
+        }
+    }
     let dst = cx.dst.join("implementors");
+    for (path, lines) in implementors {
+        let mydst = dst.join(path);
+        cx.shared.ensure_dir(mydst.parent().unwrap())?;
+
+        let mut all_implementors = if keep_existing {
+            try_err!(collect(&mydst, &krate.name, "implementors"), &mydst).0
+        } else {
+            Vec::new()
+        };
+        all_implementors.extend(lines);
+        // Sort the implementors by crate so the file will be generated
+        // identically even with rustdoc running in parallel.
+        all_implementors.sort();
+
+        let mut v = String::from("(function() {var implementors = {};\n");
+        for implementor in &all_implementors {
+            writeln!(v, "{}", *implementor).unwrap();
+        }
+        v.push_str(
+            "if (window.register_implementors) {\
+                 window.register_implementors(implementors);\
+             } else {\
+                 window.pending_implementors = implementors;\
+             }",
+        );
+        v.push_str("})()");
+        cx.shared.fs.write(&mydst, &v)?;
+    }
+    Ok(())
+}
+
+/// Collects the crate's part of the files shared with the other crates of the output directory,
+/// except for its pages, which are added as they're rendered.
+fn crate_info(
+    scx: &SharedContext,
+    krate: &clean::Crate,
+    search_index: String,
+    full_text_index: Option<String>,
+    cache: &Cache,
+) -> CrateInfo {
+    use std::ffi::OsString;
+
+    #[derive(Debug)]
+    struct Hierarchy {
+        elem: OsString,
+        children: FxHashMap<OsString, Hierarchy>,
+        elems: FxHashSet<OsString>,
+    }
+
+    impl Hierarchy {
+        fn new(elem: OsString) -> Hierarchy {
+            Hierarchy { elem, children: FxHashMap::default(), elems: FxHashSet::default() }
+        }
+
+        fn to_json_string(&self) -> String {
+            let mut subs: Vec<&Hierarchy> = self.children.values().collect();
+            subs.sort_unstable_by(|a, b| a.elem.cmp(&b.elem));
+            let mut files = self
+                .elems
+                .iter()
+                .map(|s| format!("\"{}\"", s.to_str().expect("invalid osstring conversion")))
+                .collect::<Vec<_>>();
+            files.sort_unstable_by(|a, b| a.cmp(b));
+            let subs = subs.iter().map(|s| s.to_json_string()).collect::<Vec<_>>().join(",");
+            let dirs =
+                if subs.is_empty() { String::new() } else { format!(",\"dirs\":[{}]", subs) };
+            let files = files.join(",");
+            let files =
+                if files.is_empty() { String::new() } else { format!(",\"files\":[{}]", files) };
+            format!(
+                "{{\"name\":\"{name}\"{dirs}{files}}}",
+                name = self.elem.to_str().expect("invalid osstring conversion"),
+                dirs = dirs,
+                files = files
+            )
+        }
+    }
+
+    let sources = if scx.include_sources {
+        let mut hierarchy = Hierarchy::new(OsString::new());
+        for source in scx.local_sources.iter().filter_map(|p| p.0.strip_prefix(&scx.src_root).ok())
+        {
+            let mut h = &mut hierarchy;
+            let mut elems = source
+                .components()
+                .filter_map(|s| match s {
+                    Component::Normal(s) => Some(s.to_owned()),
+                    _ => None,
+                })
+                .peekable();
+            loop {
+                let cur_elem = elems.next().expect("empty file path");
+                if elems.peek().is_none() {
+                    h.elems.insert(cur_elem);
+                    break;
+                } else {
+                    let e = cur_elem.clone();
+                    h.children.entry(cur_elem.clone()).or_insert_with(|| Hierarchy::new(e));
+                    h = h.children.get_mut(&cur_elem).expect("not found child");
+                }
+            }
+        }
+        Some(hierarchy.to_json_string())
+    } else {
+        None
+    };
+
+    let mut implementors = BTreeMap::new();
     for (&did, imps) in &cache.implementors {
         // Private modules can leak through to this phase of rustdoc, which
         // could contain implementations for otherwise private types. In some
@@ -1106,7 +1173,7 @@ themePicker.onblur = handleThemeButtonsBlur;
             types: Vec<String>,
         }
 
-        let implementors = imps
+        let imps = imps
             .iter()
             .filter_map(|imp| {
                 // If the trait and implementation are in the same crate, then
@@ -1131,45 +1198,28 @@ themePicker.onblur = handleThemeButtonsBlur;
         // Only create a js file if we have impls to add to it. If the trait is
         // documented locally though we always create the file to avoid dead
         // links.
-        if implementors.is_empty() && !cache.paths.contains_key(&did) {
+        if imps.is_empty() && !cache.paths.contains_key(&did) {
             continue;
         }
 
-        let implementors = format!(
-            r#"implementors["{}"] = {};"#,
-            krate.name,
-            serde_json::to_string(&implementors).unwrap()
-        );
-
-        let mut mydst = dst.clone();
+        let mut path = String::new();
         for part in &remote_path[..remote_path.len() - 1] {
-            mydst.push(part);
+            path.push_str(part);
+            path.push('/');
         }
-        cx.shared.ensure_dir(&mydst)?;
-        mydst.push(&format!("{}.{}.js", remote_item_type, remote_path[remote_path.len() - 1]));
-
-        let (mut all_implementors, _) =
-            try_err!(collect(&mydst, &krate.name, "implementors"), &mydst);
-        all_implementors.push(implementors);
-        // Sort the implementors by crate so the file will be generated
-        // identically even with rustdoc running in parallel.
-        all_implementors.sort();
+        path.push_str(&format!("{}.{}.js", remote_item_type, remote_path[remote_path.len() - 1]));
+        implementors.insert(path, serde_json::to_string(&imps).unwrap());
+    }
 
-        let mut v = String::from("(function() {var implementors = {};\n");
-        for implementor in &all_implementors {
-            writeln!(v, "{}", *implementor).unwrap();
-        }
-        v.push_str(
-            "if (window.register_implementors) {\
-                 window.register_implementors(implementors);\
-             } else {\
-                 window.pending_implementors = implementors;\
-             }",
-        );
-        v.push_str("})()");
-        cx.shared.fs.write(&mydst, &v)?;
+    CrateInfo {
+        name: krate.name.clone(),
+        search_index,
+        full_text_index,
+        sources,
+        implementors,
+        pages: Default::default(),
+        links: Default::default(),
     }
-    Ok(())
 }
 
 fn write_minify(
@@ -1695,7 +1745,7 @@ fn print_item(cx: &Context, item: &clean::Item, buf: &mut Buffer, cache: &Cache)
     match item.inner {
         clean::ModuleItem(ref m) => item_module(buf, cx, item, &m.items),
         clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
-            item_function(buf, cx, item, f)
+            item_function(buf, cx, item, f, cache)
         }
         clean::TraitItem(ref t) => item_trait(buf, cx, item, t, cache),
         clean::StructItem(ref s) => item_struct(buf, cx, item, s, cache),
@@ -1770,6 +1820,65 @@ fn document(w: &mut Buffer, cx: &Context, item: &clean::Item) {
     document_full(w, item, cx, "", false);
 }
 
+/// How many of the files a function is called in to show the calls of, on its page.
+const MAX_SCRAPED_EXAMPLES: usize = 3;
+
+/// Renders the calls to the function or method `item` found in other crates of the repository
+/// (see `--with-examples`), with each call highlighted in the item it's in.
+fn render_call_locations(w: &mut Buffer, item: &clean::Item, cache: &Cache) {
+    let call_locations = match cache.call_locations.get(&item.def_id) {
+        Some(call_locations) => call_locations,
+        None => return,
+    };
+
+    // The files may have moved since they were scraped, in which case they're skipped.
+    let mut files = call_locations.iter();
+    let examples: Vec<String> = files
+        .by_ref()
+        .filter_map(|(path, call_data)| render_scraped_example(path, call_data))
+        .take(MAX_SCRAPED_EXAMPLES)
+        .collect();
+    if examples.is_empty() {
+        return;
+    }
+
+    write!(w, "<div class='docblock scraped-examples'><h5>Examples found in repository</h5>");
+    for example in examples {
+        w.write_str(&example);
+    }
+    let other_files: Vec<_> =
+        files.map(|(path, _)| format!("<code>{}</code>", Escape(path))).collect();
+    if !other_files.is_empty() {
+        write!(w, "<p class='more-scraped-examples'>Also used in {}</p>", other_files.join(", "));
+    }
+    write!(w, "</div>");
+}
+
+/// Renders the first item calling the function in the file at `path`, highlighting the calls.
+fn render_scraped_example(path: &str, call_data: &CallData) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let enclosing_item = &call_data.locations.first()?.enclosing_item;
+    let (lo, hi) = enclosing_item.byte_span;
+    let src = contents.get(lo as usize..hi as usize)?;
+    let calls: Vec<_> = call_data
+        .locations
+        .iter()
+        .filter(|location| location.enclosing_item.byte_span == enclosing_item.byte_span)
+        .map(|location| {
+            let (call_lo, call_hi) = location.call_expr.byte_span;
+            ((call_lo - lo) as usize, (call_hi - lo) as usize)
+        })
+        .collect();
+    Some(format!(
+        "<div class='scraped-example'><div class='scraped-example-title'>\
+         <code>{path}</code> (line {line}, in crate <code>{krate}</code>)</div>{code}</div>",
+        path = Escape(path),
+        line = enclosing_item.line_span.0 + 1,
+        krate = Escape(&call_data.crate_name),
+        code = highlight::render_with_marked_ranges(src, None, &calls),
+    ))
+}
+
 /// Render md_text as markdown.
 fn render_markdown(
     w: &mut Buffer,
@@ -2298,7 +2407,13 @@ fn item_static(w: &mut Buffer, cx: &Context, it: &clean::Item, s: &clean::Static
     document(w, cx, it)
 }
 
-fn item_function(w: &mut Buffer, cx: &Context, it: &clean::Item, f: &clean::Function) {
+fn item_function(
+    w: &mut Buffer,
+    cx: &Context,
+    it: &clean::Item,
+    f: &clean::Function,
+    cache: &Cache,
+) {
     let header_len = format!(
         "{}{}{}{}{:#}fn {}{:#}",
         it.visibility.print_with_space(),
@@ -2328,7 +2443,8 @@ fn item_function(w: &mut Buffer, cx: &Context, it: &clean::Item, f: &clean::Func
             .print(),
         spotlight = spotlight_decl(&f.decl),
     );
-    document(w, cx, it)
+    document(w, cx, it);
+    render_call_locations(w, it, cache);
 }
 
 fn render_implementor(
@@ -2517,7 +2633,7 @@ fn item_trait(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Trait,
         write!(w, "{}<span class='loading-content'>Loading content...</span>", extra_content)
     }
 
-    fn trait_item(w: &mut Buffer, cx: &Context, m: &clean::Item, t: &clean::Item) {
+    fn trait_item(w: &mut Buffer, cx: &Context, m: &clean::Item, t: &clean::Item, cache: &Cache) {
         let name = m.name.as_ref().unwrap();
         let item_type = m.type_();
         let id = cx.derive_id(format!("{}.{}", item_type, name));
@@ -2527,6 +2643,7 @@ fn item_trait(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Trait,
         render_stability_since(w, m, t);
         write!(w, "</h3>");
         document(w, cx, m);
+        render_call_locations(w, m, cache);
     }
 
     if !types.is_empty() {
@@ -2537,7 +2654,7 @@ fn item_trait(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Trait,
             "<div class='methods'>",
         );
         for t in &types {
-            trait_item(w, cx, *t, it);
+            trait_item(w, cx, *t, it, cache);
         }
         write_loading_content(w, "</div>");
     }
@@ -2550,7 +2667,7 @@ fn item_trait(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Trait,
             "<div class='methods'>",
         );
         for t in &consts {
-            trait_item(w, cx, *t, it);
+            trait_item(w, cx, *t, it, cache);
         }
         write_loading_content(w, "</div>");
     }
@@ -2564,7 +2681,7 @@ fn item_trait(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Trait,
             "<div class='methods'>",
         );
         for m in &required {
-            trait_item(w, cx, *m, it);
+            trait_item(w, cx, *m, it, cache);
         }
         write_loading_content(w, "</div>");
     }
@@ -2576,7 +2693,7 @@ fn item_trait(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Trait,
             "<div class='methods'>",
         );
         for m in &provided {
-            trait_item(w, cx, *m, it);
+            trait_item(w, cx, *m, it, cache);
         }
         write_loading_content(w, "</div>");
     }
@@ -3689,6 +3806,7 @@ fn render_impl(
                     document_stability(w, cx, item, is_hidden);
                     if show_def_docs {
                         document_full(w, item, cx, "", is_hidden);
+                        render_call_locations(w, item, cache);
                     }
                 }
             } else {
@@ -4405,7 +4523,7 @@ fn sidebar_enum(buf: &mut Buffer, it: &clean::Item, e: &clean::Enum) {
     }
 }
 
-fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
+crate fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
     match *ty {
         ItemType::ExternCrate | ItemType::Import => ("reexports", "Re-exports"),
         ItemType::Module => ("modules", "Modules"),
@@ -561,13 +561,24 @@ function defocusSearchBar() {
     window.initSearch = function(rawSearchIndex) {
         var MAX_LEV_DISTANCE = 3;
         var MAX_RESULTS = 200;
-        var GENERICS_DATA = 1;
+        // The elements of the types in the signatures of functions in the search index.
         var NAME = 0;
+        var KIND_DATA = 1;
+        var GENERICS_DATA = 2;
+        var BOUNDS_DATA = 3;
         var INPUTS_DATA = 0;
         var OUTPUT_DATA = 1;
         var NO_TYPE_FILTER = -1;
         var currentResults, index, searchIndex;
         var ALIASES = {};
+        // The full-text index of each crate, if `--full-text-search` was used.
+        var fullTextIndex = null;
+        // The index in `searchIndex` of each crate, which its full-text index is relative to.
+        var crateOffsets = {};
+        // Must be kept in sync with `STOP_WORDS` in `html/render/cache.rs`.
+        var STOP_WORDS = ["an", "and", "are", "as", "at", "be", "by", "for", "from", "if", "in",
+                          "into", "is", "it", "its", "of", "on", "or", "that", "the", "this",
+                          "to", "with"];
         var params = getQueryStringParams();
 
         // Populate search bar with query string search term when provided,
@@ -578,6 +589,26 @@ function defocusSearchBar() {
             search_input.value = params.search || "";
         }
 
+        // Splits `val` at the commas which aren't within brackets.
+        function splitTopLevel(val) {
+            var parts = [];
+            var depth = 0;
+            var start = 0;
+            for (var i = 0; i < val.length; ++i) {
+                var c = val.charAt(i);
+                if (c === "<" || c === "(" || c === "[") {
+                    depth += 1;
+                } else if (c === ">" || c === ")" || c === "]") {
+                    depth -= 1;
+                } else if (c === "," && depth === 0) {
+                    parts.push(val.substring(start, i).trim());
+                    start = i + 1;
+                }
+            }
+            parts.push(val.substring(start).trim());
+            return parts.filter(function(part) { return part.length > 0; });
+        }
+
         /**
          * Executes the query and builds an index of results
          * @param  {[Object]} query      [The user query]
@@ -742,121 +773,248 @@ function defocusSearchBar() {
                 return transformResults(results);
             }
 
-            function extractGenerics(val) {
-                val = val.toLowerCase();
-                if (val.indexOf("<") !== -1) {
-                    var values = val.substring(val.indexOf("<") + 1, val.lastIndexOf(">"));
-                    return {
-                        name: val.substring(0, val.indexOf("<")),
-                        generics: values.split(/\s*,\s*/),
-                    };
+            // Parses a type of the query, like `Vec<T>`, `&str`, `[u8]` or `impl Read`, into its
+            // name and generic arguments. In generic arguments, and everywhere if
+            // `allowVariables` is true, single letters are type variables, which stand for any
+            // type as long as it's the same one everywhere.
+            function extractGenerics(val, allowVariables) {
+                val = val.trim().toLowerCase();
+                var name;
+                var generics = [];
+                var inner;
+                if (val.charAt(0) === "&") {
+                    name = "reference";
+                    generics = [extractGenerics(val.substring(1).replace(/^mut\s+/, ""), true)];
+                } else if (val.charAt(0) === "[" && val.charAt(val.length - 1) === "]") {
+                    inner = val.substring(1, val.length - 1);
+                    var semicolon = inner.lastIndexOf(";");
+                    name = semicolon === -1 ? "slice" : "array";
+                    if (semicolon !== -1) {
+                        inner = inner.substring(0, semicolon);
+                    }
+                    generics = [extractGenerics(inner, true)];
+                } else if (val.charAt(0) === "(" && val.charAt(val.length - 1) === ")") {
+                    inner = splitTopLevel(val.substring(1, val.length - 1));
+                    name = inner.length === 0 ? "unit" : "tuple";
+                    generics = inner.map(function(elem) { return extractGenerics(elem, true); });
+                } else {
+                    // `impl Trait` and `dyn Trait` are searched for like `Trait`.
+                    val = val.replace(/^(impl|dyn)\s+/, "");
+                    var start = val.indexOf("<");
+                    if (start !== -1) {
+                        inner = val.substring(start + 1, val.lastIndexOf(">"));
+                        generics = splitTopLevel(inner).map(function(arg) {
+                            // `Iterator<Item = T>` is searched for like `Iterator<T>`.
+                            return extractGenerics(arg.replace(/^\w+\s*=\s*/, ""), true);
+                        });
+                        val = val.substring(0, start);
+                    }
+                    name = val.split("::").pop().trim();
                 }
                 return {
-                    name: val,
-                    generics: [],
+                    name: name,
+                    generics: generics,
+                    isVariable: allowVariables === true && /^[a-z]$/.test(name),
                 };
             }
 
-            function getObjectFromId(id) {
-                if (typeof id === "number") {
-                    return searchIndex[id];
+            // Whether the type `obj` of a function's signature is one of its generic parameters.
+            function isParam(obj) {
+                return obj.length > BOUNDS_DATA;
+            }
+
+            // Returns a key identifying the type `obj` of a function's signature.
+            function typeKey(obj) {
+                if (isParam(obj)) {
+                    return "<" + obj[NAME] + ">";
+                }
+                var generics = obj.length > GENERICS_DATA ? obj[GENERICS_DATA] : [];
+                if (generics.length === 0) {
+                    return obj[NAME];
+                }
+                return obj[NAME] + "<" + generics.map(typeKey).join(",") + ">";
+            }
+
+            // Returns a key identifying the type `val` of the query.
+            function queryKey(val) {
+                if (val.isVariable === true) {
+                    return "?" + val.name;
+                }
+                if (val.generics.length === 0) {
+                    return val.name;
+                }
+                return val.name + "<" + val.generics.map(queryKey).join(",") + ">";
+            }
+
+            // Returns a copy of `bindings` where `key` is bound to `value`, or null if `key` is
+            // already bound to another value.
+            function bind(bindings, key, value) {
+                if (hasOwnProperty(bindings, key)) {
+                    return bindings[key] === value ? bindings : null;
+                }
+                var newBindings = {};
+                for (var k in bindings) {
+                    if (hasOwnProperty(bindings, k)) {
+                        newBindings[k] = bindings[k];
+                    }
                 }
-                return {'name': id};
+                newBindings[key] = value;
+                return newBindings;
+            }
+
+            // Unifies the type `obj` of a function's signature with the type `val` of the query.
+            // A generic parameter of the function stands for the query types naming one of the
+            // traits it's bound by, and a type variable of the query stands for any type, but
+            // each of them has to stand for the same type everywhere, which `bindings` keeps
+            // track of. Returns the new bindings, or null if the types don't unify.
+            function unifyType(obj, val, bindings) {
+                if (val.isVariable === true) {
+                    return bind(bindings, queryKey(val), typeKey(obj));
+                }
+                if (isParam(obj)) {
+                    var bounds = obj[BOUNDS_DATA];
+                    for (var i = 0; i < bounds.length; ++i) {
+                        var newBindings = unifyType(bounds[i], val, bindings);
+                        if (newBindings !== null) {
+                            // `impl Trait` arguments are anonymous.
+                            if (obj[NAME] === "") {
+                                return newBindings;
+                            }
+                            return bind(newBindings, typeKey(obj), queryKey(val));
+                        }
+                    }
+                    return null;
+                }
+                var generics = obj.length > GENERICS_DATA ? obj[GENERICS_DATA] : [];
+                if (obj[NAME] !== val.name) {
+                    // References are searched for like the type they refer to.
+                    if (obj[NAME] === "reference" && generics.length === 1) {
+                        return unifyType(generics[0], val, bindings);
+                    }
+                    return null;
+                }
+                return unifyTypes(generics, val.generics, bindings, unifyType, function(b) {
+                    return b;
+                });
+            }
+
+            // Like `unifyType`, but the query type may also be one of the generic arguments of
+            // `obj`, like `String` in `io::Result<String>`.
+            function unifyTypeOrArgument(obj, val, bindings) {
+                var newBindings = unifyType(obj, val, bindings);
+                if (newBindings !== null || val.isVariable === true || isParam(obj) ||
+                        obj.length <= GENERICS_DATA) {
+                    return newBindings;
+                }
+                var generics = obj[GENERICS_DATA];
+                for (var i = 0; i < generics.length; ++i) {
+                    newBindings = unifyType(generics[i], val, bindings);
+                    if (newBindings !== null) {
+                        return newBindings;
+                    }
+                }
+                return null;
+            }
+
+            // Unifies each of the query types `vals` with a different one of the function's types
+            // `objs` using `unify`, in any order, and passes the resulting bindings to `next`,
+            // trying the other ways to unify them until it returns non-null bindings, which are
+            // returned. Returns null if there are none.
+            function unifyTypes(objs, vals, bindings, unify, next) {
+                if (vals.length === 0) {
+                    return next(bindings);
+                }
+                for (var i = 0; i < objs.length; ++i) {
+                    var newBindings = unify(objs[i], vals[0], bindings);
+                    if (newBindings !== null) {
+                        newBindings = unifyTypes(objs.slice(0, i).concat(objs.slice(i + 1)),
+                                                 vals.slice(1), newBindings, unify, next);
+                        if (newBindings !== null) {
+                            return newBindings;
+                        }
+                    }
+                }
+                return null;
+            }
+
+            // Whether the function type `type` matches the query `inputs -> output`, where
+            // `output` may be `*`.
+            function unifySignature(type, inputs, output) {
+                var fnOutput = type.length > OUTPUT_DATA ? type[OUTPUT_DATA] : null;
+                return unifyTypes(type[INPUTS_DATA], inputs, {}, unifyTypeOrArgument,
+                                  function(bindings) {
+                    if (output.name === "*") {
+                        return bindings;
+                    }
+                    if (fnOutput === null) {
+                        return null;
+                    }
+                    return unifyTypeOrArgument(fnOutput, output, bindings);
+                }) !== null;
             }
 
             function checkGenerics(obj, val) {
                 // The names match, but we need to be sure that all generics kinda
                 // match as well.
-                var lev_distance = MAX_LEV_DISTANCE + 1;
-                if (val.generics.length > 0) {
-                    if (obj.length > GENERICS_DATA &&
-                          obj[GENERICS_DATA].length >= val.generics.length) {
-                        var elems = obj[GENERICS_DATA].slice(0);
-                        var total = 0;
-                        var done = 0;
-                        // We need to find the type that matches the most to remove it in order
-                        // to move forward.
-                        var vlength = val.generics.length;
-                        for (var y = 0; y < vlength; ++y) {
-                            var lev = { pos: -1, lev: MAX_LEV_DISTANCE + 1};
-                            var elength = elems.length;
-                            var firstGeneric = getObjectFromId(val.generics[y]).name;
-                            for (var x = 0; x < elength; ++x) {
-                                var tmp_lev = levenshtein(getObjectFromId(elems[x]).name,
-                                                          firstGeneric);
-                                if (tmp_lev < lev.lev) {
-                                    lev.lev = tmp_lev;
-                                    lev.pos = x;
-                                }
-                            }
-                            if (lev.pos !== -1) {
-                                elems.splice(lev.pos, 1);
-                                lev_distance = Math.min(lev.lev, lev_distance);
-                                total += lev.lev;
-                                done += 1;
-                            } else {
-                                return MAX_LEV_DISTANCE + 1;
-                            }
+                var generics = obj.length > GENERICS_DATA ? obj[GENERICS_DATA] : [];
+                if (val.generics.length === 0 || generics.length < val.generics.length) {
+                    return MAX_LEV_DISTANCE + 1;
+                }
+                var elems = generics.slice(0);
+                var total = 0;
+                // We need to find the type that matches the most to remove it in order
+                // to move forward.
+                var vlength = val.generics.length;
+                for (var y = 0; y < vlength; ++y) {
+                    var lev = { pos: -1, lev: MAX_LEV_DISTANCE + 1};
+                    var elength = elems.length;
+                    for (var x = 0; x < elength; ++x) {
+                        var tmp_lev = val.generics[y].isVariable === true ? 0 :
+                            checkType(elems[x], val.generics[y], false);
+                        if (tmp_lev < lev.lev) {
+                            lev.lev = tmp_lev;
+                            lev.pos = x;
                         }
-                        return Math.ceil(total / done);
                     }
+                    if (lev.pos === -1) {
+                        return MAX_LEV_DISTANCE + 1;
+                    }
+                    elems.splice(lev.pos, 1);
+                    total += lev.lev;
                 }
-                return MAX_LEV_DISTANCE + 1;
+                return Math.ceil(total / vlength);
             }
 
             // Check for type name and type generics (if any).
             function checkType(obj, val, literalSearch) {
+                if (literalSearch === true) {
+                    return unifyTypeOrArgument(obj, val, {}) !== null;
+                }
                 var lev_distance = MAX_LEV_DISTANCE + 1;
                 var x;
-                if (obj[NAME] === val.name) {
-                    if (literalSearch === true) {
-                        if (val.generics && val.generics.length !== 0) {
-                            if (obj.length > GENERICS_DATA &&
-                                  obj[GENERICS_DATA].length >= val.generics.length) {
-                                var elems = obj[GENERICS_DATA].slice(0);
-                                var allFound = true;
-
-                                for (var y = 0; allFound === true && y < val.generics.length; ++y) {
-                                    allFound = false;
-                                    var firstGeneric = getObjectFromId(val.generics[y]).name;
-                                    for (x = 0; allFound === false && x < elems.length; ++x) {
-                                        allFound = getObjectFromId(elems[x]).name === firstGeneric;
-                                    }
-                                    if (allFound === true) {
-                                        elems.splice(x - 1, 1);
-                                    }
-                                }
-                                if (allFound === true) {
-                                    return true;
-                                }
-                            } else {
-                                return false;
-                            }
-                        }
-                        return true;
+                // Generic parameters are found through the traits they're bound by.
+                if (isParam(obj)) {
+                    var bounds = obj[BOUNDS_DATA];
+                    for (x = 0; x < bounds.length; ++x) {
+                        lev_distance = Math.min(checkType(bounds[x], val, false), lev_distance);
                     }
-                    // If the type has generics but don't match, then it won't return at this point.
-                    // Otherwise, `checkGenerics` will return 0 and it'll return.
-                    if (obj.length > GENERICS_DATA && obj[GENERICS_DATA].length !== 0) {
-                        var tmp_lev = checkGenerics(obj, val);
-                        if (tmp_lev <= MAX_LEV_DISTANCE) {
-                            return tmp_lev;
-                        }
-                    } else {
+                    return lev_distance;
+                }
+                var generics = obj.length > GENERICS_DATA ? obj[GENERICS_DATA] : [];
+                // References are searched for like the type they refer to.
+                if (obj[NAME] === "reference" && val.name !== "reference" &&
+                        generics.length === 1) {
+                    return checkType(generics[0], val, false);
+                }
+                if (obj[NAME] === val.name) {
+                    if (val.generics.length === 0) {
                         return 0;
                     }
-                }
-                // Names didn't match so let's check if one of the generic types could.
-                if (literalSearch === true) {
-                     if (obj.length > GENERICS_DATA && obj[GENERICS_DATA].length > 0) {
-                        var length = obj[GENERICS_DATA].length;
-                        for (x = 0; x < length; ++x) {
-                            if (obj[GENERICS_DATA][x] === val.name) {
-                                return true;
-                            }
-                        }
+                    // If the generics don't match, then it won't return at this point.
+                    var tmp_lev = checkGenerics(obj, val);
+                    if (tmp_lev <= MAX_LEV_DISTANCE) {
+                        return tmp_lev;
                     }
-                    return false;
                 }
                 lev_distance = Math.min(levenshtein(obj[NAME], val.name), lev_distance);
                 if (lev_distance <= MAX_LEV_DISTANCE) {
@@ -864,11 +1022,10 @@ function defocusSearchBar() {
                     // a levenshtein distance value that isn't *this* good so it goes
                     // into the search results but not too high.
                     lev_distance = Math.ceil((checkGenerics(obj, val) + lev_distance) / 2);
-                } else if (obj.length > GENERICS_DATA && obj[GENERICS_DATA].length > 0) {
+                } else if (generics.length > 0) {
                     // We can check if the type we're looking for is inside the generics!
-                    var olength = obj[GENERICS_DATA].length;
-                    for (x = 0; x < olength; ++x) {
-                        lev_distance = Math.min(levenshtein(obj[GENERICS_DATA][x], val.name),
+                    for (x = 0; x < generics.length; ++x) {
+                        lev_distance = Math.min(levenshtein(generics[x][NAME], val.name),
                                                 lev_distance);
                     }
                 }
@@ -877,6 +1034,25 @@ function defocusSearchBar() {
                 return lev_distance + 1;
             }
 
+            // Whether the type `obj` of a function's signature, or one of its generic arguments,
+            // passes the filter. Generic parameters are filtered like the traits they're bound
+            // by.
+            function signatureTypePassesFilter(filter, obj) {
+                if (filter <= NO_TYPE_FILTER || typePassesFilter(filter, obj[KIND_DATA])) {
+                    return true;
+                }
+                var types = isParam(obj) ? obj[BOUNDS_DATA] :
+                    (obj.length > GENERICS_DATA ? obj[GENERICS_DATA] : []);
+                for (var i = 0; i < types.length; ++i) {
+                    if (typePassesFilter(filter, types[i][KIND_DATA]) === true) {
+                        return true;
+                    }
+                }
+                // References are filtered like the type they refer to.
+                return obj[NAME] === "reference" && types.length === 1 &&
+                    signatureTypePassesFilter(filter, types[0]);
+            }
+
             function findArg(obj, val, literalSearch, typeFilter) {
                 var lev_distance = MAX_LEV_DISTANCE + 1;
 
@@ -884,7 +1060,7 @@ function defocusSearchBar() {
                     var length = obj.type[INPUTS_DATA].length;
                     for (var i = 0; i < length; i++) {
                         var tmp = obj.type[INPUTS_DATA][i];
-                        if (typePassesFilter(typeFilter, tmp[1]) === false) {
+                        if (signatureTypePassesFilter(typeFilter, tmp) === false) {
                             continue;
                         }
                         tmp = checkType(tmp, val, literalSearch);
@@ -904,32 +1080,13 @@ function defocusSearchBar() {
             }
 
             function checkReturned(obj, val, literalSearch, typeFilter) {
-                var lev_distance = MAX_LEV_DISTANCE + 1;
-
                 if (obj && obj.type && obj.type.length > OUTPUT_DATA) {
                     var ret = obj.type[OUTPUT_DATA];
-                    if (typeof ret[0] === "string") {
-                        ret = [ret];
-                    }
-                    for (var x = 0; x < ret.length; ++x) {
-                        var tmp = ret[x];
-                        if (typePassesFilter(typeFilter, tmp[1]) === false) {
-                            continue;
-                        }
-                        tmp = checkType(tmp, val, literalSearch);
-                        if (literalSearch === true) {
-                            if (tmp === true) {
-                                return true;
-                            }
-                            continue;
-                        }
-                        lev_distance = Math.min(tmp, lev_distance);
-                        if (lev_distance === 0) {
-                            return 0;
-                        }
+                    if (signatureTypePassesFilter(typeFilter, ret) === true) {
+                        return checkType(ret, val, literalSearch);
                     }
                 }
-                return literalSearch === true ? false : lev_distance;
+                return literalSearch === true ? false : MAX_LEV_DISTANCE + 1;
             }
 
             function checkPath(contains, lastElem, ty) {
@@ -1119,12 +1276,11 @@ function defocusSearchBar() {
                 var trimmer = function(s) { return s.trim(); };
                 var parts = val.split("->").map(trimmer);
                 var input = parts[0];
-                // sort inputs so that order does not matter
-                var inputs = input.split(",").map(trimmer).sort();
-                for (i = 0; i < inputs.length; ++i) {
-                    inputs[i] = extractGenerics(inputs[i]);
-                }
-                var output = extractGenerics(parts[1]);
+                // Single letters are type variables, as in `Vec<T> -> Option<T>`.
+                var inputs = splitTopLevel(input).map(function(input) {
+                    return extractGenerics(input, true);
+                });
+                var output = extractGenerics(parts[1], true);
 
                 for (i = 0; i < nSearchWords; ++i) {
                     if (filterCrates !== undefined && searchIndex[i].crate !== filterCrates) {
@@ -1137,19 +1293,18 @@ function defocusSearchBar() {
                     }
                     fullId = generateId(ty);
 
-                    returned = checkReturned(ty, output, true, NO_TYPE_FILTER);
-                    if (output.name === "*" || returned === true) {
+                    // Any function returns a type variable, which only matters for the
+                    // inputs.
+                    returned = output.isVariable === false &&
+                        checkReturned(ty, output, true, NO_TYPE_FILTER);
+                    if (output.name === "*" || output.isVariable === true || returned === true) {
                         in_args = false;
                         var is_module = false;
 
                         if (input === "*") {
                             is_module = true;
-                        } else {
-                            var allFound = true;
-                            for (var it = 0; allFound === true && it < inputs.length; it++) {
-                                allFound = checkType(type, inputs[it], true);
-                            }
-                            in_args = allFound;
+                        } else if (inputs.length > 0) {
+                            in_args = unifySignature(type, inputs, output);
                         }
                         if (in_args === true) {
                             results_in_args[fullId] = {
@@ -1286,10 +1441,25 @@ function defocusSearchBar() {
                 }
             }
 
+            var results_full_text = {};
+            if (query.query.indexOf("->") === -1) {
+                onEach(findInDocumentation(query.query, filterCrates), function(id) {
+                    if (typePassesFilter(typeFilter, searchIndex[id].ty)) {
+                        results_full_text[id] = {
+                            id: id,
+                            index: -1,
+                            lev: 0,
+                            dontValidate: true,
+                        };
+                    }
+                });
+            }
+
             var ret = {
                 "in_args": sortResults(results_in_args, true),
                 "returned": sortResults(results_returned, true),
                 "others": sortResults(results),
+                "full_text": sortResults(results_full_text),
             };
             handleAliases(ret, query, filterCrates);
             return ret;
@@ -1329,6 +1499,101 @@ function defocusSearchBar() {
             return true;
         }
 
+        /**
+         * Finds the items whose documentation contains all the words of the query, using the
+         * full-text index. The last word may not be typed in full yet, so it matches the words
+         * starting with it.
+         *
+         * @param  {[string]} query        [The user query]
+         * @param  {[string]} filterCrates [Crate to search in if defined]
+         * @return {[Number]}              [The indexes of the items in `searchIndex`]
+         */
+        function findInDocumentation(query, filterCrates) {
+            var ids = [];
+            if (fullTextIndex === null) {
+                return ids;
+            }
+            var words = query.toLowerCase().split(/[^0-9a-z_\u0080-\uffff]+/);
+            var last = words.length - 1;
+            while (last >= 0 && words[last] === "") {
+                last -= 1;
+            }
+            words = words.filter(function(word, i) {
+                // Like when building the index, short and common words are ignored, unless
+                // they may be the start of a longer word.
+                return i === last ||
+                    (word.length > 1 && !/^[0-9]+$/.test(word) && STOP_WORDS.indexOf(word) === -1);
+            });
+            if (last < 0) {
+                return ids;
+            }
+
+            for (var crate in fullTextIndex) {
+                if (!hasOwnProperty(fullTextIndex, crate) ||
+                    !hasOwnProperty(crateOffsets, crate) ||
+                    (filterCrates !== undefined && crate !== filterCrates)) {
+                    continue;
+                }
+                var found = null;
+                for (var i = 0; i < words.length; ++i) {
+                    var items = findWordInCrate(fullTextIndex[crate], words[i],
+                                                i === words.length - 1);
+                    if (found !== null) {
+                        for (var item in found) {
+                            if (hasOwnProperty(found, item) && !hasOwnProperty(items, item)) {
+                                delete found[item];
+                            }
+                        }
+                    } else {
+                        found = items;
+                    }
+                }
+                for (var id in found) {
+                    if (hasOwnProperty(found, id)) {
+                        ids.push(crateOffsets[crate] + parseInt(id, 10));
+                    }
+                }
+            }
+            return ids;
+        }
+
+        /**
+         * Finds the items of a crate whose documentation contains a word.
+         *
+         * @param  {[Object]}  crateIndex [The full-text index of the crate]
+         * @param  {[string]}  word       [The word to find]
+         * @param  {[boolean]} isPrefix   [Whether to find the words starting with `word` too]
+         * @return {[Object]}             [The set of the indexes of the items in the crate]
+         */
+        function findWordInCrate(crateIndex, word, isPrefix) {
+            var terms = crateIndex.t;
+            var items = {};
+            // Binary search for the first term not before `word`, as the terms are sorted.
+            var lo = 0;
+            var hi = terms.length;
+            while (lo < hi) {
+                var mid = (lo + hi) >> 1;
+                if (terms[mid] < word) {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            for (var i = lo; i < terms.length; ++i) {
+                if (isPrefix ? !terms[i].startsWith(word) : terms[i] !== word) {
+                    break;
+                }
+                // The items are stored as the differences between consecutive indexes.
+                var deltas = crateIndex.i[i];
+                var item = 0;
+                for (var j = 0; j < deltas.length; ++j) {
+                    item += deltas[j];
+                    items[item] = true;
+                }
+            }
+            return items;
+        }
+
         function getQuery(raw) {
             var matches, type, query;
             query = raw;
@@ -1394,7 +1659,7 @@ function defocusSearchBar() {
             search_input.onkeydown = function(e) {
                 // "actives" references the currently highlighted item in each search tab.
                 // Each array in "actives" represents a tab.
-                var actives = [[], [], []];
+                var actives = [[], [], [], []];
                 // "current" is used to know which tab we're looking into.
                 var current = 0;
                 onEachLazy(document.getElementById("results").childNodes, function(e) {
@@ -1433,10 +1698,11 @@ function defocusSearchBar() {
                             actives[currentTab][0].getElementsByTagName("a")[0].href;
                     }
                 } else if (e.which === 9) { // tab
+                    var lastTab = document.getElementById("titles").childNodes.length - 1;
                     if (e.shiftKey) {
-                        printTab(currentTab > 0 ? currentTab - 1 : 2);
+                        printTab(currentTab > 0 ? currentTab - 1 : lastTab);
                     } else {
-                        printTab(currentTab > 1 ? 0 : currentTab + 1);
+                        printTab(currentTab < lastTab ? currentTab + 1 : 0);
                     }
                     e.preventDefault();
                 } else if (e.which === 16) { // shift
@@ -1601,15 +1867,23 @@ function defocusSearchBar() {
             var ret_others = addTab(results.others, query);
             var ret_in_args = addTab(results.in_args, query, false);
             var ret_returned = addTab(results.returned, query, false);
+            // The documentation can only be searched if the full-text index was generated.
+            var hasFullText = fullTextIndex !== null;
+            var ret_full_text = addTab(results.full_text, query, false);
+            if (!hasFullText && currentTab === 3) {
+                currentTab = 0;
+            }
 
             var output = "<h1>Results for " + escape(query.query) +
                 (query.type ? " (type: " + escape(query.type) + ")" : "") + "</h1>" +
-                "<div id=\"titles\">" +
+                "<div id=\"titles\"" + (hasFullText ? " class=\"full-text\"" : "") + ">" +
                 makeTabHeader(0, "In Names", ret_others[1]) +
                 makeTabHeader(1, "In Parameters", ret_in_args[1]) +
                 makeTabHeader(2, "In Return Types", ret_returned[1]) +
+                (hasFullText ? makeTabHeader(3, "In Documentation", ret_full_text[1]) : "") +
                 "</div><div id=\"results\">" +
-                ret_others[0] + ret_in_args[0] + ret_returned[0] + "</div>";
+                ret_others[0] + ret_in_args[0] + ret_returned[0] +
+                (hasFullText ? ret_full_text[0] : "") + "</div>";
 
             search.innerHTML = output;
             showSearchResults(search);
@@ -1627,6 +1901,9 @@ function defocusSearchBar() {
             elems[0].onclick = function() { printTab(0); };
             elems[1].onclick = function() { printTab(1); };
             elems[2].onclick = function() { printTab(2); };
+            if (hasFullText) {
+                elems[3].onclick = function() { printTab(3); };
+            }
             printTab(currentTab);
         }
 
@@ -1671,11 +1948,13 @@ function defocusSearchBar() {
                 return ret;
             }
 
-            var queries = query.raw.split(",");
+            // Type signatures can't be split, their inputs being separated by commas too.
+            var queries = query.raw.indexOf("->") === -1 ? splitTopLevel(query.raw) : [query.raw];
             var results = {
                 "in_args": [],
                 "returned": [],
                 "others": [],
+                "full_text": [],
             };
 
             for (var i = 0; i < queries.length; ++i) {
@@ -1686,6 +1965,7 @@ function defocusSearchBar() {
                     results.in_args.push(tmp.in_args);
                     results.returned.push(tmp.returned);
                     results.others.push(tmp.others);
+                    results.full_text.push(tmp.full_text);
                 }
             }
             if (queries.length > 1) {
@@ -1693,12 +1973,14 @@ function defocusSearchBar() {
                     "in_args": mergeArrays(results.in_args),
                     "returned": mergeArrays(results.returned),
                     "others": mergeArrays(results.others),
+                    "full_text": mergeArrays(results.full_text),
                 };
             }
             return {
                 "in_args": results.in_args[0],
                 "returned": results.returned[0],
                 "others": results.others[0],
+                "full_text": results.full_text[0],
             };
         }
 
@@ -1757,6 +2039,7 @@ function defocusSearchBar() {
 
                 var crateSize = 0;
 
+                crateOffsets[crate] = currentIndex;
                 searchWords.push(crate);
                 searchIndex.push({
                     crate: crate,
@@ -1924,6 +2207,14 @@ function defocusSearchBar() {
         index = buildIndex(rawSearchIndex);
         startSearch();
 
+        window.initFullTextSearch = function(rawFullTextIndex) {
+            fullTextIndex = rawFullTextIndex;
+            // Show the results in the documentation if a search was already made without them.
+            if (search_input.value !== "" && !hasClass(getSearchElement(), "hidden")) {
+                search(undefined, true);
+            }
+        };
+
         // Draw a convenient sidebar of known crates if we have a listing
         if (rootPath === "../" || rootPath === "./") {
             var sidebar = document.getElementsByClassName("sidebar-elems")[0];
@@ -2653,7 +2944,7 @@ function defocusSearchBar() {
 
     // In the search display, allows to switch between tabs.
     function printTab(nb) {
-        if (nb === 0 || nb === 1 || nb === 2) {
+        if (nb === 0 || nb === 1 || nb === 2 || nb === 3) {
             currentTab = nb;
         }
         var nb_copy = nb;
@@ -2819,6 +3110,9 @@ function defocusSearchBar() {
              and <code>const</code>.",
             "Search functions by type signature (e.g., <code>vec -&gt; usize</code> or \
              <code>* -&gt; vec</code>)",
+            "Single letters in type signatures stand for any type, the same one everywhere \
+             (e.g., <code>Vec&lt;T&gt; -&gt; Option&lt;T&gt;</code>), and generic \
+             parameters are found by their bounds (e.g., <code>impl Read -&gt; String</code>)",
             "Search multiple things at once by splitting your query with comma (e.g., \
              <code>str,u8</code> or <code>String,struct:Vec,test</code>)",
             "You can look for items with an exact name by putting double quotes around \
@@ -65,7 +65,9 @@ use rustc_session::{early_error, early_warn};
 #[macro_use]
 mod externalfiles;
 
+mod api_diff;
 mod clean;
+mod commonmark;
 mod config;
 mod core;
 mod docfs;
@@ -79,6 +81,7 @@ pub mod html;
 mod json;
 mod markdown;
 mod passes;
+mod scrape_examples;
 mod theme;
 mod visit_ast;
 mod visit_lib;
@@ -362,6 +365,13 @@ fn opts() -> Vec<RustcOptGroup> {
                 "disables generating the crate selector on the search box",
             )
         }),
+        unstable("full-text-search", |o| {
+            o.optflag(
+                "",
+                "full-text-search",
+                "generates an index to search the text of the documentation, not only item names",
+            )
+        }),
         unstable("persist-doctests", |o| {
             o.optopt(
                 "",
@@ -370,6 +380,13 @@ fn opts() -> Vec<RustcOptGroup> {
                 "PATH",
             )
         }),
+        unstable("batch-doctests", |o| {
+            o.optflag(
+                "",
+                "batch-doctests",
+                "compile the doctests of each edition which can be into one binary",
+            )
+        }),
         unstable("show-coverage", |o| {
             o.optflag(
                 "",
@@ -407,6 +424,72 @@ fn opts() -> Vec<RustcOptGroup> {
                 "specified the rustc-like binary to use as the test builder",
             )
         }),
+        unstable("scrape-examples-output-path", |o| {
+            o.optopt(
+                "",
+                "scrape-examples-output-path",
+                "scrape the calls this crate makes to the target crates into a file, \
+                 instead of documenting it",
+                "PATH",
+            )
+        }),
+        unstable("scrape-examples-target-crate", |o| {
+            o.optmulti(
+                "",
+                "scrape-examples-target-crate",
+                "crate whose functions and methods to scrape the calls to",
+                "CRATE",
+            )
+        }),
+        unstable("with-examples", |o| {
+            o.optmulti(
+                "",
+                "with-examples",
+                "path to a file of calls scraped from another crate, to show as examples",
+                "PATH",
+            )
+        }),
+        unstable("merge", |o| {
+            o.optopt(
+                "",
+                "merge",
+                "how to write the files shared with the other crates of the output directory: \
+                 `none`, `shared` (the default) or `finalize`",
+                "none|shared|finalize",
+            )
+        }),
+        unstable("parts-out-dir", |o| {
+            o.optopt(
+                "",
+                "parts-out-dir",
+                "directory to write the crate's part of the shared files to, with --merge=none",
+                "PATH",
+            )
+        }),
+        unstable("include-parts-dir", |o| {
+            o.optmulti(
+                "",
+                "include-parts-dir",
+                "directory of the part of the shared files of another crate of the bundle",
+                "PATH",
+            )
+        }),
+        unstable("api-snapshot", |o| {
+            o.optopt(
+                "",
+                "api-snapshot",
+                "save a snapshot of the crate's public API to compare later versions to",
+                "PATH",
+            )
+        }),
+        unstable("api-diff", |o| {
+            o.optopt(
+                "",
+                "api-diff",
+                "compare the crate's public API to a snapshot, and report the semver changes",
+                "PATH",
+            )
+        }),
     ]
 }
 
@@ -498,6 +581,7 @@ fn main_options(options: config::Options) -> MainResult {
     // but we can't crates the Handler ahead of time because it's not Send
     let diag_opts = (options.error_format, options.edition, options.debugging_opts.clone());
     let show_coverage = options.show_coverage;
+    let api_diff_options = options.api_diff_options.clone();
 
     // First, parse the crate and extract all relevant information.
     info!("starting to run rustc");
@@ -509,7 +593,11 @@ fn main_options(options: config::Options) -> MainResult {
     let crate_name = options.crate_name.clone();
     let crate_version = options.crate_version.clone();
     let output_format = options.output_format;
-    let (mut krate, renderinfo, renderopts, sess) = core::run_core(options);
+    let (mut krate, renderinfo, renderopts, sess) = match core::run_core(options) {
+        Some(output) => output,
+        // The crate was scraped for examples rather than documented.
+        None => return Ok(()),
+    };
 
     info!("finished with rustc");
 
@@ -519,6 +607,12 @@ fn main_options(options: config::Options) -> MainResult {
 
     krate.version = crate_version;
 
+    if api_diff_options.is_enabled() {
+        // The API is compared instead of being documented.
+        let res = sess.time("api_diff", || api_diff::run(&krate, api_diff_options, output_format));
+        return wrap_return(&diag, res);
+    }
+
     let out = Output { krate, renderinfo, renderopts };
 
     if show_coverage {
@@ -538,5 +632,10 @@ fn main_options(options: config::Options) -> MainResult {
         Some(config::OutputFormat::Json) => sess.time("render_json", || {
             run_renderer::<json::JsonRenderer>(krate, renderopts, renderinfo, &diag, edition)
         }),
+        Some(config::OutputFormat::Markdown) => sess.time("render_markdown", || {
+            run_renderer::<commonmark::CommonMarkRenderer>(
+                krate, renderopts, renderinfo, &diag, edition,
+            )
+        }),
     }
 }
@@ -7,7 +7,7 @@ use rustc_span::edition::Edition;
 use rustc_span::source_map::DUMMY_SP;
 
 use crate::config::{Options, RenderOptions};
-use crate::doctest::{Collector, TestOptions};
+use crate::doctest::{self, Collector, TestOptions};
 use crate::html::escape::Escape;
 use crate::html::markdown;
 use crate::html::markdown::{find_testable_code, ErrorCodes, IdMap, Markdown, MarkdownWithToc};
@@ -115,9 +115,13 @@ pub fn render<P: AsRef<Path>>(
 }
 
 /// Runs any tests/code examples in the markdown file `input`.
-pub fn test(mut options: Options) -> Result<(), String> {
+pub fn test(options: Options) -> Result<(), String> {
     let input_str = read_to_string(&options.input)
         .map_err(|err| format!("{}: {}", options.input.display(), err))?;
+    let test_opts = match doctest::parse_test_args(&options.test_args, options.display_warnings) {
+        Some(test_opts) => test_opts,
+        None => return Ok(()),
+    };
     let mut opts = TestOptions::default();
     opts.no_crate_inject = true;
     opts.display_warnings = options.display_warnings;
@@ -129,17 +133,17 @@ pub fn test(mut options: Options) -> Result<(), String> {
         None,
         Some(options.input),
         options.enable_per_target_ignores,
+        test_opts.format == testing::OutputFormat::Json,
     );
     collector.set_position(DUMMY_SP);
     let codes = ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build());
 
     find_testable_code(&input_str, &mut collector, codes, options.enable_per_target_ignores, None);
 
-    options.test_args.insert(0, "rustdoctest".to_string());
-    testing::test_main(
-        &options.test_args,
+    testing::test_main_with_doctests(
+        &test_opts,
         collector.tests,
-        Some(testing::Options::new().display_output(options.display_warnings)),
+        collector.doctests.unwrap_or_default(),
     );
     Ok(())
 }
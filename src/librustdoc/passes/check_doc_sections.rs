@@ -0,0 +1,265 @@
+//! Checks the `# Safety`, `# Errors` and `# Panics` sections of the documentation of public
+//! functions, and runs two lints:
+//!
+//! - MISSING_DOC_SECTIONS: looks for `unsafe` functions without a `# Safety` section, functions
+//!   returning a `Result` without an `# Errors` section, and functions whose body visibly panics
+//!   (by calling `panic!`, `assert!` and the like, or `unwrap` or `expect` on an `Option` or a
+//!   `Result`) without a `# Panics` section.
+//! - UNUSED_DOC_SECTIONS: looks for `# Safety` sections on functions which aren't `unsafe`, and
+//!   `# Errors` sections on functions which don't return a `Result`.
+
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc_middle::hir::map::Map;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::lint::{self, Level};
+use rustc_span::hygiene::{ExpnKind, MacroKind};
+use rustc_span::symbol::sym;
+use rustc_span::Span;
+
+use std::ops::Range;
+
+use super::{source_span_for_markdown_range, span_of_attrs, Pass};
+use crate::clean::{self, Item};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::html::markdown::markdown_headings;
+
+pub const CHECK_DOC_SECTIONS: Pass = Pass {
+    name: "check-doc-sections",
+    run: check_doc_sections,
+    description: "checks the `# Safety`, `# Errors` and `# Panics` sections of function docs",
+};
+
+pub fn check_doc_sections(krate: clean::Crate, cx: &DocContext<'_>) -> clean::Crate {
+    SectionChecker { cx }.fold_crate(krate)
+}
+
+struct SectionChecker<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+}
+
+impl<'a, 'tcx> DocFolder for SectionChecker<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        let header = match item.inner {
+            clean::FunctionItem(ref f) => Some(f.header),
+            clean::MethodItem(ref m) => Some(m.header),
+            clean::TyMethodItem(ref m) => Some(m.header),
+            _ => None,
+        };
+        if let Some(header) = header {
+            self.check_fn(&item, header);
+        }
+
+        self.fold_item_recur(item)
+    }
+}
+
+impl<'a, 'tcx> SectionChecker<'a, 'tcx> {
+    fn check_fn(&self, item: &Item, header: hir::FnHeader) {
+        let cx = self.cx;
+        let tcx = cx.tcx;
+        let hir_id = match cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            // If non-local, no need to check anything.
+            None => return,
+        };
+        // Both lints are allowed by default, and looking for panics means type-checking the
+        // body of the function, which rustdoc otherwise avoids, so only check what's linted.
+        let is_linted = |lint| tcx.lint_level_at_node(lint, hir_id).0 != Level::Allow;
+        let check_missing = is_linted(lint::builtin::MISSING_DOC_SECTIONS);
+        let check_unused = is_linted(lint::builtin::UNUSED_DOC_SECTIONS);
+        if !check_missing && !check_unused {
+            return;
+        }
+        if !cx.renderinfo.borrow().access_levels.is_public(item.def_id) {
+            return;
+        }
+        // The documentation of trait methods is what's checked, not that of their impls.
+        if tcx.impl_of_method(item.def_id).and_then(|did| tcx.trait_id_of_impl(did)).is_some() {
+            return;
+        }
+        // Undocumented functions are left to `missing_docs`.
+        let dox = match item.attrs.collapsed_doc_value() {
+            Some(dox) => dox,
+            None => return,
+        };
+
+        let headings = markdown_headings(&dox);
+        let section = |name: &str| {
+            headings.iter().find(|(heading, _)| heading.eq_ignore_ascii_case(name)).map(|(_, r)| r)
+        };
+
+        let is_unsafe = header.unsafety == hir::Unsafety::Unsafe;
+        match section("Safety") {
+            None if is_unsafe && check_missing => self.missing_section(
+                item,
+                hir_id,
+                "missing `# Safety` section in the documentation of an `unsafe` function",
+                None,
+            ),
+            Some(range) if !is_unsafe && check_unused => self.unused_section(
+                item,
+                hir_id,
+                &dox,
+                range,
+                "`# Safety` section in the documentation of a function which isn't `unsafe`",
+            ),
+            _ => {}
+        }
+
+        let returns_result = returns_result(tcx, item.def_id);
+        match section("Errors") {
+            None if returns_result && check_missing => self.missing_section(
+                item,
+                hir_id,
+                "missing `# Errors` section in the documentation of a function returning `Result`",
+                None,
+            ),
+            Some(range) if !returns_result && check_unused => self.unused_section(
+                item,
+                hir_id,
+                &dox,
+                range,
+                "`# Errors` section in the documentation of a function which doesn't return \
+                 `Result`",
+            ),
+            _ => {}
+        }
+
+        // Whether a function can panic can't be known for sure, so `# Panics` sections are
+        // only ever missing.
+        if check_missing && section("Panics").is_none() {
+            if let Some(panic_span) = find_panic(tcx, hir_id) {
+                self.missing_section(
+                    item,
+                    hir_id,
+                    "missing `# Panics` section in the documentation of a function which can \
+                     panic",
+                    Some(panic_span),
+                );
+            }
+        }
+    }
+
+    fn missing_section(
+        &self,
+        item: &Item,
+        hir_id: hir::HirId,
+        msg: &str,
+        panic_span: Option<Span>,
+    ) {
+        let sp = span_of_attrs(&item.attrs).unwrap_or(item.source.span());
+        self.cx.tcx.struct_span_lint_hir(lint::builtin::MISSING_DOC_SECTIONS, hir_id, sp, |lint| {
+            let mut diag = lint.build(msg);
+            if let Some(panic_span) = panic_span {
+                diag.span_note(panic_span, "the function can panic here");
+            }
+            diag.emit()
+        });
+    }
+
+    fn unused_section(
+        &self,
+        item: &Item,
+        hir_id: hir::HirId,
+        dox: &str,
+        range: &Range<usize>,
+        msg: &str,
+    ) {
+        let sp = source_span_for_markdown_range(self.cx, dox, range, &item.attrs)
+            .or_else(|| span_of_attrs(&item.attrs))
+            .unwrap_or(item.source.span());
+        self.cx.tcx.struct_span_lint_hir(lint::builtin::UNUSED_DOC_SECTIONS, hir_id, sp, |lint| {
+            lint.build(msg).emit()
+        });
+    }
+}
+
+fn returns_result(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    match tcx.fn_sig(def_id).output().skip_binder().kind() {
+        ty::Adt(adt, _) => tcx.is_diagnostic_item(sym::result_type, adt.did),
+        _ => false,
+    }
+}
+
+/// Finds a place in the body of the function where it visibly panics, if any.
+fn find_panic(tcx: TyCtxt<'_>, hir_id: hir::HirId) -> Option<Span> {
+    let body_id = tcx.hir().maybe_body_owned_by(hir_id)?;
+    let mut finder = FindPanic { tcx, typeck_results: tcx.typeck_body(body_id), panic_span: None };
+    finder.visit_body(tcx.hir().body(body_id));
+    finder.panic_span
+}
+
+struct FindPanic<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typeck_results: &'tcx ty::TypeckResults<'tcx>,
+    panic_span: Option<Span>,
+}
+
+impl<'tcx> FindPanic<'tcx> {
+    /// Whether `span` comes from a call to `panic!`, including through `assert!` and the like,
+    /// but not through `unreachable!`, `todo!` and `unimplemented!`, which aren't meant to be
+    /// reached.
+    fn is_panic_macro(&self, span: Span) -> bool {
+        let mut panics = false;
+        for expn_data in span.macro_backtrace() {
+            if let ExpnKind::Macro(MacroKind::Bang, name) = expn_data.kind {
+                match &*name.as_str() {
+                    "panic" => panics = true,
+                    "unreachable" | "todo" | "unimplemented" => return false,
+                    _ => {}
+                }
+            }
+        }
+        panics
+    }
+
+    /// Whether `def_id` is `unwrap` or `expect` on an `Option` or a `Result`.
+    fn is_unwrap(&self, def_id: DefId) -> bool {
+        let tcx = self.tcx;
+        if !matches!(&*tcx.item_name(def_id).as_str(), "unwrap" | "expect") {
+            return false;
+        }
+        let impl_ty = match tcx.impl_of_method(def_id) {
+            Some(impl_) => tcx.type_of(impl_),
+            None => return false,
+        };
+        match impl_ty.kind() {
+            ty::Adt(adt, _) => {
+                tcx.is_diagnostic_item(sym::option_type, adt.did)
+                    || tcx.is_diagnostic_item(sym::result_type, adt.did)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for FindPanic<'tcx> {
+    type Map = Map<'tcx>;
+
+    fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+        // Closures are visited, but not the items nested in the function.
+        NestedVisitorMap::OnlyBodies(self.tcx.hir())
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        if self.panic_span.is_some() {
+            return;
+        }
+        let panics = self.is_panic_macro(ex.span)
+            || match ex.kind {
+                hir::ExprKind::MethodCall(..) => self
+                    .typeck_results
+                    .type_dependent_def_id(ex.hir_id)
+                    .map_or(false, |def_id| self.is_unwrap(def_id)),
+                _ => false,
+            };
+        if panics {
+            self.panic_span = Some(ex.span.source_callsite());
+            return;
+        }
+        intravisit::walk_expr(self, ex);
+    }
+}
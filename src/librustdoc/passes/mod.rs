@@ -42,6 +42,9 @@ pub use self::collect_trait_impls::COLLECT_TRAIT_IMPLS;
 mod check_code_block_syntax;
 pub use self::check_code_block_syntax::CHECK_CODE_BLOCK_SYNTAX;
 
+mod check_doc_sections;
+pub use self::check_doc_sections::CHECK_DOC_SECTIONS;
+
 mod calculate_doc_coverage;
 pub use self::calculate_doc_coverage::CALCULATE_DOC_COVERAGE;
 
@@ -85,6 +88,7 @@ pub const PASSES: &[Pass] = &[
     PROPAGATE_DOC_CFG,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
+    CHECK_DOC_SECTIONS,
     COLLECT_TRAIT_IMPLS,
     CALCULATE_DOC_COVERAGE,
 ];
@@ -100,6 +104,7 @@ pub const DEFAULT_PASSES: &[ConditionalPass] = &[
     ConditionalPass::new(STRIP_PRIV_IMPORTS, WhenDocumentPrivate),
     ConditionalPass::always(COLLECT_INTRA_DOC_LINKS),
     ConditionalPass::always(CHECK_CODE_BLOCK_SYNTAX),
+    ConditionalPass::always(CHECK_DOC_SECTIONS),
     ConditionalPass::always(PROPAGATE_DOC_CFG),
 ];
 
@@ -1,10 +1,14 @@
 use std::sync::Arc;
 
+use rustc_data_structures::fx::FxHashSet;
+use rustc_session::lint;
+use rustc_span::symbol::sym;
+
 use crate::clean::cfg::Cfg;
-use crate::clean::{Crate, Item};
+use crate::clean::{AttributesExt, Crate, Item, NestedAttributesExt};
 use crate::core::DocContext;
 use crate::fold::DocFolder;
-use crate::passes::Pass;
+use crate::passes::{span_of_attrs, Pass};
 
 pub const PROPAGATE_DOC_CFG: Pass = Pass {
     name: "propagate-doc-cfg",
@@ -12,17 +16,72 @@ pub const PROPAGATE_DOC_CFG: Pass = Pass {
     description: "propagates `#[doc(cfg(...))]` to child items",
 };
 
-pub fn propagate_doc_cfg(cr: Crate, _: &DocContext<'_>) -> Crate {
-    CfgPropagator { parent_cfg: None }.fold_crate(cr)
+pub fn propagate_doc_cfg(cr: Crate, cx: &DocContext<'_>) -> Crate {
+    let mut auto_cfg = false;
+    // `doc` and `doctest` are always enabled when documenting, and `test` never is.
+    let mut hidden_cfg: FxHashSet<Cfg> = vec![sym::doc, sym::doctest, sym::test]
+        .into_iter()
+        .map(|name| Cfg::Cfg(name, None))
+        .collect();
+    if let Some(ref module) = cr.module {
+        auto_cfg = module.attrs.lists(sym::doc).has_word(sym::auto_cfg);
+        for attr in module.attrs.lists(sym::doc).filter(|attr| attr.has_name(sym::cfg_hide)) {
+            for cfg_mi in attr.meta_item_list().unwrap_or(&[]) {
+                match cfg_mi.meta_item().map(Cfg::parse) {
+                    Some(Ok(cfg)) => {
+                        hidden_cfg.insert(cfg);
+                    }
+                    Some(Err(e)) => cx.sess().span_err(e.span, e.msg),
+                    None => cx.sess().span_err(cfg_mi.span(), "unexpected literal"),
+                }
+            }
+        }
+    }
+
+    CfgPropagator { cx, parent_cfg: None, parent_actual_cfg: Cfg::True, auto_cfg, hidden_cfg }
+        .fold_crate(cr)
 }
 
-struct CfgPropagator {
+struct CfgPropagator<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
     parent_cfg: Option<Arc<Cfg>>,
+    /// The `#[cfg]` of the parent items, which the current item is compiled under.
+    parent_actual_cfg: Cfg,
+    /// Whether the `#[cfg]` of the items is shown like a `#[doc(cfg)]`, with
+    /// `#![doc(auto_cfg)]`.
+    auto_cfg: bool,
+    /// The options left out of the `#[cfg]` shown, with `#![doc(cfg_hide(...))]`.
+    hidden_cfg: FxHashSet<Cfg>,
 }
 
-impl DocFolder for CfgPropagator {
+impl<'a, 'tcx> DocFolder for CfgPropagator<'a, 'tcx> {
     fn fold_item(&mut self, mut item: Item) -> Option<Item> {
         let old_parent_cfg = self.parent_cfg.clone();
+        let old_parent_actual_cfg = self.parent_actual_cfg.clone();
+
+        let mut actual_cfg = Cfg::True;
+        for cfg_mi in item.attrs.lists(sym::cfg) {
+            // Invalid `#[cfg]`s have already been reported by rustc.
+            if let Some(Ok(cfg)) = cfg_mi.meta_item().map(Cfg::parse) {
+                actual_cfg &= cfg;
+            }
+        }
+        self.parent_actual_cfg &= actual_cfg.clone();
+
+        let has_doc_cfg = item.attrs.lists(sym::doc).any(|attr| attr.has_name(sym::cfg));
+        if has_doc_cfg {
+            self.check_doc_cfg(&item);
+        } else if self.auto_cfg {
+            if let Some(cfg) = actual_cfg.strip_hidden(&self.hidden_cfg) {
+                item.attrs.cfg = match item.attrs.cfg.take() {
+                    Some(mut rc) => {
+                        *Arc::make_mut(&mut rc) &= cfg;
+                        Some(rc)
+                    }
+                    None => Some(Arc::new(cfg)),
+                };
+            }
+        }
 
         let new_cfg = match (self.parent_cfg.take(), item.attrs.cfg.take()) {
             (None, None) => None,
@@ -38,7 +97,53 @@ impl DocFolder for CfgPropagator {
 
         let result = self.fold_item_recur(item);
         self.parent_cfg = old_parent_cfg;
+        self.parent_actual_cfg = old_parent_actual_cfg;
 
         result
     }
 }
+
+impl<'a, 'tcx> CfgPropagator<'a, 'tcx> {
+    /// Runs the `CONTRADICTORY_DOC_CFG` lint on the `#[doc(cfg)]` of the item.
+    fn check_doc_cfg(&self, item: &Item) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => return,
+        };
+        let doc_cfg = match item.attrs.cfg {
+            Some(ref doc_cfg) => doc_cfg,
+            None => return,
+        };
+        if !doc_cfg.contradicts(&self.parent_actual_cfg) {
+            return;
+        }
+
+        let sp = item
+            .attrs
+            .other_attrs
+            .iter()
+            .find(|attr| {
+                attr.has_name(sym::doc)
+                    && attr
+                        .meta_item_list()
+                        .map_or(false, |list| list.iter().any(|attr| attr.has_name(sym::cfg)))
+            })
+            .map(|attr| attr.span)
+            .or_else(|| span_of_attrs(&item.attrs))
+            .unwrap_or(item.source.span());
+        let actual_cfg = &self.parent_actual_cfg;
+        self.cx.tcx.struct_span_lint_hir(
+            lint::builtin::CONTRADICTORY_DOC_CFG,
+            hir_id,
+            sp,
+            |lint| {
+                let mut diag =
+                    lint.build("`doc(cfg)` contradicts the `cfg` the item is compiled under");
+                if *actual_cfg != Cfg::True {
+                    diag.note(&format!("the item is only compiled with `cfg({})`", actual_cfg));
+                }
+                diag.emit()
+            },
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Finding the calls to a crate's functions and methods in other crates (usually its examples),
+//! so they can be shown as examples in its documentation.
+//!
+//! Each crate which calls the documented crate is scraped by running rustdoc on it with
+//! `--scrape-examples-output-path` and `--scrape-examples-target-crate`, which writes the
+//! locations of the calls found in its type-checked HIR to a JSON file instead of documenting
+//! it. Those files are then given to rustdoc with `--with-examples` when documenting the crate.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_hir::{
+    self as hir,
+    intravisit::{self, NestedVisitorMap, Visitor},
+};
+use rustc_middle::hir::map::Map;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::getopts;
+use rustc_span::{FileName, Span};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+crate struct ScrapeExamplesOptions {
+    output_path: PathBuf,
+    target_crates: Vec<String>,
+}
+
+impl ScrapeExamplesOptions {
+    crate fn new(
+        matches: &getopts::Matches,
+        diag: &rustc_errors::Handler,
+    ) -> Result<Option<Self>, i32> {
+        let output_path = matches.opt_str("scrape-examples-output-path");
+        let target_crates = matches.opt_strs("scrape-examples-target-crate");
+        match (output_path, target_crates.is_empty()) {
+            (Some(output_path), false) => Ok(Some(ScrapeExamplesOptions {
+                output_path: PathBuf::from(output_path),
+                target_crates,
+            })),
+            (Some(_), true) => {
+                diag.struct_err(
+                    "must use --scrape-examples-target-crate with --scrape-examples-output-path",
+                )
+                .emit();
+                Err(1)
+            }
+            (None, false) => {
+                diag.struct_err(
+                    "must use --scrape-examples-output-path with --scrape-examples-target-crate",
+                )
+                .emit();
+                Err(1)
+            }
+            (None, true) => Ok(None),
+        }
+    }
+}
+
+/// A range of a source file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+crate struct SyntaxRange {
+    /// Byte offsets from the start of the file.
+    crate byte_span: (u32, u32),
+    /// 0-based, inclusive.
+    crate line_span: (usize, usize),
+}
+
+impl SyntaxRange {
+    fn new(span: Span, file: &rustc_span::SourceFile) -> Self {
+        let get_pos = |bytepos: rustc_span::BytePos| (bytepos - file.start_pos).0;
+        let get_line = |bytepos| file.lookup_line(bytepos).unwrap_or(0);
+        SyntaxRange {
+            byte_span: (get_pos(span.lo()), get_pos(span.hi())),
+            line_span: (get_line(span.lo()), get_line(span.hi())),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+crate struct CallLocation {
+    crate call_expr: SyntaxRange,
+    /// The item (e.g. the function) the call is in, which is shown as the example.
+    crate enclosing_item: SyntaxRange,
+}
+
+/// The calls to a function in a given file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+crate struct CallData {
+    crate locations: Vec<CallLocation>,
+    /// The name of the crate the file belongs to.
+    crate crate_name: String,
+}
+
+/// The calls to a function, by the path of the file they're in.
+crate type FnCallLocations = BTreeMap<String, CallData>;
+
+/// The calls to each function, by the key returned by `call_key`.
+crate type AllCallLocations = FxHashMap<String, FnCallLocations>;
+
+/// Identifies a function across the crate it's defined in and the crates calling it.
+fn call_key(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    format!("{}{}", tcx.crate_name(def_id.krate), tcx.def_path(def_id).to_string_no_crate_verbose())
+}
+
+struct FindCalls<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    target_crates: &'a [String],
+    crate_name: String,
+    calls: &'a mut AllCallLocations,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for FindCalls<'a, 'tcx> {
+    type Map = Map<'tcx>;
+
+    fn nested_visit_map(&mut self) -> NestedVisitorMap<Self::Map> {
+        NestedVisitorMap::All(self.tcx.hir())
+    }
+
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old = self.maybe_typeck_results.replace(self.tcx.typeck_body(body));
+        let body = self.tcx.hir().body(body);
+        intravisit::walk_body(self, body);
+        self.maybe_typeck_results = old;
+    }
+
+    fn visit_expr(&mut self, ex: &'tcx hir::Expr<'tcx>) {
+        intravisit::walk_expr(self, ex);
+
+        let typeck_results = match self.maybe_typeck_results {
+            Some(typeck_results) => typeck_results,
+            None => return,
+        };
+        let def_id = match ex.kind {
+            hir::ExprKind::Call(f, _) => match typeck_results.node_type(f.hir_id).kind() {
+                ty::FnDef(def_id, _) => *def_id,
+                _ => return,
+            },
+            hir::ExprKind::MethodCall(..) => {
+                match typeck_results.type_dependent_def_id(ex.hir_id) {
+                    Some(def_id) => def_id,
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+
+        // Calls written by macros don't make for good examples, and may not even be in this crate.
+        if ex.span.from_expansion() {
+            return;
+        }
+        let crate_name = self.tcx.crate_name(def_id.krate).to_string();
+        if !self.target_crates.contains(&crate_name) {
+            return;
+        }
+
+        let hir = self.tcx.hir();
+        let enclosing_item_span = hir.span_with_body(hir.get_parent_item(ex.hir_id));
+        let source_map = self.tcx.sess.source_map();
+        let file = source_map.lookup_source_file(ex.span.lo());
+        let path = match &file.name {
+            FileName::Real(name) => name.local_path().display().to_string(),
+            _ => return,
+        };
+        if enclosing_item_span.from_expansion()
+            || !enclosing_item_span.contains(ex.span)
+            || !file.contains(enclosing_item_span.lo())
+        {
+            return;
+        }
+
+        let location = CallLocation {
+            call_expr: SyntaxRange::new(ex.span, &file),
+            enclosing_item: SyntaxRange::new(enclosing_item_span, &file),
+        };
+        let crate_name = &self.crate_name;
+        self.calls
+            .entry(call_key(self.tcx, def_id))
+            .or_default()
+            .entry(path)
+            .or_insert_with(|| CallData { locations: Vec::new(), crate_name: crate_name.clone() })
+            .locations
+            .push(location);
+    }
+}
+
+/// Writes the calls which the local crate makes to the target crates to the output path.
+crate fn run(tcx: TyCtxt<'_>, options: ScrapeExamplesOptions) {
+    let mut calls = AllCallLocations::default();
+    let mut finder = FindCalls {
+        tcx,
+        maybe_typeck_results: None,
+        target_crates: &options.target_crates,
+        crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+        calls: &mut calls,
+    };
+    intravisit::walk_crate(&mut finder, tcx.hir().krate());
+
+    let result = serde_json::to_string(&calls)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(&options.output_path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        tcx.sess.fatal(&format!(
+            "failed to write scraped examples to `{}`: {}",
+            options.output_path.display(),
+            e
+        ));
+    }
+}
+
+/// Reads the calls scraped into each of `paths`.
+crate fn load_call_locations(
+    paths: Vec<String>,
+    diag: &rustc_errors::Handler,
+) -> Result<AllCallLocations, i32> {
+    let mut all_calls = AllCallLocations::default();
+    for path in paths {
+        let calls = fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|contents| {
+            serde_json::from_str::<AllCallLocations>(&contents).map_err(|e| e.to_string())
+        });
+        let calls = match calls {
+            Ok(calls) => calls,
+            Err(e) => {
+                diag.struct_err(&format!("failed to load examples from `{}`: {}", path, e)).emit();
+                return Err(1);
+            }
+        };
+        for (key, fn_calls) in calls {
+            let all_fn_calls = all_calls.entry(key).or_default();
+            for (file, data) in fn_calls {
+                match all_fn_calls.get_mut(&file) {
+                    Some(all_data) => all_data.locations.extend(data.locations),
+                    None => {
+                        all_fn_calls.insert(file, data);
+                    }
+                }
+            }
+        }
+    }
+    Ok(all_calls)
+}
+
+/// Finds the calls to the local crate's functions and methods in `all_calls`.
+crate fn local_call_locations(
+    tcx: TyCtxt<'_>,
+    all_calls: &AllCallLocations,
+) -> FxHashMap<DefId, FnCallLocations> {
+    if all_calls.is_empty() {
+        return FxHashMap::default();
+    }
+    let hir = tcx.hir();
+    let krate = hir.krate();
+    let hir_ids = krate
+        .items
+        .keys()
+        .copied()
+        .chain(krate.trait_items.keys().map(|id| id.hir_id))
+        .chain(krate.impl_items.keys().map(|id| id.hir_id));
+    hir_ids
+        .map(|hir_id| hir.local_def_id(hir_id).to_def_id())
+        .filter(|&def_id| matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn))
+        .filter_map(|def_id| Some((def_id, all_calls.get(&call_key(tcx, def_id))?.clone())))
+        .collect()
+}
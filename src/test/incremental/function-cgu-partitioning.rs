@@ -0,0 +1,53 @@
+// Test that the `function` partitioning strategy, which gives every function
+// its own codegen unit, works across incremental sessions in which functions
+// are changed, added and removed.
+
+// revisions:rpass1 rpass2 rpass3
+// compile-flags: -Z cgu-partitioning-strategy=function -Z incremental-info -Z query-dep-graph
+
+#![allow(dead_code)]
+#![feature(rustc_attrs)]
+
+// `square` changes, `main` changes because it starts calling `cube`, and
+// everything else is untouched.
+#![rustc_partition_codegened(module="function_cgu_partitioning-shapes-square", cfg="rpass2")]
+#![rustc_partition_codegened(module="function_cgu_partitioning-main", cfg="rpass2")]
+#![rustc_partition_codegened(module="function_cgu_partitioning-shapes-cube", cfg="rpass2")]
+#![rustc_partition_reused(module="function_cgu_partitioning-shapes-generic.volatile", cfg="rpass2")]
+#![rustc_partition_reused(module="function_cgu_partitioning-TABLE", cfg="rpass2")]
+
+// `cube` is removed again and `main` changes back, but `square` is the same
+// as in rpass2 and can be reused even though it shares a module with `cube`.
+#![rustc_partition_codegened(module="function_cgu_partitioning-main", cfg="rpass3")]
+#![rustc_partition_reused(module="function_cgu_partitioning-shapes-square", cfg="rpass3")]
+#![rustc_partition_reused(module="function_cgu_partitioning-shapes-generic.volatile", cfg="rpass3")]
+#![rustc_partition_reused(module="function_cgu_partitioning-TABLE", cfg="rpass3")]
+
+mod shapes {
+    pub fn square(x: u32) -> u32 {
+        #[cfg(rpass1)]
+        return x * x;
+
+        #[cfg(not(rpass1))]
+        return x.pow(2);
+    }
+
+    #[cfg(rpass2)]
+    pub fn cube(x: u32) -> u32 {
+        x * square(x)
+    }
+
+    pub fn generic<T: Copy>(x: T) -> (T, T) {
+        (x, x)
+    }
+}
+
+static TABLE: [u32; 3] = [1, 2, 3];
+
+fn main() {
+    assert_eq!(shapes::square(3), 9);
+    assert_eq!(shapes::generic(TABLE[1]), (2, 2));
+
+    #[cfg(rpass2)]
+    assert_eq!(shapes::cube(2), 8);
+}
@@ -0,0 +1,36 @@
+// Test that `-Z incremental-explain` can explain the re-execution of the
+// queries of an item whose dependency changed, as well as of an item that did
+// not exist in the previous session.
+
+// revisions:rpass1 rpass2 rpass3
+// compile-flags: -Z incremental-explain=shapes::area
+
+#![allow(dead_code)]
+
+mod shapes {
+    pub fn side() -> u32 {
+        #[cfg(rpass1)]
+        return 3;
+
+        #[cfg(not(rpass1))]
+        return 4;
+    }
+
+    #[cfg(not(rpass3))]
+    pub fn area() -> u32 {
+        side() * side()
+    }
+
+    #[cfg(rpass3)]
+    pub fn area() -> u64 {
+        side() as u64 * side() as u64
+    }
+}
+
+fn main() {
+    #[cfg(rpass1)]
+    assert_eq!(shapes::area(), 9);
+
+    #[cfg(not(rpass1))]
+    assert_eq!(shapes::area(), 16);
+}
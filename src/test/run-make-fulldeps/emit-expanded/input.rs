@@ -0,0 +1,68 @@
+// The local variable of the macro would be confused with that of the caller.
+macro_rules! add_one_to {
+    ($e:expr) => {{
+        let x = 1;
+        $e + x
+    }};
+}
+
+fn answer() -> i32 {
+    42
+}
+
+// The local variable of the caller would shadow the function.
+macro_rules! answer {
+    () => {
+        answer()
+    };
+}
+
+fn show(n: i32) {
+    println!("{}", n);
+}
+
+// The label of the macro would be confused with that of the caller.
+macro_rules! for_each {
+    ($v:expr, $body:block) => {
+        'search: for n in $v {
+            show(n);
+            $body
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+mod point {
+    #[macro_export]
+    macro_rules! point {
+        ($x:expr, $y:expr) => {
+            $crate::Point { x: $x, y: $y }
+        };
+    }
+}
+
+fn main() {
+    let x = 10;
+    show(add_one_to!(x * 2));
+
+    // The local variable is renamed in the invocation of `println!` too, which is left
+    // unexpanded.
+    let answer = 0;
+    let n = answer!();
+    println!("{} {}", answer, n);
+
+    'search: for _ in 0..2 {
+        for_each!(vec![1, 2, 3], {
+            continue 'search;
+        });
+    }
+
+    let y = 2;
+    let p = point!(x, y);
+    println!("{:?} {}", p, p == p.clone());
+}
@@ -0,0 +1,22 @@
+#![allow(dead_code)]
+
+mod shapes {
+    #[cfg(not(changed_side))]
+    pub fn side() -> u32 {
+        3
+    }
+
+    #[cfg(changed_side)]
+    pub fn side() -> u64 {
+        3
+    }
+
+    #[cfg(with_area)]
+    pub fn area() -> u64 {
+        side() as u64 * side() as u64
+    }
+}
+
+fn main() {
+    println!("{}", shapes::side());
+}
@@ -0,0 +1,9 @@
+#![crate_type = "lib"]
+
+pub fn sum(values: &[u32]) -> u32 {
+    values.iter().copied().filter(|value| value % 2 == 0).sum()
+}
+
+pub fn words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(|word| word.to_uppercase()).collect()
+}
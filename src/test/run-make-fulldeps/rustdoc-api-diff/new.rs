@@ -0,0 +1,30 @@
+pub fn changed(x: u64) -> u64 {
+    x
+}
+
+#[deprecated]
+pub fn deprecated() {}
+
+pub fn renamed_argument(y: u32) -> u32 {
+    y
+}
+
+pub fn added() {}
+
+pub struct Unchanged;
+
+pub struct Exhaustive {
+    pub a: u32,
+    pub b: u32,
+}
+
+#[non_exhaustive]
+pub enum NonExhaustive {
+    A,
+    B,
+}
+
+pub trait Trait {
+    fn provided(&self) {}
+    fn required(&self);
+}
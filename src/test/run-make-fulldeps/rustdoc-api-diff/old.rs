@@ -0,0 +1,26 @@
+pub fn removed() {}
+
+pub fn changed(x: u32) -> u32 {
+    x
+}
+
+pub fn deprecated() {}
+
+pub fn renamed_argument(x: u32) -> u32 {
+    x
+}
+
+pub struct Unchanged;
+
+pub struct Exhaustive {
+    pub a: u32,
+}
+
+#[non_exhaustive]
+pub enum NonExhaustive {
+    A,
+}
+
+pub trait Trait {
+    fn provided(&self) {}
+}
@@ -0,0 +1 @@
+//! A guide to the assembly of widgets.
@@ -0,0 +1 @@
+//! A guide to the frobnication of gadgets.
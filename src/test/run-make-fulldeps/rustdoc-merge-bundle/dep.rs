@@ -0,0 +1,9 @@
+pub struct Dep;
+
+pub trait Trait {}
+
+#[doc(hidden)]
+pub struct Hidden;
+
+#[doc(hidden)]
+pub struct Unused;
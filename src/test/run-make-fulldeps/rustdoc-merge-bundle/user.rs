@@ -0,0 +1,16 @@
+extern crate dep;
+
+// @has search-index.js '"dep":'
+// @has search-index.js '"user":'
+// @has index.html '//ul[@class="crate mod"]//a[@href="dep/index.html"]' 'dep'
+// @has index.html '//ul[@class="crate mod"]//a[@href="user/index.html"]' 'user'
+// @has implementors/dep/trait.Trait.js 'implementors["user"]'
+
+// @has user/fn.dep.html '//a[@href="../dep/struct.Dep.html"]' 'Dep'
+pub fn dep(_: dep::Dep) {}
+
+pub fn hidden(_: dep::Hidden) {}
+
+pub struct User;
+
+impl dep::Trait for User {}
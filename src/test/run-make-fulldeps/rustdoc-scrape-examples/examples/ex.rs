@@ -0,0 +1,11 @@
+fn main() {
+    scraped::ok();
+    let mut counter = scraped::Counter::new();
+    counter.incr();
+    counter.incr();
+    assert_eq!(counter.get(), 2);
+}
+
+fn also_calls_ok() {
+    scraped::ok();
+}
@@ -0,0 +1,27 @@
+// @has scraped/fn.ok.html
+// @has - '//div[@class="scraped-example-title"]' 'examples/ex.rs (line 1, in crate ex)'
+// @has - '//div[@class="scraped-example"]//span[@class="highlight"]' 'scraped::ok()'
+// @count - '//div[@class="scraped-example"]' 1
+pub fn ok() {}
+
+// @count scraped/fn.unused.html '//div[@class="scraped-example"]' 0
+pub fn unused() {}
+
+pub struct Counter(u32);
+
+impl Counter {
+    // @has scraped/struct.Counter.html
+    // @has - '//div[@class="scraped-example"]//span[@class="highlight"]' 'scraped::Counter::new()'
+    pub fn new() -> Counter {
+        Counter(0)
+    }
+
+    // @count - '//div[@class="scraped-example"]//span[@class="highlight"]' 4
+    pub fn incr(&mut self) {
+        self.0 += 1;
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
@@ -0,0 +1,30 @@
+#![feature(proc_macro_pure, proc_macro_tracked_env)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+#[proc_macro_derive(Named)]
+pub fn derive_named(input: TokenStream) -> TokenStream {
+    proc_macro::declare_pure();
+
+    // Keep track of every run, so the test can tell when the output was reused.
+    let log = env::var("PROC_MACRO_CACHE_LOG").unwrap();
+    let mut log = OpenOptions::new().create(true).append(true).open(log).unwrap();
+    writeln!(log, "derive_named").unwrap();
+
+    let suffix = tracked_env::var("NAME_SUFFIX").unwrap_or_default();
+    let name = input.into_iter().skip_while(|tt| tt.to_string() != "struct").nth(1).unwrap();
+    let full_name = format!("{}{}", name, suffix);
+    let body = format!("{{ fn name() -> String {{ String::from({:?}) }} }}", full_name);
+
+    // Keep the span of the name from the input, which has to be adjusted when reused.
+    let mut output: TokenStream = "impl Named for".parse().unwrap();
+    output.extend(vec![name]);
+    output.extend(body.parse::<TokenStream>().unwrap());
+    output
+}
@@ -0,0 +1,13 @@
+#[macro_use]
+extern crate macro_def;
+
+trait Named {
+    fn name() -> String;
+}
+
+#[derive(Named)]
+struct Foo;
+
+fn main() {
+    println!("{}", Foo::name());
+}
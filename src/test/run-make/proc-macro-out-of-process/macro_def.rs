@@ -0,0 +1,36 @@
+#![feature(proc_macro_expand)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+use std::io::{self, Write};
+use std::os::unix::process::parent_id;
+use std::process;
+
+/// Expands to the ids of the process running it and of its parent.
+#[proc_macro]
+pub fn pids(_: TokenStream) -> TokenStream {
+    format!("({}u32, {}u32)", process::id(), parent_id()).parse().unwrap()
+}
+
+#[proc_macro]
+pub fn pid(_: TokenStream) -> TokenStream {
+    format!("{}u32", process::id()).parse().unwrap()
+}
+
+/// Expands to the id of the process running it, and to the one `pid!()`
+/// ran in when expanded by this macro.
+#[proc_macro]
+pub fn nested_pids(_: TokenStream) -> TokenStream {
+    let inner = "pid!()".parse::<TokenStream>().unwrap().expand_expr().unwrap();
+    format!("({}u32, {})", process::id(), inner).parse().unwrap()
+}
+
+#[proc_macro]
+pub fn noisy(_: TokenStream) -> TokenStream {
+    let mut stdout = io::stdout();
+    writeln!(stdout, "printed by a proc macro").unwrap();
+    stdout.flush().unwrap();
+    TokenStream::new()
+}
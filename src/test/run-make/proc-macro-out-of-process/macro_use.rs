@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate macro_def;
+
+noisy!();
+
+fn main() {
+    let (pid, ppid) = pids!();
+    let (outer, inner) = nested_pids!();
+    println!("{} {} {} {}", pid, ppid, outer, inner);
+}
@@ -0,0 +1,15 @@
+#![feature(proc_macro_tracked_path)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+use std::fs;
+
+#[proc_macro]
+pub fn read_schema(_: TokenStream) -> TokenStream {
+    let path = "schema.proto";
+    let schema = fs::read_to_string(path).unwrap();
+    tracked_path::path(path);
+    format!("const SCHEMA: &str = {:?};", schema).parse().unwrap()
+}
@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate macro_def;
+
+read_schema!();
+
+fn main() {
+    assert!(SCHEMA.contains("message Point"));
+}
@@ -0,0 +1,8 @@
+const QUERY = 'Vec<u8> -> String';
+
+const EXPECTED = {
+    'in_args': [
+        { 'path': 'std::string::String', 'name': 'from_utf8' },
+        { 'path': 'std::string::String', 'name': 'from_utf8_unchecked' },
+    ],
+};
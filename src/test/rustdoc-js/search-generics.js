@@ -0,0 +1,50 @@
+// exact-check
+
+const QUERY = [
+    'Vec<T> -> Option<T>',
+    'T -> Vec<T>',
+    'impl Read -> String',
+    '&str -> Result<Widget>',
+    'Iterator<Item = u32> -> u32',
+    'Iterator -> Option<Item>',
+    'T -> T',
+];
+
+const EXPECTED = [
+    {
+        'in_args': [
+            { 'path': 'search_generics', 'name': 'first' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'search_generics', 'name': 'wrap' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'search_generics', 'name': 'read_all' },
+            { 'path': 'search_generics', 'name': 'read_generic' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'search_generics', 'name': 'parse' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'search_generics', 'name': 'sum' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'search_generics', 'name': 'last' },
+        ],
+    },
+    {
+        'in_args': [
+            { 'path': 'search_generics::Combine', 'name': 'combine' },
+        ],
+    },
+];
@@ -0,0 +1,42 @@
+use std::io::Read;
+
+pub struct Widget;
+pub struct ParseError;
+
+pub fn first<T>(v: Vec<T>) -> Option<T> {
+    v.into_iter().next()
+}
+
+pub fn read_all(mut r: impl Read) -> String {
+    let mut s = String::new();
+    r.read_to_string(&mut s).unwrap();
+    s
+}
+
+pub fn read_generic<R: Read>(r: R) -> String {
+    read_all(r)
+}
+
+pub fn parse(_: &str) -> Result<Widget, ParseError> {
+    Ok(Widget)
+}
+
+pub fn sum<I: Iterator<Item = u32>>(it: I) -> u32 {
+    it.sum()
+}
+
+pub fn wrap<T>(x: T) -> Vec<T> {
+    vec![x]
+}
+
+pub fn bytes<T>(_: T) -> Vec<u8> {
+    Vec::new()
+}
+
+pub fn last<I: Iterator>(it: I) -> Option<I::Item> {
+    it.last()
+}
+
+pub trait Combine {
+    fn combine(self, other: Self) -> Self;
+}
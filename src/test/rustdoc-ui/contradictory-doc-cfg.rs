@@ -0,0 +1,23 @@
+// compile-flags: --cfg foo
+#![feature(doc_cfg)]
+#![deny(contradictory_doc_cfg)]
+
+#[cfg(doc)]
+#[doc(cfg(not(doc)))]
+//~^ ERROR `doc(cfg)` contradicts the `cfg` the item is compiled under
+pub struct Contradicts;
+
+#[cfg(foo)]
+pub mod foo_only {
+    #[doc(cfg(not(foo)))]
+    //~^ ERROR `doc(cfg)` contradicts the `cfg` the item is compiled under
+    pub fn contradicts_parent() {}
+
+    #[doc(cfg(all(foo, unix)))]
+    pub fn agrees() {}
+}
+
+// The item is compiled when documenting, whatever the platform.
+#[cfg(any(unix, doc))]
+#[doc(cfg(windows))]
+pub struct DocumentedEverywhere;
@@ -0,0 +1,17 @@
+// Doctests with `extern crate` items aren't compiled together with the others, which could
+// otherwise use the crates they declare.
+
+// compile-flags:--test --test-args --test-threads=1 -Z unstable-options --batch-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+
+/// ```
+/// extern crate proc_macro;
+///
+/// use proc_macro::TokenStream;
+/// ```
+///
+/// ```
+/// use proc_macro::TokenStream;
+/// ```
+pub struct Foo;
@@ -0,0 +1,18 @@
+// Doctests which don't compile together are compiled separately.
+
+// check-pass
+// compile-flags:--test --test-args --test-threads=1 -Z unstable-options --batch-doctests
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+/// ```
+/// fn main() {
+///     crate::bar();
+/// }
+///
+/// fn bar() {}
+/// ```
+///
+/// ```
+/// assert_eq!(2 + 2, 4);
+/// ```
+pub struct Foo;
@@ -0,0 +1,27 @@
+// Doctests compiled together are still run and reported separately, and the failing ones are
+// run again on their own, so they fail like the doctests compiled separately.
+
+// compile-flags:--test --test-args --test-threads=1 -Z unstable-options --batch-doctests
+// rustc-env:RUST_BACKTRACE=0
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+
+/// ```
+/// assert_eq!(2 + 2, 4);
+/// ```
+///
+/// ```
+/// panic!("oh no");
+/// ```
+///
+/// ```should_panic
+/// let v: Vec<u32> = Vec::new();
+/// v[0];
+/// ```
+///
+/// ```
+/// fn main() -> Result<(), String> {
+///     Err("not found".to_owned())
+/// }
+/// ```
+pub struct Foo;
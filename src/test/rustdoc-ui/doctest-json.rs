@@ -0,0 +1,25 @@
+// Doctests report their sources and the errors of the ones that don't compile in the JSON output
+// of libtest.
+
+// compile-flags:--test --test-args=--test-threads=1 --test-args=-Zunstable-options
+// compile-flags:--test-args=--format=json
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test: "\[\{.*\}\]" -> "[..]"
+// failure-status: 101
+
+/// ```
+/// assert_eq!(2 + 2, 4);
+/// ```
+///
+/// ```should_panic,edition2018
+/// let v: Vec<u32> = Vec::new();
+/// v[0];
+/// ```
+pub struct Foo;
+
+pub mod bar {
+    /// ```
+    /// let x: u32 = "not a number";
+    /// ```
+    pub fn baz() {}
+}
@@ -0,0 +1,95 @@
+#![deny(missing_doc_sections, unused_doc_sections)]
+
+/// Doc
+pub unsafe fn no_safety() {}
+//~^^ ERROR missing `# Safety` section
+
+/// Doc
+///
+/// # Safety
+///
+/// Don't.
+pub unsafe fn safety() {}
+
+/// Doc
+///
+/// # Safety
+pub fn safe() {}
+//~^^ ERROR `# Safety` section in the documentation of a function which isn't `unsafe`
+
+/// Doc
+pub fn no_errors() -> Result<(), ()> {
+    //~^^ ERROR missing `# Errors` section
+    Ok(())
+}
+
+/// Doc
+///
+/// # Errors
+///
+/// Never.
+pub fn errors() -> Result<(), ()> {
+    Ok(())
+}
+
+/// Doc
+///
+/// # Errors
+pub fn infallible() {}
+//~^^ ERROR `# Errors` section in the documentation of a function which doesn't return `Result`
+
+/// Doc
+pub fn no_panics(x: Option<u32>) -> u32 {
+    //~^^ ERROR missing `# Panics` section
+    x.unwrap()
+}
+
+/// Doc
+pub fn no_panics_assert(x: u32) {
+    //~^^ ERROR missing `# Panics` section
+    assert!(x > 0);
+}
+
+/// Doc
+///
+/// # Panics
+///
+/// If `x` is `None`.
+pub fn panics(x: Option<u32>) -> u32 {
+    x.expect("x is `None`")
+}
+
+/// Doc
+pub fn unreachable(x: bool) {
+    if x {
+        unreachable!();
+    }
+}
+
+/// Doc
+pub fn unwrap_or(x: Option<u32>) -> u32 {
+    x.unwrap_or(0)
+}
+
+/// Doc
+pub trait Trait {
+    /// Doc
+    unsafe fn no_safety(&self);
+    //~^^ ERROR missing `# Safety` section
+}
+
+/// Doc
+pub struct S;
+
+impl Trait for S {
+    /// Doc
+    unsafe fn no_safety(&self) {}
+}
+
+impl S {
+    /// Doc
+    pub fn no_panics(&self) {
+        //~^^ ERROR missing `# Panics` section
+        panic!("no")
+    }
+}
@@ -0,0 +1,3 @@
+#![crate_name = "markdown_aux"]
+
+pub struct Bar;
@@ -0,0 +1,44 @@
+#![feature(doc_cfg)]
+#![doc(auto_cfg, cfg_hide(feature = "std"))]
+#![crate_name = "foo"]
+
+// @has foo/struct.WindowsToken.html \
+//  '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+//  'This is supported on Windows only.'
+#[cfg(any(windows, doc))]
+pub struct WindowsToken;
+
+// `doc` and the hidden options are left out.
+// @has foo/struct.StdToken.html
+// @count - '//*[@class="stab portability"]' 0
+#[cfg(any(feature = "std", doc))]
+pub struct StdToken;
+
+// An explicit `doc(cfg)` replaces the `cfg`.
+// @has foo/struct.LinuxToken.html \
+//  '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+//  'This is supported on Linux only.'
+#[cfg(any(unix, doc))]
+#[doc(cfg(target_os = "linux"))]
+pub struct LinuxToken;
+
+// @has foo/unix_only/index.html \
+//  '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+//  'This is supported on Unix only.'
+#[cfg(any(unix, doc))]
+pub mod unix_only {
+    // @has foo/unix_only/fn.arm_only.html \
+    //  '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+    //  'This is supported on Unix and ARM only.'
+    #[cfg(any(target_arch = "arm", doc))]
+    pub fn arm_only() {}
+}
+
+pub struct Portable;
+
+// @has foo/struct.Portable.html '//*[@class="stab portability"]' \
+//  'This is supported on Windows only.'
+#[cfg(any(windows, doc))]
+impl Portable {
+    pub fn windows_only() {}
+}
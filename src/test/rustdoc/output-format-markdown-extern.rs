@@ -0,0 +1,28 @@
+// aux-build:output-format-markdown-aux.rs
+// build-aux-docs
+// compile-flags: -Z unstable-options --output-format markdown
+
+#![crate_name = "foo"]
+
+extern crate markdown_aux;
+
+use markdown_aux::Bar;
+
+// The other crate is documented as HTML, so the link to it is kept as it is, and the headings
+// of the docs come under the title of the page.
+
+// @has foo/fn.f.md '# Function `foo::f`'
+// @has foo/fn.f.md 'Returns a [`Bar`](../markdown_aux/struct.Bar.html). ## Examples \
+//     Some text. ### Panics Never.'
+/// Returns a [`Bar`].
+///
+/// # Examples
+///
+/// Some text.
+///
+/// ## Panics
+///
+/// Never.
+pub fn f() -> Bar {
+    Bar
+}
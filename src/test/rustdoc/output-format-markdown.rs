@@ -0,0 +1,96 @@
+// compile-flags: -Z unstable-options --output-format markdown
+
+#![crate_name = "foo"]
+
+//! The crate, with a link to [`Foo`].
+
+// @has foo/index.md '# Crate `foo`'
+// @has foo/index.md 'The crate, with a link to [`Foo`](../foo/struct.Foo.md).'
+// @has foo/index.md '## Modules'
+// @has foo/index.md '- [`bar`](bar/index.md): A module.'
+// @has foo/index.md '## Structs'
+// @has foo/index.md '- [`Foo`](struct.Foo.md): A struct.'
+// @!has foo/index.html
+
+// @has foo/struct.Foo.md '# Struct `foo::Foo`'
+// @has foo/struct.Foo.md '```rust pub struct Foo { pub x: u32, // some fields omitted } ```'
+// @has foo/struct.Foo.md 'See [`bar::baz`](../foo/bar/fn.baz.md) and [the \
+//     constructor](../foo/struct.Foo.md#method.new).'
+// @has foo/struct.Foo.md '<a id="structfield.x"></a> ### `x: u32` The field.'
+// @has foo/struct.Foo.md '## Implementations ### `impl Foo`'
+// @has foo/struct.Foo.md '<a id="method.new"></a> #### `pub fn new(x: u32) -> Foo`'
+// @has foo/struct.Foo.md '#### `pub fn x(&self) -> u32`'
+// @has foo/struct.Foo.md '## Trait Implementations - `impl Clone for Foo`'
+/// A struct.
+///
+/// See [`bar::baz`] and [the constructor](Foo::new).
+#[derive(Clone)]
+pub struct Foo {
+    /// The field.
+    pub x: u32,
+    y: u8,
+}
+
+impl Foo {
+    /// Makes a `Foo`.
+    pub fn new(x: u32) -> Foo {
+        Foo { x, y: 0 }
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+}
+
+/// A module.
+pub mod bar {
+    // @has foo/bar/index.md '# Module `foo::bar`'
+    // @has foo/bar/index.md '- [`baz`](fn.baz.md): A function.'
+
+    // @has foo/bar/fn.baz.md '```rust pub fn baz<T: Clone>(t: &T) -> T ```'
+    // @has foo/bar/fn.baz.md 'Returns a clone of `t`, like [`Clone::clone`](https://'
+    /// A function.
+    ///
+    /// Returns a clone of `t`, like [`Clone::clone`].
+    pub fn baz<T: Clone>(t: &T) -> T {
+        t.clone()
+    }
+}
+
+// @has foo/trait.Qux.md '# Trait `foo::Qux`'
+// @has foo/trait.Qux.md 'pub trait Qux { type Item; fn get(&self) -> Self::Item; \
+//     fn describe(&self) -> String { ... } }'
+// @has foo/trait.Qux.md '## Required Methods <a id="tymethod.get"></a> \
+//     ### `fn get(&self) -> Self::Item`'
+// @has foo/trait.Qux.md '## Implementors - `impl Qux for Foo`'
+/// A trait.
+pub trait Qux {
+    type Item;
+
+    fn get(&self) -> Self::Item;
+
+    fn describe(&self) -> String {
+        String::new()
+    }
+}
+
+impl Qux for Foo {
+    type Item = u32;
+
+    fn get(&self) -> u32 {
+        self.x
+    }
+}
+
+// @has foo/enum.E.md 'pub enum E { A, B(u8), C { c: bool }, }'
+// @has foo/enum.E.md '## Variants <a id="variant.A"></a> ### `A`'
+// @has foo/enum.E.md '### `C { c: bool }`'
+// @has foo/enum.E.md '- `c: bool`: The field of C.'
+pub enum E {
+    A,
+    B(u8),
+    C {
+        /// The field of C.
+        c: bool,
+    },
+}
@@ -0,0 +1,22 @@
+// compile-flags: -Z unstable-options --full-text-search
+
+#![crate_name = "foo"]
+
+//! A guide to the frobnication of widgets.
+
+// @has foo/index.html '//script[@src="../search-fulltext.js"]'
+// @has 'search-fulltext.js' '"frobnication"'
+// @has - '"widgets"'
+// @has - '"persistence"'
+// @!has 'search-index.js' 'persistence'
+// @!has 'search-fulltext.js' 'hidden_in_code'
+// @!has - '"the"'
+
+/// A widget.
+///
+/// Its persistence is guaranteed by the frobnication.
+///
+/// ```
+/// let hidden_in_code = 1;
+/// ```
+pub struct Widget;
@@ -0,0 +1,36 @@
+// Test that the arguments of an `async fn` are only stored once in its future,
+// even when they are held across an `.await`.
+//
+// The exact sizes can change by a few bytes (we'd like to know when they do).
+// What we don't want to see is the wrong multiple of 1024 (the size of the
+// arguments) being reflected in the size.
+
+// ignore-emscripten (sizes don't match)
+// run-pass
+
+// edition:2018
+
+async fn noop() {}
+
+async fn single_arg(x: [u8; 1024]) {
+    noop().await;
+    drop(x);
+}
+
+async fn two_args(x: [u8; 1024], y: [u8; 1024]) {
+    noop().await;
+    drop(x);
+    drop(y);
+}
+
+async fn mut_arg(mut x: [u8; 1024]) {
+    x[0] = 1;
+    noop().await;
+    drop(x);
+}
+
+fn main() {
+    assert_eq!(1026, std::mem::size_of_val(&single_arg([0; 1024])));
+    assert_eq!(2050, std::mem::size_of_val(&two_args([0; 1024], [0; 1024])));
+    assert_eq!(1026, std::mem::size_of_val(&mut_arg([0; 1024])));
+}
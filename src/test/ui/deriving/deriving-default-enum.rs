@@ -0,0 +1,29 @@
+// run-pass
+
+#![feature(derive_default_enum)]
+
+// nb: does not impl Default
+#[derive(Debug, PartialEq)]
+struct NotDefault;
+
+#[derive(Debug, Default, PartialEq)]
+enum Foo {
+    #[default]
+    Alpha,
+    #[allow(dead_code)]
+    Beta(NotDefault),
+}
+
+// The derive still adds the usual bounds to generic enums.
+#[derive(Debug, Default, PartialEq)]
+enum MyOption<T> {
+    #[default]
+    None,
+    #[allow(dead_code)]
+    Some(T),
+}
+
+fn main() {
+    assert_eq!(Foo::default(), Foo::Alpha);
+    assert_eq!(MyOption::<u8>::default(), MyOption::None);
+}
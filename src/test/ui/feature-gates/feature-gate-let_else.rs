@@ -0,0 +1,5 @@
+fn main() {
+    let Some(_x) = Some(1) else { //~ ERROR `let...else` statements are unstable
+        return;
+    };
+}
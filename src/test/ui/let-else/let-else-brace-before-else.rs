@@ -0,0 +1,8 @@
+// run-rustfix
+
+#![feature(let_else)]
+
+fn main() {
+    let Some(_x) = if true { Some(1) } else { None } else { return };
+    //~^ ERROR right curly brace `}` before `else` in a `let...else` statement not allowed
+}
@@ -0,0 +1,27 @@
+// run-pass
+// Test that the pattern of a `let...else` can borrow parts of a place behind the
+// result of a call, instead of moving out of it.
+
+#![feature(let_else)]
+
+struct Config {
+    name: Option<String>,
+}
+
+fn boxed_name() -> Box<Option<String>> {
+    Box::new(Some("boxed".to_string()))
+}
+
+fn name(config: &Config) -> &Option<String> {
+    &config.name
+}
+
+fn main() {
+    let Some(ref s) = *boxed_name() else { return };
+    assert_eq!(s, "boxed");
+
+    let config = Config { name: Some("config".to_string()) };
+    let Some(ref s) = *name(&config) else { return };
+    assert_eq!(s, "config");
+    assert_eq!(config.name.as_deref(), Some("config"));
+}
@@ -0,0 +1,13 @@
+#![feature(let_else)]
+
+fn main() {
+    let Some(_x) = Some(1) else { //~ ERROR does not diverge
+        Some(2)
+    };
+    let Some(_x) = Some(1) else { //~ ERROR does not diverge
+        if 1 == 1 {
+            panic!();
+        }
+    };
+    let Some(_x) = Some(1) else { return }; // OK
+}
@@ -0,0 +1,39 @@
+// run-pass
+
+#![feature(let_else)]
+
+enum Shape {
+    Circle(u32),
+    Square(u32),
+}
+
+fn radius(shape: &Shape) -> Option<u32> {
+    let Shape::Circle(r) = shape else {
+        return None;
+    };
+    Some(*r)
+}
+
+fn first_word(s: &str) -> &str {
+    let Some(word) = s.split_whitespace().next() else { panic!("empty string") };
+    word
+}
+
+fn sum_pairs(pairs: &[(Option<u32>, u32)]) -> u32 {
+    let mut sum = 0;
+    for pair in pairs {
+        let (Some(a), b) = *pair else { continue };
+        sum += a + b;
+    }
+    sum
+}
+
+fn main() {
+    assert_eq!(radius(&Shape::Circle(3)), Some(3));
+    assert_eq!(radius(&Shape::Square(3)), None);
+    assert_eq!(first_word("hello world"), "hello");
+    assert_eq!(sum_pairs(&[(Some(1), 2), (None, 10), (Some(3), 4)]), 10);
+
+    let Some(x): Option<u8> = Some(7) else { unreachable!() };
+    assert_eq!(x, 7);
+}
@@ -0,0 +1,46 @@
+// run-pass
+// Test that the temporaries of the initializer of a `let...else` live until the
+// end of the enclosing block, like those of the scrutinee of a `match`, and are
+// dropped after the variables declared in the rest of the block.
+
+#![feature(let_else)]
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+struct Noisy<'a> {
+    name: &'static str,
+    log: &'a RefCell<Vec<&'static str>>,
+}
+
+impl Noisy<'_> {
+    fn value(&self) -> Option<u32> {
+        Some(1)
+    }
+}
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push(self.name);
+    }
+}
+
+fn main() {
+    let log = RefCell::new(vec![]);
+    {
+        let Some(x) = (Noisy { name: "init", log: &log }).value() else { return };
+        let _rest = Noisy { name: "rest", log: &log };
+        log.borrow_mut().push("body");
+        assert_eq!(x, 1);
+    }
+    assert_eq!(*log.borrow(), ["body", "rest", "init"]);
+
+    // The guard is only released at the end of the block.
+    let mutex = Mutex::new(Some(2));
+    {
+        let Some(y) = *mutex.lock().unwrap() else { return };
+        assert!(mutex.try_lock().is_err());
+        assert_eq!(y, 2);
+    }
+    assert!(mutex.try_lock().is_ok());
+}
@@ -0,0 +1,19 @@
+// Check that failing to match a `macro_rules!` invocation points at what
+// the rules that got furthest into the input expected instead.
+
+macro_rules! dsl {
+    (add $a:expr, $b:expr) => { $a + $b };
+    (neg $a:expr) => { -$a };
+    (sum [$($x:expr),*]) => { 0 $(+ $x)* };
+}
+
+fn main() {
+    dsl!(mul 1, 2);
+    //~^ ERROR no rules expected the token `mul`
+    dsl!(add 1; 2);
+    //~^ ERROR no rules expected the token `;`
+    dsl!(sum [1 2]);
+    //~^ ERROR no rules expected the token `2`
+    dsl!(neg);
+    //~^ ERROR unexpected end of macro invocation
+}
@@ -0,0 +1,11 @@
+// compile-flags: -Z trace-macros-json
+// check-pass
+
+macro_rules! sum {
+    () => { 0 };
+    ($head:expr $(, $tail:expr)*) => { $head + sum!($($tail),*) };
+}
+
+fn main() {
+    let _ = sum!(1, 2);
+}
@@ -0,0 +1,26 @@
+// compile-flags: -Z print-type-sizes
+// build-pass (FIXME(62277): could be check-pass?)
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+//     FIXME: consider using an attribute instead of side-effects.
+
+// This file illustrates how generators are reported: every state
+// includes the upvars, followed by the locals saved across that
+// state's suspension point.
+
+#![feature(generators, generator_trait, start)]
+
+use std::ops::Generator;
+
+fn generator(array: [u8; 1024]) -> impl Generator<Yield = (), Return = ()> {
+    move || {
+        yield;
+        let _ = array;
+    }
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _ = generator([0; 1024]);
+    0
+}
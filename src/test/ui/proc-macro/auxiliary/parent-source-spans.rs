@@ -1,7 +1,7 @@
 // force-host
 // no-prefer-dynamic
 
-#![feature(proc_macro_diagnostic, proc_macro_span)]
+#![feature(proc_macro_span)]
 #![crate_type = "proc-macro"]
 
 extern crate proc_macro;
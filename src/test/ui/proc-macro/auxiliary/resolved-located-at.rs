@@ -2,7 +2,6 @@
 // no-prefer-dynamic
 
 #![feature(proc_macro_def_site)]
-#![feature(proc_macro_diagnostic)]
 #![feature(proc_macro_quote)]
 #![crate_type = "proc-macro"]
 
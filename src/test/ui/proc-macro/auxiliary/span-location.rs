@@ -0,0 +1,32 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+// Emits a warning describing the location of every token in the input.
+#[proc_macro]
+pub fn describe_location(input: TokenStream) -> TokenStream {
+    for tt in input {
+        let span = tt.span();
+        let (start, end) = (span.start(), span.end());
+        let path = span.source_file().path();
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        span.warning(format!(
+            "`{}` at {}:{}:{} to {}:{}, bytes {:?}",
+            tt,
+            file_name,
+            start.line,
+            start.column,
+            end.line,
+            end.column,
+            span.byte_range(),
+        ))
+        .note("emitted by a proc macro")
+        .emit();
+    }
+    TokenStream::new()
+}
@@ -0,0 +1,25 @@
+// aux-build:expand-expr.rs
+// check-pass
+
+extern crate expand_expr;
+
+use expand_expr::{expand_expr_fail, expand_expr_is};
+
+macro_rules! echo_lit {
+    ($l:literal) => {
+        $l
+    };
+}
+
+expand_expr_is!("Hello, World!", concat!("Hello, ", "World", "!"));
+expand_expr_is!("int10floats5.3booltrue", concat!("int", 10, "floats", 5.3, "bool", true));
+expand_expr_is!("1 + 1", stringify!(1 + 1));
+expand_expr_is!("nested", concat!(stringify!(nested)));
+expand_expr_is!(42u8, echo_lit!(42u8));
+expand_expr_is!(-5, -5);
+
+// Only expressions expanding to literals are supported.
+expand_expr_fail!(1 + 1);
+expand_expr_fail!(echo_lit!("a").len());
+
+fn main() {}
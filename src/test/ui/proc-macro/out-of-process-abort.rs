@@ -0,0 +1,17 @@
+// aux-build:abort-bang.rs
+// aux-build:test-macros.rs
+// compile-flags: -Z proc-macro-out-of-process
+// normalize-stderr-test "exited with .*" -> "exited with STATUS"
+// A proc macro taking down the proc macro server is reported as an error,
+// and the next proc macro is run by a new proc macro server.
+
+#[macro_use]
+extern crate abort_bang;
+#[macro_use]
+extern crate test_macros;
+
+abort!(); //~ ERROR: proc macro server crashed
+
+fn main() {
+    let _: u8 = identity!(1);
+}
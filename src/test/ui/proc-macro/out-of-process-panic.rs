@@ -0,0 +1,16 @@
+// aux-build:test-macros.rs
+// compile-flags: -Z proc-macro-out-of-process
+
+#[macro_use]
+extern crate test_macros;
+
+#[derive(Panic)]
+//~^ ERROR: proc-macro derive panicked
+struct Foo;
+
+#[panic_attr] //~ ERROR: custom attribute panicked
+struct Bar;
+
+panic_bang!(); //~ ERROR: proc macro panicked
+
+fn main() {}
@@ -0,0 +1,19 @@
+// run-pass
+// aux-build:test-macros.rs
+// compile-flags: -Z proc-macro-out-of-process
+// Proc macros run in a separate process still have full access to the compiler.
+
+#[macro_use]
+extern crate test_macros;
+
+#[identity_attr]
+#[derive(Identity, Empty)]
+struct Foo {
+    bar: u8,
+}
+
+fn main() {
+    let foo = identity!(Foo { bar: 1 });
+    assert_eq!(foo.bar, 1);
+    empty!(this is discarded);
+}
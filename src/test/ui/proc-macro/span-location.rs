@@ -0,0 +1,10 @@
+// aux-build:span-location.rs
+// check-pass
+
+extern crate span_location;
+
+span_location::describe_location!(foo "bar");
+//~^ WARNING `foo` at span-location.rs:6:34 to 6:37
+//~| WARNING `"bar"` at span-location.rs:6:38 to 6:43
+
+fn main() {}
@@ -730,6 +730,7 @@ fn desugaring_name(des: hir::MatchSource) -> String {
             contains_else_clause
         ),
         hir::MatchSource::AwaitDesugar => "MatchSource::AwaitDesugar".to_string(),
+        hir::MatchSource::LetElseDesugar => "MatchSource::LetElseDesugar".to_string(),
     }
 }
 
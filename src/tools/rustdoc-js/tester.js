@@ -227,16 +227,17 @@ function loadMainJsAndIndex(mainJs, searchIndex, storageJs, crate) {
     searchIndex = loadContent(fullSearchIndex);
     var finalJS = "";
 
-    var arraysToLoad = ["itemTypes"];
+    var arraysToLoad = ["itemTypes", "STOP_WORDS"];
     var variablesToLoad = ["MAX_LEV_DISTANCE", "MAX_RESULTS", "NO_TYPE_FILTER",
-                           "GENERICS_DATA", "NAME", "INPUTS_DATA", "OUTPUT_DATA",
-                           "TY_PRIMITIVE", "TY_KEYWORD",
-                           "levenshtein_row2"];
+                           "GENERICS_DATA", "NAME", "KIND_DATA", "BOUNDS_DATA", "INPUTS_DATA",
+                           "OUTPUT_DATA", "TY_PRIMITIVE", "TY_KEYWORD", "fullTextIndex",
+                           "crateOffsets", "levenshtein_row2"];
     // execQuery first parameter is built in getQuery (which takes in the search input).
     // execQuery last parameter is built in buildIndex.
     // buildIndex requires the hashmap from search-index.
     var functionsToLoad = ["buildHrefAndPath", "pathSplitter", "levenshtein", "validateResult",
-                           "handleAliases", "getQuery", "buildIndex", "execQuery", "execSearch"];
+                           "handleAliases", "getQuery", "buildIndex", "execQuery", "execSearch",
+                           "findInDocumentation", "findWordInCrate", "splitTopLevel"];
 
     ALIASES = {};
     finalJS += 'window = { "currentCrate": "' + crate + '" };\n';